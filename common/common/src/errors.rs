@@ -1,98 +1,375 @@
 use casper_types::ApiError;
+use std::fmt;
 
-#[repr(u16)]
+/// Which bound of a swap or liquidity removal was not met.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    /// more than the maximum input would have been spent
+    Input,
+    /// less than the minimum output would have been received
+    Output,
+}
+
+/// Errors raised by the router and library, reverted as `ApiError::User(code)`.
+///
+/// Variants that replaced a group of numbered errors keep the first code of the group, the
+/// other codes of the group are still decoded by `Errors::from_code`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Errors {
     /// 65,599 for (UniswapV2 Router Not In Whitelist)
-    UniswapV2RouterNotInWhitelist = 63,
-    /// 65,600 for (UniswapV2 Router Not Owner1)
-    UniswapV2RouterNotOwner1 = 64,
-    /// 65,601 for (UniswapV2 Router Not Owner2)
-    UniswapV2RouterNotOwner2 = 65,
-    /// 65,602 for (UniswapV2 Router Not Owner3)
-    UniswapV2RouterNotOwner3 = 66,
+    NotInWhitelist,
+    /// 65,600 for (UniswapV2 Router Not Owner), formerly also 65,601 and 65,602
+    NotOwner,
     /// 65,603 for (UniswapV2 Router Amount A Desired Is Zero)
-    UniswapV2RouterAmountADesiredIsZero = 67,
+    AmountADesiredIsZero,
     /// 65,604 for (UniswapV2 Router Amount B Desired Is Zero)
-    UniswapV2RouterAmountBDesiredIsZero = 68,
+    AmountBDesiredIsZero,
     /// 65,605 for (UniswapV2 Router Amount Token Is Zero)
-    UniswapV2RouterAmountTokenIsZero = 69,
-    /// 65,606 for (UniswapV2 Router Abort1)
-    UniswapV2RouterAbort1 = 70,
-    /// 65,609 for (UniswapV2 Router Abort2)
-    UniswapV2RouterAbort2 = 71,
-    /// 65,610 for (UniswapV2 Router Abort3)
-    UniswapV2RouterAbort3 = 72,
-    /// 65,611 for (UniswapV2 Router Abort4)
-    UniswapV2RouterAbort4 = 73,
-    /// 65,612 for (UniswapV2 Router Abort5)
-    UniswapV2RouterAbort5 = 74,
-    /// 65,613 for (UniswapV2 Router Abort6)
-    UniswapV2RouterAbort6 = 75,
-    /// 65,614 for (UniswapV2 Router Abort7)
-    UniswapV2RouterAbort7 = 76,
-    /// 65,615 for (UniswapV2 Router Abort8)
-    UniswapV2RouterAbort8 = 77,
-    /// 65,616 for (UniswapV2 Router Abort9)
-    UniswapV2RouterAbort9 = 78,
-    /// 65,617 for (UniswapV2 Router Abort10)
-    UniswapV2RouterAbort10 = 79,
-    /// 65,617 for (UniswapV2 Router Abort11)
-    UniswapV2RouterAbort11 = 80,
-    /// 65,640 for (UniswapV2 Router Zero Addr)
-    UniswapV2RouterZeroAddr = 81,
-    /// 65,641 for (UniswapV2 Router Invalid Arguments)
-    UniswapV2RouterInvalidArguments = 82,
-    /// 65,642 for (UniswapV2 Router Timed Out1)
-    UniswapV2RouterTimedOut1 = 83,
-    /// 65,644 for (UniswapV2 Router Timed Out2)
-    UniswapV2RouterTimedOut2 = 84,
-    /// 65,646 for (UniswapV2 Router Timed Out3)
-    UniswapV2RouterTimedOut3 = 85,
-    /// 65,648 for (UniswapV2 Router Timed Out4)
-    UniswapV2RouterTimedOut4 = 86,
-    /// 65,650 for (UniswapV2 Router Timed Out5)
-    UniswapV2RouterTimedOut5 = 87,
-    /// 65,652 for (UniswapV2 Router Timed Out6)
-    UniswapV2RouterTimedOut6 = 88,
-    /// 65,654 for (UniswapV2 Router Timed Out7)
-    UniswapV2RouterTimedOut7 = 89,
-    /// 65,656 for (UniswapV2 Router Timed Out8)
-    UniswapV2RouterTimedOut8 = 90,
-    /// 65,658 for (UniswapV2 Router Timed Out9)
-    UniswapV2RouterTimedOut9 = 91,
-    /// 65,660 for (UniswapV2 Router Timed Out10)
-    UniswapV2RouterTimedOut10 = 92,
+    AmountTokenIsZero,
+    /// 65,606 for (UniswapV2 Router Slippage Exceeded on the output), formerly also 65,607,
+    /// 65,610 and 65,614
+    /// 65,608 for (UniswapV2 Router Slippage Exceeded on the input), formerly also 65,612 and
+    /// 65,616
+    SlippageExceeded { side: Side },
+    /// 65,609 for (UniswapV2 Router Path Must Start With Wcspr), formerly also 65,615
+    PathMustStartWithWcspr,
+    /// 65,611 for (UniswapV2 Router Path Must End With Wcspr), formerly also 65,613
+    PathMustEndWithWcspr,
+    /// 65,617 for (UniswapV2 Router Pair Not Found)
+    PairNotFound,
+    /// 65,618 for (UniswapV2 Router Optimal Amount Exceeds Desired)
+    OptimalAmountExceedsDesired,
+    /// 65,619 for (UniswapV2 Router Timed Out), formerly also 65,620 to 65,628, the reason is
+    /// given by `check_deadline`
+    TimedOut,
+
+    /// 65,629 for (UniswapV2 Library Arithmetic Overflow), formerly also 65,630 and 65,631
+    ArithmeticOverflow,
+    /// 65,632 for (UniswapV2 Library Zero Address)
+    ZeroAddress,
+    /// 65,633 for (UniswapV2 Library Identical Addresses)
+    IdenticalAddresses,
+    /// 65,634 for (UniswapV2 Library Insufficient Amount)
+    InsufficientAmount,
+    /// 65,635 for (UniswapV2 Library Insufficient Input Amount)
+    InsufficientInputAmount,
+    /// 65,636 for (UniswapV2 Library Insufficient Output Amount)
+    InsufficientOutputAmount,
+    /// 65,637 for (UniswapV2 Library Invalid Path), formerly also 65,641
+    InvalidPath,
+    /// 65,638 for (UniswapV2 Library Insufficient Liquidity), formerly also 65,639 and 65,640
+    InsufficientLiquidity,
+
+    /// 65,642 for (UniswapV2 Router Order Not Found)
+    OrderNotFound,
+    /// 65,643 for (UniswapV2 Router Order Not Open)
+    OrderNotOpen,
+    /// 65,644 for (UniswapV2 Router Order Expired)
+    OrderExpired,
+    /// 65,645 for (UniswapV2 Router Order Limit Not Reached)
+    OrderLimitNotReached,
+    /// 65,646 for (UniswapV2 Router Not Order Owner)
+    NotOrderOwner,
+    /// 65,647 for (UniswapV2 Router Invalid Order)
+    InvalidOrder,
+    /// 65,648 for (UniswapV2 Router Order Escrow Overflow)
+    OrderEscrowOverflow,
+
+    /// 65,649 for (UniswapV2 Library Oracle Invalid Granularity)
+    OracleInvalidGranularity,
+    /// 65,650 for (UniswapV2 Library Oracle Missing Historical Observation)
+    OracleMissingHistoricalObservation,
+    /// 65,651 for (UniswapV2 Library Oracle Unexpected Time Elapsed)
+    OracleUnexpectedTimeElapsed,
+    /// 65,652 for (UniswapV2 Library Oracle No Reserves)
+    OracleNoReserves,
+    /// 65,653 for (UniswapV2 Library Oracle Multiplication Overflow)
+    OracleMultiplicationOverflow,
+
+    /// 65,654 for (UniswapV2 Router Dca Order Not Found)
+    DcaOrderNotFound,
+    /// 65,655 for (UniswapV2 Router Dca Order Not Active)
+    DcaOrderNotActive,
+    /// 65,656 for (UniswapV2 Router Dca Order Not Paused)
+    DcaOrderNotPaused,
+    /// 65,657 for (UniswapV2 Router Dca Order Not Due)
+    DcaOrderNotDue,
+    /// 65,658 for (UniswapV2 Router Dca Budget Exhausted)
+    DcaBudgetExhausted,
+    /// 65,659 for (UniswapV2 Router Dca Insufficient Output Amount)
+    DcaInsufficientOutputAmount,
+    /// 65,660 for (UniswapV2 Router Not Dca Order Owner)
+    NotDcaOrderOwner,
+    /// 65,661 for (UniswapV2 Router Invalid Dca Order)
+    InvalidDcaOrder,
+    /// 65,662 for (UniswapV2 Router Invalid Recipient)
+    InvalidRecipient,
+    /// 65,663 for (UniswapV2 Router Operation Timelocked)
+    OperationTimelocked,
+    /// 65,664 for (UniswapV2 Router Operation Not Found)
+    OperationNotFound,
+    /// 65,665 for (UniswapV2 Router Operation Not Queued)
+    OperationNotQueued,
+    /// 65,666 for (UniswapV2 Router Operation Eta Too Early)
+    OperationEtaTooEarly,
+    /// 65,667 for (UniswapV2 Router Operation Stale)
+    OperationStale,
+    /// 65,668 for (UniswapV2 Router Invalid Operation)
+    InvalidOperation,
+    /// 65,669 for (UniswapV2 Router Paused)
+    Paused,
+    /// 65,670 for (UniswapV2 Router Rescue Exceeds Free Balance)
+    RescueExceedsFreeBalance,
+    /// 65,671 for (UniswapV2 Router Receive Not From Wcspr)
+    ReceiveNotFromWcspr,
+    /// 65,672 for (UniswapV2 Router Unexpected Cspr)
+    UnexpectedCspr,
+    /// 65,673 for (UniswapV2 Router Storage Version Unsupported)
+    StorageVersionUnsupported,
+    /// 65,674 for (UniswapV2 Router Deployment Reference Mismatch)
+    DeploymentReferenceMismatch,
+    /// 65,675 for (UniswapV2 Router Insufficient Cspr)
+    InsufficientCspr,
+    /// 65,676 for (UniswapV2 Router Wcspr Held In Escrow)
+    WcsprHeldInEscrow,
+    /// 65,677 for (UniswapV2 Router Operation Eta Out Of Range)
+    OperationEtaOutOfRange,
+    /// 65,678 for (UniswapV2 Library Oracle Factory Not Set)
+    OracleFactoryNotSet,
+}
+
+impl Errors {
+    /// User error code the error is reverted with.
+    pub fn code(&self) -> u16 {
+        match self {
+            Errors::NotInWhitelist => 63,
+            Errors::NotOwner => 64,
+            Errors::AmountADesiredIsZero => 67,
+            Errors::AmountBDesiredIsZero => 68,
+            Errors::AmountTokenIsZero => 69,
+            Errors::SlippageExceeded { side: Side::Output } => 70,
+            Errors::SlippageExceeded { side: Side::Input } => 72,
+            Errors::PathMustStartWithWcspr => 73,
+            Errors::PathMustEndWithWcspr => 75,
+            Errors::PairNotFound => 81,
+            Errors::OptimalAmountExceedsDesired => 82,
+            Errors::TimedOut => 83,
+            Errors::ArithmeticOverflow => 93,
+            Errors::ZeroAddress => 96,
+            Errors::IdenticalAddresses => 97,
+            Errors::InsufficientAmount => 98,
+            Errors::InsufficientInputAmount => 99,
+            Errors::InsufficientOutputAmount => 100,
+            Errors::InvalidPath => 101,
+            Errors::InsufficientLiquidity => 102,
+            Errors::OrderNotFound => 106,
+            Errors::OrderNotOpen => 107,
+            Errors::OrderExpired => 108,
+            Errors::OrderLimitNotReached => 109,
+            Errors::NotOrderOwner => 110,
+            Errors::InvalidOrder => 111,
+            Errors::OrderEscrowOverflow => 112,
+            Errors::OracleInvalidGranularity => 113,
+            Errors::OracleMissingHistoricalObservation => 114,
+            Errors::OracleUnexpectedTimeElapsed => 115,
+            Errors::OracleNoReserves => 116,
+            Errors::OracleMultiplicationOverflow => 117,
+            Errors::DcaOrderNotFound => 118,
+            Errors::DcaOrderNotActive => 119,
+            Errors::DcaOrderNotPaused => 120,
+            Errors::DcaOrderNotDue => 121,
+            Errors::DcaBudgetExhausted => 122,
+            Errors::DcaInsufficientOutputAmount => 123,
+            Errors::NotDcaOrderOwner => 124,
+            Errors::InvalidDcaOrder => 125,
+            Errors::InvalidRecipient => 126,
+            Errors::OperationTimelocked => 127,
+            Errors::OperationNotFound => 128,
+            Errors::OperationNotQueued => 129,
+            Errors::OperationEtaTooEarly => 130,
+            Errors::OperationStale => 131,
+            Errors::InvalidOperation => 132,
+            Errors::Paused => 133,
+            Errors::RescueExceedsFreeBalance => 134,
+            Errors::ReceiveNotFromWcspr => 135,
+            Errors::UnexpectedCspr => 136,
+            Errors::StorageVersionUnsupported => 137,
+            Errors::DeploymentReferenceMismatch => 138,
+            Errors::InsufficientCspr => 139,
+            Errors::WcsprHeldInEscrow => 140,
+            Errors::OperationEtaOutOfRange => 141,
+            Errors::OracleFactoryNotSet => 142,
+        }
+    }
 
-    /// 65,662 for (UniswapV2 Library Multiplication Overflow 1)
-    MultiplicationOverflow1 = 93,
-    /// 65,663 for (UniswapV2 Library Addition Overflow 1)
-    AdditionOverflow1 = 94,
-    /// 65,664 for (UniswapV2 Library Addition Overflow 2)
-    AdditionOverflow2 = 95,
-    /// 65,666 for (UniswapV2 Library Zero Address)
-    ZeroAddress = 96,
-    /// 65,667 for (UniswapV2 Library Identical Addresses)
-    IdenticalAddresses = 97,
-    /// 65,668 for (UniswapV2 Library Insufficient Amount)
-    InsufficientAmount = 98,
-    /// 65,669 for (UniswapV2 Library Insufficient Input Amount)
-    InsufficientInputAmount = 99,
-    /// 65,670 for (UniswapV2 Library Insufficient Output Amount)
-    InsufficientOutputAmount = 100,
-    /// 65,671 for (UniswapV2 Library Invalid Path)
-    InvalidPath = 101,
-    /// 65,672 for (UniswapV2 Library Insufficient Liquidity1)
-    InsufficientLiquidity1 = 102,
-    /// 65,672 for (UniswapV2 Library Insufficient Liquidity2)
-    InsufficientLiquidity2 = 103,
-    /// 65,672 for (UniswapV2 Library Insufficient Liquidity3)
-    InsufficientLiquidity3 = 104,
-    /// 65,672 for (UniswapV2 Library Insufficient Liquidity4)
-    InsufficientLiquidity4 = 105,
+    /// Decodes a user error code, including the codes of the numbered errors that were merged.
+    pub fn from_code(code: u16) -> Option<Errors> {
+        let error = match code {
+            63 => Errors::NotInWhitelist,
+            64..=66 => Errors::NotOwner,
+            67 => Errors::AmountADesiredIsZero,
+            68 => Errors::AmountBDesiredIsZero,
+            69 => Errors::AmountTokenIsZero,
+            70 | 71 | 74 | 78 => Errors::SlippageExceeded { side: Side::Output },
+            72 | 76 | 80 => Errors::SlippageExceeded { side: Side::Input },
+            73 | 79 => Errors::PathMustStartWithWcspr,
+            75 | 77 => Errors::PathMustEndWithWcspr,
+            81 => Errors::PairNotFound,
+            82 => Errors::OptimalAmountExceedsDesired,
+            83..=92 => Errors::TimedOut,
+            93..=95 => Errors::ArithmeticOverflow,
+            96 => Errors::ZeroAddress,
+            97 => Errors::IdenticalAddresses,
+            98 => Errors::InsufficientAmount,
+            99 => Errors::InsufficientInputAmount,
+            100 => Errors::InsufficientOutputAmount,
+            101 | 105 => Errors::InvalidPath,
+            102..=104 => Errors::InsufficientLiquidity,
+            106 => Errors::OrderNotFound,
+            107 => Errors::OrderNotOpen,
+            108 => Errors::OrderExpired,
+            109 => Errors::OrderLimitNotReached,
+            110 => Errors::NotOrderOwner,
+            111 => Errors::InvalidOrder,
+            112 => Errors::OrderEscrowOverflow,
+            113 => Errors::OracleInvalidGranularity,
+            114 => Errors::OracleMissingHistoricalObservation,
+            115 => Errors::OracleUnexpectedTimeElapsed,
+            116 => Errors::OracleNoReserves,
+            117 => Errors::OracleMultiplicationOverflow,
+            118 => Errors::DcaOrderNotFound,
+            119 => Errors::DcaOrderNotActive,
+            120 => Errors::DcaOrderNotPaused,
+            121 => Errors::DcaOrderNotDue,
+            122 => Errors::DcaBudgetExhausted,
+            123 => Errors::DcaInsufficientOutputAmount,
+            124 => Errors::NotDcaOrderOwner,
+            125 => Errors::InvalidDcaOrder,
+            126 => Errors::InvalidRecipient,
+            127 => Errors::OperationTimelocked,
+            128 => Errors::OperationNotFound,
+            129 => Errors::OperationNotQueued,
+            130 => Errors::OperationEtaTooEarly,
+            131 => Errors::OperationStale,
+            132 => Errors::InvalidOperation,
+            133 => Errors::Paused,
+            134 => Errors::RescueExceedsFreeBalance,
+            135 => Errors::ReceiveNotFromWcspr,
+            136 => Errors::UnexpectedCspr,
+            137 => Errors::StorageVersionUnsupported,
+            138 => Errors::DeploymentReferenceMismatch,
+            139 => Errors::InsufficientCspr,
+            140 => Errors::WcsprHeldInEscrow,
+            141 => Errors::OperationEtaOutOfRange,
+            142 => Errors::OracleFactoryNotSet,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    /// Human readable description of a user error code, for clients that only see the code of
+    /// a failed deploy.
+    pub fn from_api_error(code: u16) -> String {
+        match Errors::from_code(code) {
+            Some(error) => error.to_string(),
+            None => format!("unknown error code {}", code),
+        }
+    }
+
+    /// What went wrong, in the words shown to users next to the code.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Errors::NotInWhitelist => {
+                "caller is not whitelisted to create pairs through the router"
+            }
+            Errors::NotOwner => "caller is not the owner of the router",
+            Errors::AmountADesiredIsZero => "amount_a_desired is zero",
+            Errors::AmountBDesiredIsZero => "amount_b_desired is zero",
+            Errors::AmountTokenIsZero => "token amount to add is zero",
+            Errors::SlippageExceeded { side: Side::Output } => {
+                "output amount is below the requested minimum"
+            }
+            Errors::SlippageExceeded { side: Side::Input } => {
+                "input amount is above the requested maximum"
+            }
+            Errors::PathMustStartWithWcspr => "path must start with wcspr",
+            Errors::PathMustEndWithWcspr => "path must end with wcspr",
+            Errors::PairNotFound => "pair does not exist and none was given",
+            Errors::OptimalAmountExceedsDesired => {
+                "optimal amount of token a exceeds amount_a_desired"
+            }
+            Errors::TimedOut => {
+                "deadline is invalid or has passed, deadlines are u64 block times in milliseconds"
+            }
+            Errors::ArithmeticOverflow => "arithmetic overflow",
+            Errors::ZeroAddress => "token is the zero address",
+            Errors::IdenticalAddresses => "tokens are identical",
+            Errors::InsufficientAmount => "amount is zero",
+            Errors::InsufficientInputAmount => "input amount is zero",
+            Errors::InsufficientOutputAmount => "output amount is zero",
+            Errors::InvalidPath => "path must contain at least two tokens, given as formatted keys",
+            Errors::InsufficientLiquidity => "pair has no liquidity",
+            Errors::OrderNotFound => "limit order does not exist",
+            Errors::OrderNotOpen => "limit order is not open",
+            Errors::OrderExpired => "limit order has expired",
+            Errors::OrderLimitNotReached => "limit order price not reached",
+            Errors::NotOrderOwner => "caller is not the owner of the limit order",
+            Errors::InvalidOrder => "limit order is invalid",
+            Errors::OrderEscrowOverflow => "amount_in + bounty overflows",
+            Errors::OracleInvalidGranularity => {
+                "granularity must be above 1 and divide the window size"
+            }
+            Errors::OracleMissingHistoricalObservation => {
+                "no oracle observation within the window, update the pair first"
+            }
+            Errors::OracleUnexpectedTimeElapsed => "oracle observation is too recent",
+            Errors::OracleNoReserves => "pair has no reserves",
+            Errors::OracleMultiplicationOverflow => "oracle amount overflows",
+            Errors::DcaOrderNotFound => "dca order does not exist",
+            Errors::DcaOrderNotActive => "dca order is not active",
+            Errors::DcaOrderNotPaused => "dca order is not paused",
+            Errors::DcaOrderNotDue => "dca order interval has not passed yet",
+            Errors::DcaBudgetExhausted => "dca order budget is exhausted",
+            Errors::DcaInsufficientOutputAmount => "dca execution output is below amount_out_min",
+            Errors::NotDcaOrderOwner => "caller is not the owner of the dca order",
+            Errors::InvalidDcaOrder => "dca order is invalid",
+            Errors::InvalidRecipient => {
+                "recipient must be an account, a contract package or, for cspr, a purse"
+            }
+            Errors::OperationTimelocked => "timelock operation eta has not passed",
+            Errors::OperationNotFound => "timelock operation does not exist",
+            Errors::OperationNotQueued => "timelock operation is not queued",
+            Errors::OperationEtaTooEarly => "timelock operation eta is before the timelock delay",
+            Errors::OperationStale => "timelock operation was not executed within the grace period",
+            Errors::InvalidOperation => "timelock operation action is unknown",
+            Errors::Paused => "router is paused",
+            Errors::RescueExceedsFreeBalance => {
+                "rescue amount exceeds the router balance not held in escrow"
+            }
+            Errors::ReceiveNotFromWcspr => "cspr can only be received from wcspr",
+            Errors::UnexpectedCspr => "received more cspr than the pending wcspr withdraw",
+            Errors::StorageVersionUnsupported => "storage was written by a newer router version",
+            Errors::DeploymentReferenceMismatch => {
+                "router was installed with other factory, wcspr or library references"
+            }
+            Errors::InsufficientCspr => "caller purse can't pay the cspr the call spends",
+            Errors::WcsprHeldInEscrow => "wcspr can't change while open orders hold it in escrow",
+            Errors::OperationEtaOutOfRange => {
+                "timelock operation eta or its grace period overflows the block time"
+            }
+            Errors::OracleFactoryNotSet => "library was installed without an oracle factory",
+        }
+    }
+}
+
+impl fmt::Display for Errors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.description(), self.code())
+    }
 }
 
 impl From<Errors> for ApiError {
     fn from(error: Errors) -> ApiError {
-        ApiError::User(error as u16)
+        ApiError::User(error.code())
     }
 }
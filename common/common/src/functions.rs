@@ -1,6 +1,6 @@
 use crate::keys::*;
-use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
-use casper_types::{ApiError, Key, URef, U256, U512};
+use casper_contract::{contract_api::runtime, ext_ffi, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{api_error, bytesrepr::FromBytes, ApiError, CLTyped, Key, URef, U256, U512};
 use num_traits::AsPrimitive;
 
 pub fn zero_address() -> Key {
@@ -19,6 +19,23 @@ pub fn block_timestamp() -> u64 {
     runtime::get_blocktime().into()
 }
 
+// Named argument that callers are allowed to leave out.
+pub fn get_optional_named_arg<T: CLTyped + FromBytes>(name: &str) -> Option<T> {
+    let mut arg_size: usize = 0;
+    let ret: i32 = unsafe {
+        ext_ffi::casper_get_named_arg_size(
+            name.as_bytes().as_ptr(),
+            name.len(),
+            &mut arg_size as *mut usize,
+        )
+    };
+    match api_error::result_from(ret) {
+        Ok(()) => Some(runtime::get_named_arg(name)),
+        Err(ApiError::MissingArgument) => None,
+        Err(e) => runtime::revert(e),
+    }
+}
+
 pub fn u256_to_u512(u256: U256) -> U512 {
     <casper_types::U256 as AsPrimitive<casper_types::U512>>::as_(u256)
 }
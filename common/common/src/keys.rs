@@ -12,14 +12,67 @@ pub const DESTINATION_SWAP_EXACT_CSPR_FOR_TOKENS: &str = "swap_exact_cspr_for_to
 pub const DESTINATION_SWAP_CSPR_FOR_EXACT_TOKENS: &str = "swap_cspr_for_exact_tokens";
 pub const DESTINATION_SWAP_TOKENS_FOR_EXACT_CSPR: &str = "swap_tokens_for_exact_cspr";
 pub const DESTINATION_SWAP_EXACT_TOKENS_FOR_CSPR: &str = "swap_exact_tokens_for_cspr";
+pub const DESTINATION_CREATE_LIMIT_ORDER: &str = "create_limit_order";
+pub const DESTINATION_CREATE_LIMIT_ORDER_CSPR: &str = "create_limit_order_cspr";
+pub const DESTINATION_FILL_ORDER: &str = "fill_order";
+pub const DESTINATION_CREATE_DCA_ORDER: &str = "create_dca_order";
+pub const DESTINATION_CREATE_DCA_ORDER_CSPR: &str = "create_dca_order_cspr";
+pub const DESTINATION_EXECUTE_DCA_ORDER: &str = "execute_dca_order";
+pub const DESTINATION_CONSULT: &str = "consult";
+pub const DESTINATION_CHECK_DEADLINE: &str = "check_deadline";
+pub const DESTINATION_QUOTE: &str = "quote";
+pub const DESTINATION_GET_AMOUNTS_OUT: &str = "get_amounts_out";
+pub const DESTINATION_GET_AMOUNTS_IN: &str = "get_amounts_in";
+pub const DESTINATION_QUEUE_OPERATION: &str = "queue_operation";
+pub const DESTINATION_GET_OPERATION: &str = "get_operation";
+pub const DESTINATION_GET_STORAGE_VERSION: &str = "get_storage_version";
+pub const DESTINATION_RESCUE_CSPR: &str = "rescue_cspr";
+pub const DESTINATION_SCRIPT: &str = "script";
+// `approve_and_<destination>` approves the router for the call's input and revokes it afterwards
+pub const DESTINATION_APPROVE_PREFIX: &str = "approve_and_";
+pub const APPROVABLE_DESTINATIONS: [&str; 10] = [
+    DESTINATION_ADD_LIQUIDITY,
+    DESTINATION_ADD_LIQUIDITY_CSPR,
+    DESTINATION_REMOVE_LIQUIDITY,
+    DESTINATION_REMOVE_LIQUIDITY_CSPR,
+    DESTINATION_SWAP_EXACT_TOKENS_FOR_TOKENS,
+    DESTINATION_SWAP_TOKENS_FOR_EXACT_TOKENS,
+    DESTINATION_SWAP_EXACT_TOKENS_FOR_CSPR,
+    DESTINATION_SWAP_TOKENS_FOR_EXACT_CSPR,
+    DESTINATION_CREATE_LIMIT_ORDER,
+    DESTINATION_CREATE_DCA_ORDER,
+];
 pub const AMOUNT_RUNTIME_ARG: &str = "amount";
+pub const SESSION_RUN_ID: &str = "session_run_id";
+pub const SESSION_RESULT: &str = "session_result";
+pub const SESSION_RESULTS: &str = "session_results";
 
 // router
 pub const OWNER: &str = "owner";
 pub const WHITELIST: &str = "whitelist";
+pub const PAIR_CACHE: &str = "pair_cache";
+pub const TIMELOCK_OPERATIONS: &str = "timelock_operations";
+pub const TIMELOCK_DELAY: &str = "timelock_delay";
+pub const PAUSED: &str = "paused";
+pub const ESCROW_BALANCES: &str = "escrow_balances";
+pub const PENDING_CSPR: &str = "pending_cspr";
+pub const STORAGE_VERSION: &str = "storage_version";
+pub const ENABLED_VERSIONS: &str = "enabled_versions";
 pub const WCSPR: &str = "wcspr";
 pub const FACTORY: &str = "factory";
 pub const LIBRARY_HASH: &str = "library_hash";
+pub const USE_LIBRARY_CONTRACT: &str = "use_library_contract";
+pub const LIMIT_ORDERS: &str = "limit_orders";
+pub const LIMIT_ORDER_COUNT: &str = "limit_order_count";
+pub const DCA_ORDERS: &str = "dca_orders";
+pub const DCA_ORDER_COUNT: &str = "dca_order_count";
+
+// library oracle
+pub const ORACLE_FACTORY: &str = "oracle_factory";
+pub const WINDOW_SIZE: &str = "window_size";
+pub const GRANULARITY: &str = "granularity";
+pub const PERIOD_SIZE: &str = "period_size";
+pub const OBSERVATIONS: &str = "observations";
 
 // FACTORY Contract Methods
 pub const FACTORY_GET_PAIR: &str = "get_pair";
@@ -41,9 +94,15 @@ pub const PAIR_MINT: &str = "mint";
 pub const PAIR_TRANSFER_FROM: &str = "transfer_from";
 pub const PAIR_BURN: &str = "burn";
 pub const PAIR_SWAP: &str = "swap";
+pub const PAIR_GET_RESERVES: &str = "get_reserves";
+pub const PAIR_PRICE0_CUMULATIVE_LAST: &str = "price0_cumulative_last";
+pub const PAIR_PRICE1_CUMULATIVE_LAST: &str = "price1_cumulative_last";
 
 // IWETH Contract methods
 pub const WCSPR_DEPOSIT: &str = "deposit";
 pub const WCSPR_TRANSFER: &str = "transfer";
 pub const WCSPR_TRANSFER_FROM: &str = "transfer_from";
 pub const WCSPR_WITHDRAW: &str = "withdraw";
+
+// Recipient Contract methods
+pub const RECIPIENT_RECEIVE: &str = "receive";
@@ -1,6 +1,8 @@
 pub mod errors;
 pub mod functions;
 pub mod keys;
+pub mod session;
+pub mod timelock;
 
 pub use casper_contract::*;
 pub use casper_types::*;
@@ -0,0 +1,49 @@
+use casper_types::{RuntimeArgs, U256};
+use casper_types_derive::{CLTyped, FromBytes, ToBytes};
+use std::collections::BTreeMap;
+
+// Result of one session code router run. Runs are numbered per account starting at 1, the last
+// result is kept under `session_result` and every result in the `session_results` dictionary
+// under its run id.
+#[derive(Clone, Debug, PartialEq, CLTyped, ToBytes, FromBytes)]
+pub struct SessionResult {
+    pub run_id: u64,
+    pub destination: String,
+    pub block_time: u64,
+    // Named outputs of the destination, e.g. `amount_a`, `amount_b` and `liquidity` for add_liquidity.
+    pub values: BTreeMap<String, U256>,
+    // Amounts along the path for swaps, order fills and path quotes, empty otherwise.
+    pub amounts: Vec<U256>,
+    pub operation_hash: Option<String>,
+}
+
+impl SessionResult {
+    pub fn value(&self, name: &str) -> U256 {
+        self.values.get(name).copied().unwrap_or_default()
+    }
+}
+
+// One router call of a script. `args` are the destination's session args without `entrypoint`
+// and `package_hash`, which the script shares.
+#[derive(Clone, Debug, PartialEq, ToBytes, FromBytes)]
+pub struct ScriptStep {
+    pub destination: String,
+    pub args: RuntimeArgs,
+    pub inputs: Vec<StepInput>,
+}
+
+// Types a step input can be passed as. Named values are U256, a U512 input is widened and a u64
+// input reverts `InvalidArgument` when the value does not fit.
+pub const INPUT_U256: u8 = 0;
+pub const INPUT_U512: u8 = 1;
+pub const INPUT_U64: u8 = 2;
+
+// Session arg `arg` of a step taken from the named value `value` of the earlier step `step`,
+// counted from 0, as the type `input_type`. `amount_out` is the last amount of a swap.
+#[derive(Clone, Debug, PartialEq, ToBytes, FromBytes)]
+pub struct StepInput {
+    pub arg: String,
+    pub step: u32,
+    pub value: String,
+    pub input_type: u8,
+}
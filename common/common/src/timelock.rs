@@ -0,0 +1,18 @@
+use casper_types::Key;
+use casper_types_derive::{CLTyped, FromBytes, ToBytes};
+
+pub const OPERATION_QUEUED: u8 = 0;
+pub const OPERATION_EXECUTED: u8 = 1;
+pub const OPERATION_CANCELLED: u8 = 2;
+
+// Privileged call waiting in the router's timelock, returned by its `get_operation` so session
+// code can read it too. `target` is the key argument of the action and `value` its number
+// argument, whichever the action does not take is ignored.
+#[derive(Clone, Debug, PartialEq, CLTyped, ToBytes, FromBytes)]
+pub struct TimelockOperation {
+    pub action: String,
+    pub target: Key,
+    pub value: u64,
+    pub eta: u64,
+    pub status: u8,
+}
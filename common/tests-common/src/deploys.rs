@@ -1,3 +1,4 @@
+use crate::helpers::*;
 use casperlabs_test_env::{TestContract, TestEnv};
 use common::{account::AccountHash, *};
 
@@ -130,13 +131,24 @@ pub fn deploy_pair(
     )
 }
 
-pub fn deploy_library(env: &TestEnv, owner: AccountHash, time: u64) -> TestContract {
+pub fn deploy_library(
+    env: &TestEnv,
+    owner: AccountHash,
+    factory: Key,
+    window_size: u64,
+    granularity: u8,
+    time: u64,
+) -> TestContract {
     TestContract::new(
         env,
         "uniswap-v2-library.wasm",
         "library",
         owner,
-        runtime_args! {},
+        runtime_args! {
+            "factory" => factory,
+            "window_size" => window_size,
+            "granularity" => granularity
+        },
         time,
     )
 }
@@ -163,6 +175,73 @@ pub fn deploy_router(
     )
 }
 
+// Adds a new contract version to the router package installed by deploy_router.
+pub fn upgrade_router(
+    env: &TestEnv,
+    owner: AccountHash,
+    disable_previous_versions: bool,
+    time: u64,
+) -> TestContract {
+    TestContract::new(
+        env,
+        "uniswap-v2-router.wasm",
+        "Uniswap Router",
+        owner,
+        runtime_args! {
+            "disable_previous_versions" => disable_previous_versions
+        },
+        time,
+    )
+}
+
+// Router built from the release before storage was versioned, see `build-contract-uniswap-v2-router-v1`
+// in the Makefile. It is installed under the name upgrade_router adds versions to.
+pub fn deploy_router_v1(
+    env: &TestEnv,
+    owner: AccountHash,
+    factory: Key,
+    wcspr: Key,
+    library: Key,
+    time: u64,
+) -> TestContract {
+    TestContract::new(
+        env,
+        "uniswap-v2-router-v1.wasm",
+        "Uniswap Router",
+        owner,
+        runtime_args! {
+            "factory" => factory,
+            "wcspr" => wcspr,
+            "library" => library
+        },
+        time,
+    )
+}
+
+// Router that prices through the library contract instead of its linked library math.
+pub fn deploy_router_using_library_contract(
+    env: &TestEnv,
+    owner: AccountHash,
+    factory: Key,
+    wcspr: Key,
+    library: Key,
+    time: u64,
+) -> TestContract {
+    TestContract::new(
+        env,
+        "uniswap-v2-router.wasm",
+        "Uniswap Router Library Contract",
+        owner,
+        runtime_args! {
+            "factory" => factory,
+            "wcspr" => wcspr,
+            "library" => library,
+            "use_library_contract" => true
+        },
+        time,
+    )
+}
+
 pub fn deploy_dummy_tokens(
     env: &TestEnv,
     owner: Option<AccountHash>,
@@ -205,3 +284,56 @@ pub fn deploy_dummy_tokens(
     );
     (token1_contract, token2_contract, token3_contract)
 }
+
+// Installs the library and the router, then runs register-deployment which registers the router
+// on the factory and records the hashes under `uniswap_v2_*` named keys of the owner.
+pub fn deploy_and_register(
+    env: &TestEnv,
+    owner: AccountHash,
+    factory: Key,
+    wcspr: Key,
+    time: u64,
+) -> (TestContract, TestContract) {
+    let library = deploy_library(
+        env,
+        owner,
+        factory,
+        ORACLE_WINDOW_SIZE,
+        ORACLE_GRANULARITY,
+        time,
+    );
+    let router = deploy_router(
+        env,
+        owner,
+        factory,
+        wcspr,
+        Key::Hash(library.package_hash()),
+        time,
+    );
+    call(
+        env,
+        owner,
+        REGISTER_DEPLOYMENT,
+        runtime_args! {
+            "deployment_name" => "uniswap_v2",
+            "library_name" => "library",
+            "router_name" => "Uniswap Router",
+            "factory" => factory,
+            "wcspr" => wcspr
+        },
+        time,
+    );
+    (library, router)
+}
+
+// Test contract that accepts cspr through `receive`, see cspr-receiver/bin/main.rs.
+pub fn deploy_cspr_receiver(env: &TestEnv, owner: AccountHash, time: u64) -> TestContract {
+    TestContract::new(
+        env,
+        "cspr-receiver.wasm",
+        "cspr_receiver",
+        owner,
+        runtime_args! {},
+        time,
+    )
+}
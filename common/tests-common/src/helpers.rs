@@ -6,12 +6,16 @@ use casperlabs_test_env::{TestContract, TestEnv};
 use common::{
     account::AccountHash,
     bytesrepr::{FromBytes, ToBytes},
-    runtime_args, Address, CLTyped, Key, RuntimeArgs, U256, U512,
+    keys::SESSION_RESULT,
+    runtime_args,
+    session::SessionResult,
+    Address, CLTyped, Key, RuntimeArgs, U256, U512,
 };
 
 pub const BALANCES: &str = "balances";
 pub const TREASURY_FEE: &str = "treasury_fee";
 pub const SESSION_CODE_ROUTER: &str = "session-code-router.wasm";
+pub const REGISTER_DEPLOYMENT: &str = "register-deployment.wasm";
 
 pub const NAME: &str = "ERC20";
 pub const SYMBOL: &str = "ERC";
@@ -20,6 +24,11 @@ pub const INIT_TOTAL_SUPPLY: U256 = U256([0, 0, 0, 0]);
 pub const AMOUNT: U256 = U256([100_000_000_000, 0, 0, 0]);
 pub const AMOUNT_U512: U512 = U512([100_000_000_000, 0, 0, 0, 0, 0, 0, 0]);
 pub const WRAPPED_CSPR: &str = "Wrapped CSPR";
+// 24 hour window observed once an hour, in milliseconds
+pub const ORACLE_WINDOW_SIZE: u64 = 24 * 60 * 60 * 1000;
+pub const ORACLE_GRANULARITY: u8 = 24;
+// timelock delay of a new router, in milliseconds
+pub const TIMELOCK_DELAY: u64 = 2 * 24 * 60 * 60 * 1000;
 
 pub fn address_to_str(owner: &Address) -> String {
     let preimage = owner.to_bytes().unwrap();
@@ -43,6 +52,18 @@ pub fn keys_to_str(key_a: &U256, key_b: &Key) -> String {
     hex::encode(ret)
 }
 
+// Hash the router gives a timelock operation, hex encoded blake2b of its arguments and eta.
+pub fn operation_hash(action: &str, target: Key, value: u64, eta: u64) -> String {
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    hasher.update(action.to_string().to_bytes().unwrap());
+    hasher.update(target.to_bytes().unwrap());
+    hasher.update(value.to_bytes().unwrap());
+    hasher.update(eta.to_bytes().unwrap());
+    let mut ret = [0u8; 32];
+    hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+    hex::encode(ret)
+}
+
 pub fn init(
     owner: AccountHash,
     token1: &TestContract,
@@ -50,6 +71,47 @@ pub fn init(
     uniswap_factory: &TestContract,
     uniswap_router: &TestContract,
     now: u64,
+) {
+    uniswap_factory.call_contract(
+        owner,
+        "set_white_list",
+        runtime_args! {
+            "white_list" => Key::Hash(uniswap_router.package_hash())
+        },
+        now,
+    );
+    // whitelisting goes through the router's timelock
+    let eta: u64 = now + TIMELOCK_DELAY;
+    uniswap_router.call_contract(
+        owner,
+        "queue_operation",
+        runtime_args! {
+            "action" => "add_to_whitelist",
+            "target" => Key::Account(owner),
+            "value" => 0u64,
+            "eta" => eta
+        },
+        now,
+    );
+    uniswap_router.call_contract(
+        owner,
+        "execute_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash("add_to_whitelist", Key::Account(owner), 0, eta)
+        },
+        eta,
+    );
+    mint_and_approve(owner, token1, token2, uniswap_router, now);
+}
+
+// Whitelisting of a router installed from the v1 fixture, which had no timelock yet.
+pub fn init_v1(
+    owner: AccountHash,
+    token1: &TestContract,
+    token2: &TestContract,
+    uniswap_factory: &TestContract,
+    uniswap_router: &TestContract,
+    now: u64,
 ) {
     uniswap_factory.call_contract(
         owner,
@@ -67,6 +129,16 @@ pub fn init(
         },
         now,
     );
+    mint_and_approve(owner, token1, token2, uniswap_router, now);
+}
+
+fn mint_and_approve(
+    owner: AccountHash,
+    token1: &TestContract,
+    token2: &TestContract,
+    uniswap_router: &TestContract,
+    now: u64,
+) {
     // Minting
     token1.call_contract(
         owner,
@@ -123,6 +195,24 @@ pub fn call(
     TestContract::new(env, wasm, "call", sender, runtime_args, time)
 }
 
+// Cspr paid by `sender` for the deploys run by `deploys`, payments are refunded down to the gas used.
+pub fn gas_used<F: FnOnce()>(env: &TestEnv, sender: AccountHash, deploys: F) -> U512 {
+    let balance_before: U512 = env.get_account_cspr_balance(sender);
+    deploys();
+    balance_before - env.get_account_cspr_balance(sender)
+}
+
 pub fn result_key<T: CLTyped + FromBytes>(env: &TestEnv, sender: AccountHash, key: &str) -> T {
     env.query_account_named_key(sender, &[key.into()])
 }
+
+// Typed result of the last session code router run of `sender`.
+pub fn session_result(env: &TestEnv, sender: AccountHash) -> SessionResult {
+    result_key(env, sender, SESSION_RESULT)
+}
+
+pub fn balance_of(token: &TestContract, owner: Address) -> U256 {
+    token
+        .query_dictionary(BALANCES, address_to_str(&owner))
+        .unwrap_or_default()
+}
@@ -1,5 +1,6 @@
 pub mod deploys;
 pub mod helpers;
+pub mod script;
 
 pub use base64::*;
 pub use blake2::*;
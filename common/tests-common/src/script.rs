@@ -0,0 +1,57 @@
+use common::{
+    bytesrepr::{Bytes, ToBytes},
+    keys::DESTINATION_SCRIPT,
+    runtime_args,
+    session::{ScriptStep, StepInput, INPUT_U256},
+    Key, RuntimeArgs,
+};
+
+// Builds the session args of a session code router script, steps run in the order they are added.
+#[derive(Default)]
+pub struct Script {
+    steps: Vec<ScriptStep>,
+}
+
+impl Script {
+    pub fn new() -> Script {
+        Script::default()
+    }
+
+    // `args` leave out `entrypoint` and `package_hash`, and any arg taken from an earlier step.
+    pub fn step(mut self, destination: &str, args: RuntimeArgs) -> Script {
+        self.steps.push(ScriptStep {
+            destination: destination.into(),
+            args,
+            inputs: Vec::new(),
+        });
+        self
+    }
+
+    // Passes the named value `value` of step `step` as `arg` to the last step added.
+    pub fn input(self, arg: &str, step: u32, value: &str) -> Script {
+        self.typed_input(arg, step, value, INPUT_U256)
+    }
+
+    // Like `input`, converting the value to `input_type`, one of the `INPUT_*` types.
+    pub fn typed_input(mut self, arg: &str, step: u32, value: &str, input_type: u8) -> Script {
+        self.steps
+            .last_mut()
+            .expect("an input needs a step")
+            .inputs
+            .push(StepInput {
+                arg: arg.into(),
+                step,
+                value: value.into(),
+                input_type,
+            });
+        self
+    }
+
+    pub fn args(&self, package_hash: Key) -> RuntimeArgs {
+        runtime_args! {
+            "entrypoint" => DESTINATION_SCRIPT,
+            "package_hash" => package_hash,
+            "steps" => Bytes::from(self.steps.to_bytes().unwrap()),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+// Json schema of the router and library entry points, built from the same `get_entry_points`
+// the contracts are installed with. The schema files in `schema/` are written by the
+// `contract-schema` binary and checked against the entry points by the router tests.
+use common::{errors::Errors, EntryPointAccess, EntryPoints};
+use serde_json::{json, Value};
+
+pub const ROUTER: &str = "uniswap-v2-router";
+pub const LIBRARY: &str = "uniswap-v2-library";
+
+pub fn router_schema() -> Value {
+    contract_schema(
+        ROUTER,
+        uniswap_v2_router_crate::entry_points::get_entry_points(),
+        uniswap_v2_router_crate::entry_points::entry_point_errors,
+    )
+}
+
+pub fn library_schema() -> Value {
+    contract_schema(
+        LIBRARY,
+        uniswap_v2_library_crate::entry_points::get_entry_points(),
+        uniswap_v2_library_crate::entry_points::entry_point_errors,
+    )
+}
+
+// Entry points are listed by name, `cl_type`s in the json representation of casper-types.
+fn contract_schema(
+    contract: &str,
+    entry_points: EntryPoints,
+    errors: fn(&str) -> Option<Vec<Errors>>,
+) -> Value {
+    let entry_points: Vec<Value> = entry_points
+        .take_entry_points()
+        .iter()
+        .map(|entry_point| {
+            let args: Vec<Value> = entry_point
+                .args()
+                .iter()
+                .map(|arg| json!({ "name": arg.name(), "cl_type": arg.cl_type() }))
+                .collect();
+            let access: Value = match entry_point.access() {
+                EntryPointAccess::Public => json!("public"),
+                EntryPointAccess::Groups(groups) => {
+                    let groups: Vec<&str> = groups.iter().map(|group| group.value()).collect();
+                    json!({ "groups": groups })
+                }
+            };
+            let errors: Vec<Value> = errors(entry_point.name())
+                .unwrap_or_else(|| panic!("no errors listed for {}", entry_point.name()))
+                .iter()
+                .map(error_schema)
+                .collect();
+            json!({
+                "name": entry_point.name(),
+                "args": args,
+                "ret": entry_point.ret(),
+                "access": access,
+                "errors": errors,
+            })
+        })
+        .collect();
+    json!({ "contract": contract, "entry_points": entry_points })
+}
+
+fn error_schema(error: &Errors) -> Value {
+    json!({
+        "name": format!("{:?}", error),
+        "code": error.code(),
+        "api_error": u32::from(error.code()) + u32::from(u16::MAX) + 1,
+        "description": error.description(),
+    })
+}
+
+// Pretty printed with a trailing newline, the format of the files in `schema/`.
+pub fn to_file_contents(schema: &Value) -> String {
+    let mut contents: String = serde_json::to_string_pretty(schema).expect("schema is valid json");
+    contents.push('\n');
+    contents
+}
@@ -0,0 +1,20 @@
+use std::{fs, path::PathBuf};
+
+// Writes `{contract}.json` for the router and the library into the directory given as the
+// first argument, `schema` by default.
+fn main() {
+    let dir: PathBuf = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "schema".into())
+        .into();
+    fs::create_dir_all(&dir).expect("schema directory can be created");
+    for (contract, schema) in [
+        (contract_schema::ROUTER, contract_schema::router_schema()),
+        (contract_schema::LIBRARY, contract_schema::library_schema()),
+    ] {
+        let path: PathBuf = dir.join(format!("{}.json", contract));
+        fs::write(&path, contract_schema::to_file_contents(&schema))
+            .unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+        println!("{}", path.display());
+    }
+}
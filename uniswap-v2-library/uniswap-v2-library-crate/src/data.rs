@@ -1,4 +1,5 @@
-use common::ContractHash;
+use casper_types_derive::{CLTyped, FromBytes, ToBytes};
+use common::{errors::Errors, keys::*, unwrap_or_revert::UnwrapOrRevert, *};
 
 // Accepts a Contract Hash and converts it into a simple String Hash without hex(0x)|(contract-)
 pub fn make_hash(contract_hash: &ContractHash) -> String {
@@ -17,3 +18,66 @@ pub fn encode_packed(args: &[&String]) -> String {
     }
     encoded_hash
 }
+
+#[derive(Clone, Copy, Default, CLTyped, ToBytes, FromBytes)]
+pub struct Observation {
+    pub timestamp: u64,
+    pub price0_cumulative: U256,
+    pub price1_cumulative: U256,
+}
+
+#[derive(CLTyped, ToBytes, FromBytes)]
+pub struct Observations {
+    dict: Dict,
+}
+
+impl Observations {
+    pub fn instance() -> Observations {
+        Observations {
+            dict: Dict::instance(OBSERVATIONS),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(OBSERVATIONS)
+    }
+
+    // observations of a pair are kept in a ring buffer of `granularity` slots
+    pub fn get(&self, pair: &Key) -> Vec<Observation> {
+        self.dict
+            .get_by_key(pair)
+            .unwrap_or_else(|| vec![Observation::default(); granularity().into()])
+    }
+
+    pub fn set(&self, pair: &Key, observations: Vec<Observation>) {
+        self.dict.set_by_key(pair, observations);
+    }
+}
+
+pub fn oracle_factory() -> Key {
+    get_key(ORACLE_FACTORY).unwrap_or_revert_with(Errors::OracleFactoryNotSet)
+}
+pub fn set_oracle_factory(factory: Key) {
+    set_key(ORACLE_FACTORY, factory);
+}
+
+pub fn window_size() -> u64 {
+    get_key(WINDOW_SIZE).unwrap_or_revert()
+}
+pub fn set_window_size(window_size: u64) {
+    set_key(WINDOW_SIZE, window_size);
+}
+
+pub fn granularity() -> u8 {
+    get_key(GRANULARITY).unwrap_or_revert()
+}
+pub fn set_granularity(granularity: u8) {
+    set_key(GRANULARITY, granularity);
+}
+
+pub fn period_size() -> u64 {
+    get_key(PERIOD_SIZE).unwrap_or_revert()
+}
+pub fn set_period_size(period_size: u64) {
+    set_key(PERIOD_SIZE, period_size);
+}
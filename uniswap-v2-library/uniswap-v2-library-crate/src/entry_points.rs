@@ -0,0 +1,202 @@
+// Entry points of the library contract. They are installed from the library wasm and exported
+// as a json schema by `contract-schema`, so both are built from the definitions below.
+use common::{errors::Errors, *};
+
+pub fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("contract_hash", ContractHash::cl_type()),
+            Parameter::new("package_hash", ContractPackageHash::cl_type()),
+            Parameter::new("factory", Option::<Key>::cl_type()),
+            Parameter::new("window_size", u64::cl_type()),
+            Parameter::new("granularity", u8::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "sort_tokens",
+        vec![
+            Parameter::new("token_a", Key::cl_type()),
+            Parameter::new("token_b", Key::cl_type()),
+        ],
+        CLType::Tuple2([
+            Box::new(ContractPackageHash::cl_type()),
+            Box::new(ContractPackageHash::cl_type()),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_reserves",
+        vec![
+            Parameter::new("factory", Key::cl_type()),
+            Parameter::new("token_a", Key::cl_type()),
+            Parameter::new("token_b", Key::cl_type()),
+        ],
+        CLType::Tuple2([Box::new(CLType::U128), Box::new(CLType::U128)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "quote",
+        vec![
+            Parameter::new("amount_a", U256::cl_type()),
+            Parameter::new("reserve_a", U128::cl_type()),
+            Parameter::new("reserve_b", U128::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amount_out",
+        vec![
+            Parameter::new("amount_in", U256::cl_type()),
+            Parameter::new("reserve_in", U256::cl_type()),
+            Parameter::new("reserve_out", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amount_in",
+        vec![
+            Parameter::new("amount_out", U256::cl_type()),
+            Parameter::new("reserve_in", U256::cl_type()),
+            Parameter::new("reserve_out", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amounts_out",
+        vec![
+            Parameter::new("factory", Key::cl_type()),
+            Parameter::new("amount_in", U256::cl_type()),
+            Parameter::new("path", CLType::List(Box::new(Key::cl_type()))),
+        ],
+        CLType::List(Box::new(U256::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amounts_in",
+        vec![
+            Parameter::new("factory", Key::cl_type()),
+            Parameter::new("amount_out", U256::cl_type()),
+            Parameter::new("path", CLType::List(Box::new(Key::cl_type()))),
+        ],
+        CLType::List(Box::new(U256::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pair_for",
+        vec![
+            Parameter::new("factory", Key::cl_type()),
+            Parameter::new("token_a", Key::cl_type()),
+            Parameter::new("token_b", Key::cl_type()),
+        ],
+        CLType::Key,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "observation_index_of",
+        vec![Parameter::new("timestamp", u64::cl_type())],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "current_cumulative_prices",
+        vec![Parameter::new("pair", Key::cl_type())],
+        CLType::Tuple3([
+            Box::new(U256::cl_type()),
+            Box::new(U256::cl_type()),
+            Box::new(u64::cl_type()),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "update",
+        vec![
+            Parameter::new("token_a", Key::cl_type()),
+            Parameter::new("token_b", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "consult",
+        vec![
+            Parameter::new("token_in", Key::cl_type()),
+            Parameter::new("amount_in", U256::cl_type()),
+            Parameter::new("token_out", Key::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+const SORT_TOKENS: [Errors; 2] = [Errors::IdenticalAddresses, Errors::ZeroAddress];
+
+/// Errors an entry point can revert with, `None` for a name that is not an entry point.
+pub fn entry_point_errors(entry_point: &str) -> Option<Vec<Errors>> {
+    let errors: Vec<Errors> = match entry_point {
+        "constructor" => vec![Errors::OracleInvalidGranularity],
+        "sort_tokens" | "get_reserves" | "pair_for" => SORT_TOKENS.to_vec(),
+        "quote" => vec![Errors::InsufficientAmount, Errors::InsufficientLiquidity],
+        "get_amount_out" => vec![
+            Errors::InsufficientInputAmount,
+            Errors::InsufficientLiquidity,
+            Errors::ArithmeticOverflow,
+        ],
+        "get_amount_in" => vec![
+            Errors::InsufficientOutputAmount,
+            Errors::InsufficientLiquidity,
+            Errors::ArithmeticOverflow,
+        ],
+        "get_amounts_out" | "get_amounts_in" => [
+            &[Errors::InvalidPath][..],
+            &SORT_TOKENS,
+            &[
+                Errors::InsufficientInputAmount,
+                Errors::InsufficientOutputAmount,
+                Errors::InsufficientLiquidity,
+                Errors::ArithmeticOverflow,
+            ],
+        ]
+        .concat(),
+        "observation_index_of" => vec![],
+        "current_cumulative_prices" => vec![Errors::OracleNoReserves],
+        "update" => [
+            &SORT_TOKENS[..],
+            &[Errors::OracleFactoryNotSet, Errors::OracleNoReserves],
+        ]
+        .concat(),
+        "consult" => [
+            &SORT_TOKENS[..],
+            &[
+                Errors::OracleFactoryNotSet,
+                Errors::OracleMissingHistoricalObservation,
+                Errors::OracleUnexpectedTimeElapsed,
+                Errors::OracleMultiplicationOverflow,
+            ],
+        ]
+        .concat(),
+        _ => return None,
+    };
+    Some(errors)
+}
@@ -1,5 +1,9 @@
 pub mod data;
+pub mod entry_points;
+pub mod math;
+pub mod oracle;
 pub mod uniswap_v2_library;
 
 pub use common::*;
+pub use oracle::SlidingWindowOracle;
 pub use uniswap_v2_library::UniswapV2Library;
@@ -0,0 +1,124 @@
+use common::{
+    errors::Errors,
+    functions::{u256_to_u512, u512_to_u256},
+    unwrap_or_revert::UnwrapOrRevert,
+    *,
+};
+
+// Pricing math of the library, free of any contract context so that the router can link it
+// instead of calling the library contract. The `try_` variants return the error instead of
+// reverting, for host side tools that quote without a contract runtime. Products are taken in
+// U512 so that no reserve a pair can hold (up to U128::MAX) overflows the intermediate values.
+
+/// given some amount of an asset and pair reserves, returns an equivalent amount of the other asset
+pub fn quote(amount_a: U256, reserve_a: U256, reserve_b: U256) -> U256 {
+    try_quote(amount_a, reserve_a, reserve_b).unwrap_or_revert()
+}
+
+/// given an input amount of an asset and pair reserves, returns the maximum output amount of the other asset
+pub fn get_amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    try_get_amount_out(amount_in, reserve_in, reserve_out).unwrap_or_revert()
+}
+
+/// given an output amount of an asset and pair reserves, returns a required input amount of the other asset
+pub fn get_amount_in(amount_out: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+    try_get_amount_in(amount_out, reserve_in, reserve_out).unwrap_or_revert()
+}
+
+/// performs chained getAmountOut calculations over the (reserve_in, reserve_out) of every hop
+pub fn get_amounts_out(amount_in: U256, reserves: &[(U256, U256)]) -> Vec<U256> {
+    try_get_amounts_out(amount_in, reserves).unwrap_or_revert()
+}
+
+/// performs chained getAmountIn calculations over the (reserve_in, reserve_out) of every hop
+pub fn get_amounts_in(amount_out: U256, reserves: &[(U256, U256)]) -> Vec<U256> {
+    try_get_amounts_in(amount_out, reserves).unwrap_or_revert()
+}
+
+pub fn try_quote(amount_a: U256, reserve_a: U256, reserve_b: U256) -> Result<U256, Errors> {
+    if amount_a <= 0.into() {
+        return Err(Errors::InsufficientAmount);
+    }
+    if reserve_a <= 0.into() || reserve_b <= 0.into() {
+        return Err(Errors::InsufficientLiquidity);
+    }
+    to_u256(u256_to_u512(amount_a) * u256_to_u512(reserve_b) / u256_to_u512(reserve_a))
+}
+
+pub fn try_get_amount_out(
+    amount_in: U256,
+    reserve_in: U256,
+    reserve_out: U256,
+) -> Result<U256, Errors> {
+    if amount_in <= 0.into() {
+        return Err(Errors::InsufficientInputAmount);
+    }
+    if reserve_in <= 0.into() || reserve_out <= 0.into() {
+        return Err(Errors::InsufficientLiquidity);
+    }
+    let amount_in_with_fee: U512 = u256_to_u512(amount_in) * 997;
+    let numerator: U512 = amount_in_with_fee * u256_to_u512(reserve_out);
+    let denominator: U512 = u256_to_u512(reserve_in) * 1000 + amount_in_with_fee;
+    to_u256(numerator / denominator)
+}
+
+pub fn try_get_amount_in(
+    amount_out: U256,
+    reserve_in: U256,
+    reserve_out: U256,
+) -> Result<U256, Errors> {
+    if amount_out <= 0.into() {
+        return Err(Errors::InsufficientOutputAmount);
+    }
+    if reserve_in <= 0.into() || reserve_out <= 0.into() {
+        return Err(Errors::InsufficientLiquidity);
+    }
+    // the pair can't pay out its whole reserve or more
+    if amount_out >= reserve_out {
+        return Err(Errors::InsufficientLiquidity);
+    }
+    let numerator: U512 = u256_to_u512(reserve_in) * u256_to_u512(amount_out) * 1000;
+    let denominator: U512 = u256_to_u512(reserve_out - amount_out) * 997;
+    to_u256(numerator / denominator + 1)
+}
+
+pub fn try_get_amounts_out(
+    amount_in: U256,
+    reserves: &[(U256, U256)],
+) -> Result<Vec<U256>, Errors> {
+    if reserves.is_empty() {
+        return Err(Errors::InvalidPath);
+    }
+    let mut amounts: Vec<U256> = vec![amount_in];
+    for &(reserve_in, reserve_out) in reserves {
+        let amount_out: U256 =
+            try_get_amount_out(amounts[amounts.len() - 1], reserve_in, reserve_out)?;
+        amounts.push(amount_out);
+    }
+    Ok(amounts)
+}
+
+pub fn try_get_amounts_in(
+    amount_out: U256,
+    reserves: &[(U256, U256)],
+) -> Result<Vec<U256>, Errors> {
+    if reserves.is_empty() {
+        return Err(Errors::InvalidPath);
+    }
+    let mut amounts: Vec<U256> = vec![amount_out];
+    for &(reserve_in, reserve_out) in reserves.iter().rev() {
+        let amount_in: U256 =
+            try_get_amount_in(amounts[amounts.len() - 1], reserve_in, reserve_out)?;
+        amounts.push(amount_in);
+    }
+    amounts.reverse();
+    Ok(amounts)
+}
+
+// Narrows a U512 intermediate back to U256, the width amounts are passed around in.
+fn to_u256(value: U512) -> Result<U256, Errors> {
+    if value > u256_to_u512(U256::MAX) {
+        return Err(Errors::ArithmeticOverflow);
+    }
+    Ok(u512_to_u256(value))
+}
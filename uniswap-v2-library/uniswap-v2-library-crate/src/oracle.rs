@@ -0,0 +1,160 @@
+use crate::{data::*, uniswap_v2_library::UniswapV2Library};
+use common::{
+    contract_api::runtime, errors::Errors, functions::block_timestamp, keys::*,
+    unwrap_or_revert::UnwrapOrRevert, *,
+};
+
+/// window of the oracle when the install doesn't give one, a day of block time in milliseconds
+pub const DEFAULT_WINDOW_SIZE: u64 = 24 * 60 * 60 * 1000;
+/// observations per window when the install doesn't give a granularity, one per hour
+pub const DEFAULT_GRANULARITY: u8 = 24;
+
+// Sliding window oracle over the pairs' cumulative prices, prices are UQ112x112 fixed point numbers.
+// Observations are recorded once per period into `granularity` slots covering `window_size`.
+// Times are block times, in the same unit the pairs use for their timestamps.
+pub trait SlidingWindowOracle<Storage: ContractStorage>: UniswapV2Library<Storage> {
+    /// a library installed without a factory keeps the oracle entry points reverting with
+    /// `OracleFactoryNotSet`
+    fn init_oracle(&self, factory: Option<Key>, window_size: u64, granularity: u8) {
+        if granularity <= 1 {
+            runtime::revert(Errors::OracleInvalidGranularity);
+        }
+        let period_size: u64 = window_size / u64::from(granularity);
+        if period_size * u64::from(granularity) != window_size {
+            runtime::revert(Errors::OracleInvalidGranularity);
+        }
+        if let Some(factory) = factory {
+            set_oracle_factory(factory);
+        }
+        set_window_size(window_size);
+        set_granularity(granularity);
+        set_period_size(period_size);
+        Observations::init();
+    }
+
+    /// returns the index of the observation corresponding to the given timestamp
+    fn observation_index_of(&self, timestamp: u64) -> u8 {
+        let epoch_period: u64 = timestamp / period_size();
+        (epoch_period % u64::from(granularity())) as u8
+    }
+
+    /// produces the cumulative prices of a pair as of the current block, using counterfactual
+    /// accumulation when the pair has not been updated in this block
+    fn current_cumulative_prices(&self, pair: Key) -> (U256, U256, u64) {
+        let pair_hash: ContractPackageHash = pair.into_hash().unwrap_or_revert().into();
+        let timestamp: u64 = block_timestamp();
+        let mut price0_cumulative: U256 = runtime::call_versioned_contract(
+            pair_hash,
+            None,
+            PAIR_PRICE0_CUMULATIVE_LAST,
+            runtime_args! {},
+        );
+        let mut price1_cumulative: U256 = runtime::call_versioned_contract(
+            pair_hash,
+            None,
+            PAIR_PRICE1_CUMULATIVE_LAST,
+            runtime_args! {},
+        );
+        let (reserve0, reserve1, timestamp_last): (U128, U128, u64) =
+            runtime::call_versioned_contract(pair_hash, None, PAIR_GET_RESERVES, runtime_args! {});
+        if timestamp_last != timestamp {
+            if reserve0 == 0.into() || reserve1 == 0.into() {
+                runtime::revert(Errors::OracleNoReserves);
+            }
+            // overflow is desired, the accumulators wrap like the pair's
+            let elapsed: U256 = U256::from(timestamp - timestamp_last);
+            price0_cumulative = price0_cumulative
+                .overflowing_add(fraction(reserve1, reserve0).overflowing_mul(elapsed).0)
+                .0;
+            price1_cumulative = price1_cumulative
+                .overflowing_add(fraction(reserve0, reserve1).overflowing_mul(elapsed).0)
+                .0;
+        }
+        (price0_cumulative, price1_cumulative, timestamp)
+    }
+
+    /// records the cumulative prices of the pair, at most once per period
+    fn update(&self, token_a: Key, token_b: Key) {
+        let pair: Key = self.pair_for(oracle_factory(), token_a, token_b);
+        let timestamp: u64 = block_timestamp();
+        let index: usize = self.observation_index_of(timestamp).into();
+        let observations = Observations::instance();
+        let mut pair_observations: Vec<Observation> = observations.get(&pair);
+        // only record once per period
+        if timestamp - pair_observations[index].timestamp > period_size() {
+            let (price0_cumulative, price1_cumulative, _) = self.current_cumulative_prices(pair);
+            pair_observations[index] = Observation {
+                timestamp,
+                price0_cumulative,
+                price1_cumulative,
+            };
+            observations.set(&pair, pair_observations);
+        }
+    }
+
+    /// returns the amount out of token_out for amount_in of token_in, using the time weighted
+    /// average price over the window ending now
+    fn consult(&self, token_in: Key, amount_in: U256, token_out: Key) -> U256 {
+        let pair: Key = self.pair_for(oracle_factory(), token_in, token_out);
+        let first_observation: Observation = self.first_observation_in_window(pair);
+        let time_elapsed: u64 = block_timestamp() - first_observation.timestamp;
+        if time_elapsed > window_size() {
+            runtime::revert(Errors::OracleMissingHistoricalObservation);
+        }
+        // should never happen, the first observation is at least one period old
+        if time_elapsed < window_size() - period_size() * 2 {
+            runtime::revert(Errors::OracleUnexpectedTimeElapsed);
+        }
+        let (price0_cumulative, price1_cumulative, _) = self.current_cumulative_prices(pair);
+        let (token_0, _): (ContractPackageHash, ContractPackageHash) = self.sort_tokens(
+            token_in.into_hash().unwrap_or_default().into(),
+            token_out.into_hash().unwrap_or_default().into(),
+        );
+        if token_in == Key::from(token_0) {
+            compute_amount_out(
+                first_observation.price0_cumulative,
+                price0_cumulative,
+                time_elapsed,
+                amount_in,
+            )
+        } else {
+            compute_amount_out(
+                first_observation.price1_cumulative,
+                price1_cumulative,
+                time_elapsed,
+                amount_in,
+            )
+        }
+    }
+
+    // *************************************** Helper methods ****************************************
+    fn first_observation_in_window(&self, pair: Key) -> Observation {
+        let index: u8 = self.observation_index_of(block_timestamp());
+        // the slot after the current one is the oldest in the ring buffer
+        let first_index: u8 = (index + 1) % granularity();
+        Observations::instance().get(&pair)[usize::from(first_index)]
+    }
+}
+
+/// encodes numerator / denominator as a UQ112x112
+pub fn fraction(numerator: U128, denominator: U128) -> U256 {
+    (U256::from(numerator.as_u128()) << 112) / U256::from(denominator.as_u128())
+}
+
+/// given the cumulative prices of the start and end of a period, and the length of the period,
+/// computes the amount out at the average price
+pub fn compute_amount_out(
+    price_cumulative_start: U256,
+    price_cumulative_end: U256,
+    time_elapsed: u64,
+    amount_in: U256,
+) -> U256 {
+    let price_average: U256 = price_cumulative_end
+        .overflowing_sub(price_cumulative_start)
+        .0
+        / U256::from(time_elapsed);
+    price_average
+        .checked_mul(amount_in)
+        .unwrap_or_revert_with(Errors::OracleMultiplicationOverflow)
+        >> 112
+}
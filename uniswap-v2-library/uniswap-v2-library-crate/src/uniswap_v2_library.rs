@@ -1,3 +1,4 @@
+use crate::math;
 use common::{
     contract_api::runtime, errors::Errors, functions::zero_address,
     unwrap_or_revert::UnwrapOrRevert, *,
@@ -72,46 +73,21 @@ pub trait UniswapV2Library<Storage: ContractStorage>: ContractContext<Storage> {
 
     /// given some amount of an asset and pair reserves, returns an equivalent amount of the other asset
     fn quote(&self, amount_a: U256, reserve_a: U128, reserve_b: U128) -> U256 {
-        if amount_a <= 0.into() {
-            runtime::revert(Errors::InsufficientAmount);
-        }
-        if reserve_a <= 0.into() || reserve_b <= 0.into() {
-            runtime::revert(Errors::InsufficientLiquidity1);
-        }
-        (amount_a * U256::from(reserve_b.as_u128())) / U256::from(reserve_a.as_u128())
+        math::quote(
+            amount_a,
+            U256::from(reserve_a.as_u128()),
+            U256::from(reserve_b.as_u128()),
+        )
     }
 
     /// given an input amount of an asset and pair reserves, returns the maximum output amount of the other asset
     fn get_amount_out(&self, amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
-        if amount_in <= 0.into() {
-            runtime::revert(Errors::InsufficientInputAmount);
-        }
-        if reserve_in <= 0.into() || reserve_out <= 0.into() {
-            runtime::revert(Errors::InsufficientLiquidity2);
-        }
-        let amount_in_with_fee: U256 = amount_in * 997;
-        let numerator: U256 = amount_in_with_fee * reserve_out;
-        let denominator: U256 = reserve_in
-            .checked_mul(U256::from(1000))
-            .unwrap_or_revert_with(Errors::MultiplicationOverflow1)
-            .checked_add(amount_in_with_fee)
-            .unwrap_or_revert_with(Errors::AdditionOverflow1);
-        numerator / denominator
+        math::get_amount_out(amount_in, reserve_in, reserve_out)
     }
 
     /// given an output amount of an asset and pair reserves, returns a required input amount of the other asset
     fn get_amount_in(&self, amount_out: U256, reserve_in: U256, reserve_out: U256) -> U256 {
-        if amount_out <= 0.into() {
-            runtime::revert(Errors::InsufficientOutputAmount);
-        }
-        if reserve_in <= 0.into() || reserve_out <= 0.into() {
-            runtime::revert(Errors::InsufficientLiquidity3);
-        }
-        let numerator: U256 = reserve_in * amount_out * 1000;
-        let denominator: U256 = (reserve_out - amount_out) * 997;
-        (numerator / denominator)
-            .checked_add(U256::from(1))
-            .unwrap_or_revert_with(Errors::AdditionOverflow2)
+        math::get_amount_in(amount_out, reserve_in, reserve_out)
     }
 
     /// performs chained getAmountOut calculations on any number of pairs
@@ -122,18 +98,13 @@ pub trait UniswapV2Library<Storage: ContractStorage>: ContractContext<Storage> {
         path: Vec<ContractPackageHash>,
     ) -> Vec<U256> {
         if path.len() < 2 {
-            runtime::revert(Errors::InsufficientLiquidity4);
-        }
-        let mut amounts: Vec<U256> = vec![0.into(); path.len()];
-        amounts[0] = amount_in;
-        for i in 0..(path.len() - 1) {
-            let (reserve_in, reserve_out): (U128, U128) =
-                self.get_reserves(factory, path[i], path[i + 1]);
-            let reserve_in: U256 = U256::from(reserve_in.as_u128());
-            let reserve_out: U256 = U256::from(reserve_out.as_u128());
-            amounts[i + 1] = self.get_amount_out(amounts[i], reserve_in, reserve_out);
+            runtime::revert(Errors::InvalidPath);
         }
-        amounts
+        let reserves: Vec<(U256, U256)> = path
+            .windows(2)
+            .map(|hop| self.get_reserves_u256(factory, hop[0], hop[1]))
+            .collect();
+        math::get_amounts_out(amount_in, &reserves)
     }
 
     /// performs chained getAmountIn calculations on any number of pairs
@@ -146,18 +117,24 @@ pub trait UniswapV2Library<Storage: ContractStorage>: ContractContext<Storage> {
         if path.len() < 2 {
             runtime::revert(Errors::InvalidPath);
         }
-        let mut amounts: Vec<U256> = vec![0.into(); path.len()];
-        let size = amounts.len();
-        amounts[size - 1] = amount_out;
-        for i in (1..path.len()).rev() {
-            let (reserve_in, reserve_out): (U128, U128) =
-                self.get_reserves(factory, path[i - 1], path[i]);
-
-            let reserve_in: U256 = U256::from(reserve_in.as_u128());
-            let reserve_out: U256 = U256::from(reserve_out.as_u128());
+        let reserves: Vec<(U256, U256)> = path
+            .windows(2)
+            .map(|hop| self.get_reserves_u256(factory, hop[0], hop[1]))
+            .collect();
+        math::get_amounts_in(amount_out, &reserves)
+    }
 
-            amounts[i - 1] = self.get_amount_in(amounts[i], reserve_in, reserve_out);
-        }
-        amounts
+    // Reserves of the pair ordered as (token_a, token_b), widened for the pricing math.
+    fn get_reserves_u256(
+        &self,
+        factory: ContractPackageHash,
+        token_a: ContractPackageHash,
+        token_b: ContractPackageHash,
+    ) -> (U256, U256) {
+        let (reserve_a, reserve_b): (U128, U128) = self.get_reserves(factory, token_a, token_b);
+        (
+            U256::from(reserve_a.as_u128()),
+            U256::from(reserve_b.as_u128()),
+        )
     }
 }
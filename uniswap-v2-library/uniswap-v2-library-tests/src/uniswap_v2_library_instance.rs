@@ -167,6 +167,18 @@ impl LibraryInstance {
         );
     }
 
+    pub fn update(&self, sender: AccountHash, token_a: Key, token_b: Key, time: u64) {
+        self.0.call_contract(
+            sender,
+            "update",
+            runtime_args! {
+                "token_a" => token_a,
+                "token_b" => token_b
+            },
+            time,
+        );
+    }
+
     pub fn approve(&self, token: &TestContract, sender: AccountHash, spender: Key, amount: U256) {
         token.call_contract(
             sender,
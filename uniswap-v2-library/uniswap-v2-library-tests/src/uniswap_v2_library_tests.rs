@@ -1,14 +1,18 @@
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::uniswap_v2_library_instance::LibraryInstance;
+use proptest::prelude::*;
 use tests_common::{
     account::AccountHash,
     assert_ge,
     deploys::{deploy_library, *},
     functions::zero_address,
-    helpers::{call, init, result_key, AMOUNT, SESSION_CODE_ROUTER},
+    helpers::{
+        call, init, result_key, AMOUNT, ORACLE_GRANULARITY, ORACLE_WINDOW_SIZE, SESSION_CODE_ROUTER,
+    },
     *,
 };
+use uniswap_v2_library_crate::math;
 
 fn deploy() -> (
     TestEnv,
@@ -66,7 +70,14 @@ fn deploy() -> (
         Key::Hash(factory_contract.package_hash()),
         now,
     );
-    let library_contract = deploy_library(&env, owner, now);
+    let library_contract = deploy_library(
+        &env,
+        owner,
+        Key::Hash(factory_contract.package_hash()),
+        ORACLE_WINDOW_SIZE,
+        ORACLE_GRANULARITY,
+        now,
+    );
     let router_contract: TestContract = deploy_router(
         &env,
         owner,
@@ -254,3 +265,416 @@ fn test_uniswap_get_amounts_in() {
     ];
     library_contract.get_amounts_in(owner, Key::Hash(factory.package_hash()), 100.into(), path);
 }
+
+// Pricing at the edges of what a pair can hold, where U256 products used to overflow.
+
+const MAX_RESERVE: u128 = u128::MAX;
+
+fn wide(value: U256) -> U512 {
+    functions::u256_to_u512(value)
+}
+
+#[test]
+fn get_amount_out_at_max_reserves() {
+    let max: U256 = MAX_RESERVE.into();
+    // amount_in * 997 * reserve_out is past U256::MAX, the common factor cancels out
+    let amount_out: U256 = math::try_get_amount_out(max, max, max).unwrap();
+    assert_eq!(amount_out, max * 997 / 1997);
+}
+
+#[test]
+fn get_amount_in_at_max_reserves() {
+    let max: U256 = MAX_RESERVE.into();
+    let amount_out: U256 = max / 2;
+    let amount_in: U256 = math::try_get_amount_in(amount_out, max, max).unwrap();
+    let expected: U512 = wide(max) * wide(amount_out) * 1000 / (wide(max - amount_out) * 997) + 1;
+    assert_eq!(wide(amount_in), expected);
+}
+
+#[test]
+fn get_amount_in_past_u256_is_an_overflow() {
+    let max: U256 = MAX_RESERVE.into();
+    // buying all but one unit of the pool costs more than a U256 can hold
+    assert!(matches!(
+        math::try_get_amount_in(max - 1, max, max),
+        Err(errors::Errors::ArithmeticOverflow)
+    ));
+}
+
+#[test]
+fn quote_of_max_amount() {
+    let max: U256 = MAX_RESERVE.into();
+    assert_eq!(math::try_quote(U256::MAX, max, max).unwrap(), U256::MAX);
+    assert!(matches!(
+        math::try_quote(U256::MAX, 1.into(), 2.into()),
+        Err(errors::Errors::ArithmeticOverflow)
+    ));
+}
+
+#[test]
+fn get_amounts_chain_single_hops() {
+    let hops: Vec<(U256, U256)> = vec![
+        (1_000_000.into(), 2_000_000.into()),
+        (5_000.into(), MAX_RESERVE.into()),
+    ];
+    let amounts_out: Vec<U256> = math::try_get_amounts_out(10_000.into(), &hops).unwrap();
+    let first: U256 = math::try_get_amount_out(10_000.into(), hops[0].0, hops[0].1).unwrap();
+    let second: U256 = math::try_get_amount_out(first, hops[1].0, hops[1].1).unwrap();
+    assert_eq!(amounts_out, vec![10_000.into(), first, second]);
+
+    let amounts_in: Vec<U256> = math::try_get_amounts_in(1_000.into(), &hops).unwrap();
+    let last: U256 = math::try_get_amount_in(1_000.into(), hops[1].0, hops[1].1).unwrap();
+    let first: U256 = math::try_get_amount_in(last, hops[0].0, hops[0].1).unwrap();
+    assert_eq!(amounts_in, vec![first, last, 1_000.into()]);
+}
+
+#[test]
+fn get_amounts_without_hops_is_an_invalid_path() {
+    assert!(matches!(
+        math::try_get_amounts_out(1.into(), &[]),
+        Err(errors::Errors::InvalidPath)
+    ));
+    assert!(matches!(
+        math::try_get_amounts_in(1.into(), &[]),
+        Err(errors::Errors::InvalidPath)
+    ));
+}
+
+fn oracle_period_size() -> u64 {
+    ORACLE_WINDOW_SIZE / u64::from(ORACLE_GRANULARITY)
+}
+
+// adds 1:1 liquidity at the start of the next oracle period and records the first observation
+fn observe_pool(
+    env: &TestEnv,
+    owner: AccountHash,
+    library_contract: &LibraryInstance,
+    pair: &TestContract,
+    router: &TestContract,
+    token_a: Key,
+    token_b: Key,
+) -> u64 {
+    let t0: u64 = (now() / oracle_period_size() + 1) * oracle_period_size();
+    call(
+        env,
+        owner,
+        SESSION_CODE_ROUTER,
+        runtime_args! {
+            "entrypoint" => "add_liquidity",
+            "package_hash" => Key::Hash(router.package_hash()),
+            "token_a" => token_a,
+            "token_b" => token_b,
+            "amount_a_desired" => AMOUNT,
+            "amount_b_desired" => AMOUNT,
+            "amount_a_min" => U256::from(1000000),
+            "amount_b_min" => U256::from(1000000),
+            "to" => Key::Account(owner),
+            "deadline" => U256::from(t0 + 1000 * (30 * 60)),
+            "pair" => Some(Key::Hash(pair.package_hash())),
+        },
+        t0,
+    );
+    library_contract.update(owner, token_a, token_b, t0);
+    t0
+}
+
+fn consult(
+    env: &TestEnv,
+    owner: AccountHash,
+    library_contract: &LibraryInstance,
+    token_in: Key,
+    amount_in: U256,
+    token_out: Key,
+    time: u64,
+) -> U256 {
+    call(
+        env,
+        owner,
+        SESSION_CODE_ROUTER,
+        runtime_args! {
+            "entrypoint" => "consult",
+            "package_hash" => Key::from(library_contract.package_hash()),
+            "token_in" => token_in,
+            "amount_in" => amount_in,
+            "token_out" => token_out,
+        },
+        time,
+    );
+    result_key(env, owner, "consult")
+}
+
+#[test]
+fn test_oracle_consult() {
+    let (env, owner, library_contract, _, pair, router, token1, token2) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let t0 = observe_pool(
+        &env,
+        owner,
+        &library_contract,
+        &pair,
+        &router,
+        token_a,
+        token_b,
+    );
+    let t1 = t0 + oracle_period_size() * 23;
+    library_contract.update(owner, token_a, token_b, t1);
+    let amount_out = consult(
+        &env,
+        owner,
+        &library_contract,
+        token_a,
+        1000.into(),
+        token_b,
+        t1,
+    );
+    assert_eq!(amount_out, 1000.into());
+    let amount_out = consult(
+        &env,
+        owner,
+        &library_contract,
+        token_b,
+        1000.into(),
+        token_a,
+        t1,
+    );
+    assert_eq!(amount_out, 1000.into());
+}
+
+#[test]
+fn test_oracle_consult_ignores_same_block_swap() {
+    let (env, owner, library_contract, _, pair, router, token1, token2) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let t0 = observe_pool(
+        &env,
+        owner,
+        &library_contract,
+        &pair,
+        &router,
+        token_a,
+        token_b,
+    );
+    let t1 = t0 + oracle_period_size() * 23;
+    library_contract.update(owner, token_a, token_b, t1);
+    // move the spot price right before consulting
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        runtime_args! {
+            "entrypoint" => "swap_exact_tokens_for_tokens",
+            "package_hash" => Key::Hash(router.package_hash()),
+            "amount_in" => AMOUNT / 2,
+            "amount_out_min" => U256::from(0),
+            "path" => vec![token_a.to_formatted_string(), token_b.to_formatted_string()],
+            "to" => Key::Account(owner),
+            "deadline" => U256::from(t1 + 1000 * (30 * 60)),
+        },
+        t1,
+    );
+    let amount_out = consult(
+        &env,
+        owner,
+        &library_contract,
+        token_a,
+        1000.into(),
+        token_b,
+        t1,
+    );
+    assert_eq!(amount_out, 1000.into());
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(114)")]
+fn test_oracle_consult_without_history() {
+    let (env, owner, library_contract, _, pair, router, token1, token2) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let t0 = observe_pool(
+        &env,
+        owner,
+        &library_contract,
+        &pair,
+        &router,
+        token_a,
+        token_b,
+    );
+    consult(
+        &env,
+        owner,
+        &library_contract,
+        token_a,
+        1000.into(),
+        token_b,
+        t0 + oracle_period_size(),
+    );
+}
+
+// an install without the oracle arguments, as before the oracle, keeps the pricing entry points
+#[test]
+#[should_panic(expected = "ApiError::User(142)")]
+fn test_oracle_update_without_factory() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let now = now();
+    let (token1, token2, _) = deploy_dummy_tokens(&env, Some(owner), now);
+    let library_contract = LibraryInstance::instance(TestContract::new(
+        &env,
+        "uniswap-v2-library.wasm",
+        "library",
+        owner,
+        runtime_args! {},
+        now,
+    ));
+    library_contract.quote(owner, 1000.into(), 1000.into(), 2000.into());
+    library_contract.update(
+        owner,
+        Key::Hash(token1.package_hash()),
+        Key::Hash(token2.package_hash()),
+        now,
+    );
+}
+
+// Properties of the pricing math, over every reserve a pair can hold.
+
+// Reserves up to U128::MAX, weighted towards small pools where rounding matters most.
+fn reserve() -> impl Strategy<Value = U256> {
+    prop_oneof![1u128..1_000_000, 1u128..=u128::MAX].prop_map(U256::from)
+}
+
+// Amounts a pair's balance can hold, from the same range as its reserves.
+fn amount() -> impl Strategy<Value = U256> {
+    reserve()
+}
+
+fn hops() -> impl Strategy<Value = Vec<(U256, U256)>> {
+    prop::collection::vec((reserve(), reserve()), 1..4)
+}
+
+// The pair's k check after the 0.3% fee:
+// (r_in * 1000 + a_in * 997) * (r_out - a_out) >= r_in * r_out * 1000
+fn k_holds(amount_in: U256, amount_out: U256, reserve_in: U256, reserve_out: U256) -> bool {
+    (wide(reserve_in) * 1000 + wide(amount_in) * 997) * wide(reserve_out - amount_out)
+        >= wide(reserve_in) * wide(reserve_out) * 1000
+}
+
+proptest! {
+    #[test]
+    fn swapping_there_and_back_never_returns_more_than_paid(
+        amount_in in amount(),
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+    ) {
+        let amount_out = math::try_get_amount_out(amount_in, reserve_in, reserve_out).unwrap();
+        prop_assume!(!amount_out.is_zero());
+        let amount_back = math::try_get_amount_out(
+            amount_out,
+            reserve_out - amount_out,
+            reserve_in + amount_in,
+        )
+        .unwrap();
+        prop_assert!(amount_back <= amount_in);
+    }
+
+    #[test]
+    fn amount_in_buys_at_least_the_amount_out(
+        amount_out in amount(),
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+    ) {
+        prop_assume!(amount_out < reserve_out);
+        let amount_in = match math::try_get_amount_in(amount_out, reserve_in, reserve_out) {
+            Ok(amount_in) => amount_in,
+            Err(error) => {
+                prop_assert!(matches!(error, errors::Errors::ArithmeticOverflow));
+                return Ok(());
+            }
+        };
+        let amount_bought = math::try_get_amount_out(amount_in, reserve_in, reserve_out).unwrap();
+        prop_assert!(amount_bought >= amount_out);
+    }
+
+    #[test]
+    fn amount_out_never_costs_more_than_paid(
+        amount_in in amount(),
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+    ) {
+        let amount_out = math::try_get_amount_out(amount_in, reserve_in, reserve_out).unwrap();
+        prop_assume!(!amount_out.is_zero());
+        let amount_required = math::try_get_amount_in(amount_out, reserve_in, reserve_out).unwrap();
+        prop_assert!(amount_required <= amount_in);
+    }
+
+    #[test]
+    fn amounts_out_are_monotonic_in_the_input(
+        amount_a in amount(),
+        amount_b in amount(),
+        reserves in hops(),
+    ) {
+        let (smaller, larger) = (amount_a.min(amount_b), amount_a.max(amount_b));
+        if let Ok(smaller) = math::try_get_amounts_out(smaller, &reserves) {
+            let larger = math::try_get_amounts_out(larger, &reserves).unwrap();
+            for (smaller, larger) in smaller.iter().zip(larger.iter()) {
+                prop_assert!(smaller <= larger);
+            }
+        }
+    }
+
+    #[test]
+    fn amounts_in_are_monotonic_in_the_output(
+        amount_a in amount(),
+        amount_b in amount(),
+        reserves in hops(),
+    ) {
+        let (smaller, larger) = (amount_a.min(amount_b), amount_a.max(amount_b));
+        if let Ok(larger) = math::try_get_amounts_in(larger, &reserves) {
+            let smaller = math::try_get_amounts_in(smaller, &reserves).unwrap();
+            for (smaller, larger) in smaller.iter().zip(larger.iter()) {
+                prop_assert!(smaller <= larger);
+            }
+        }
+    }
+
+    #[test]
+    fn quote_is_symmetric_up_to_rounding(
+        amount_a in amount(),
+        reserve_a in reserve(),
+        reserve_b in reserve(),
+    ) {
+        let amount_b = math::try_quote(amount_a, reserve_a, reserve_b).unwrap();
+        prop_assume!(!amount_b.is_zero());
+        let amount_a_back = math::try_quote(amount_b, reserve_b, reserve_a).unwrap();
+        // each quote rounds down by less than one unit of b, worth reserve_a / reserve_b of a
+        prop_assert!(amount_a_back <= amount_a);
+        prop_assert!(amount_a - amount_a_back <= reserve_a / reserve_b + 1);
+    }
+
+    #[test]
+    fn k_does_not_decrease_after_fee(
+        amount in amount(),
+        reserve_in in reserve(),
+        reserve_out in reserve(),
+    ) {
+        let amount_out = math::try_get_amount_out(amount, reserve_in, reserve_out).unwrap();
+        prop_assert!(k_holds(amount, amount_out, reserve_in, reserve_out));
+        if let Ok(amount_in) = math::try_get_amount_in(amount, reserve_in, reserve_out) {
+            prop_assert!(k_holds(amount_in, amount, reserve_in, reserve_out));
+        }
+    }
+
+    #[test]
+    fn pricing_never_panics(
+        amount in any::<[u8; 32]>().prop_map(|bytes| U256::from_little_endian(&bytes)),
+        reserve_a in prop_oneof![Just(0), Just(u128::MAX), any::<u128>()].prop_map(U256::from),
+        reserve_b in prop_oneof![Just(0), Just(u128::MAX), any::<u128>()].prop_map(U256::from),
+    ) {
+        let _ = math::try_quote(amount, reserve_a, reserve_b);
+        let _ = math::try_get_amount_out(amount, reserve_a, reserve_b);
+        let _ = math::try_get_amount_in(amount, reserve_a, reserve_b);
+        let hops = [(reserve_a, reserve_b), (reserve_b, reserve_a)];
+        let _ = math::try_get_amounts_out(amount, &hops);
+        let _ = math::try_get_amounts_in(amount, &hops);
+    }
+}
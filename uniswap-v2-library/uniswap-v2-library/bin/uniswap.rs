@@ -5,6 +5,9 @@ use std::collections::BTreeSet;
 
 use uniswap_v2_library_crate::{
     contract_api::{runtime, storage},
+    entry_points::get_entry_points,
+    functions::get_optional_named_arg,
+    oracle::{DEFAULT_GRANULARITY, DEFAULT_WINDOW_SIZE},
     unwrap_or_revert::UnwrapOrRevert,
     *,
 };
@@ -17,9 +20,18 @@ impl ContractContext<OnChainContractStorage> for Uniswap {
     }
 }
 impl UniswapV2Library<OnChainContractStorage> for Uniswap {}
+impl SlidingWindowOracle<OnChainContractStorage> for Uniswap {}
 impl Uniswap {
-    fn constructor(&self, contract_hash: ContractHash, package_hash: ContractPackageHash) {
+    fn constructor(
+        &self,
+        contract_hash: ContractHash,
+        package_hash: ContractPackageHash,
+        factory: Option<Key>,
+        window_size: u64,
+        granularity: u8,
+    ) {
         UniswapV2Library::init(self, contract_hash, package_hash);
+        SlidingWindowOracle::init_oracle(self, factory, window_size, granularity);
     }
 }
 
@@ -27,7 +39,16 @@ impl Uniswap {
 fn constructor() {
     let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
     let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
-    Uniswap::default().constructor(contract_hash, package_hash);
+    let factory: Option<Key> = runtime::get_named_arg("factory");
+    let window_size: u64 = runtime::get_named_arg("window_size");
+    let granularity: u8 = runtime::get_named_arg("granularity");
+    Uniswap::default().constructor(
+        contract_hash,
+        package_hash,
+        factory,
+        window_size,
+        granularity,
+    );
 }
 
 #[no_mangle]
@@ -133,110 +154,41 @@ fn pair_for() {
     runtime::ret(CLValue::from_t(ret).unwrap_or_revert())
 }
 
-fn get_entry_points() -> EntryPoints {
-    let mut entry_points = EntryPoints::new();
-
-    entry_points.add_entry_point(EntryPoint::new(
-        "constructor",
-        vec![
-            Parameter::new("contract_hash", ContractHash::cl_type()),
-            Parameter::new("package_hash", ContractPackageHash::cl_type()),
-        ],
-        <()>::cl_type(),
-        EntryPointAccess::Groups(vec![Group::new("constructor")]),
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "sort_tokens",
-        vec![
-            Parameter::new("token_a", Key::cl_type()),
-            Parameter::new("token_b", Key::cl_type()),
-        ],
-        CLType::Tuple2([
-            Box::new(ContractPackageHash::cl_type()),
-            Box::new(ContractPackageHash::cl_type()),
-        ]),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "get_reserves",
-        vec![
-            Parameter::new("factory", Key::cl_type()),
-            Parameter::new("token_a", Key::cl_type()),
-            Parameter::new("token_b", Key::cl_type()),
-        ],
-        CLType::Tuple2([Box::new(CLType::U128), Box::new(CLType::U128)]),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "quote",
-        vec![
-            Parameter::new("amount_a", U256::cl_type()),
-            Parameter::new("reserve_a", U128::cl_type()),
-            Parameter::new("reserve_b", U128::cl_type()),
-        ],
-        U256::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "get_amount_out",
-        vec![
-            Parameter::new("amount_in", U256::cl_type()),
-            Parameter::new("reserve_in", U256::cl_type()),
-            Parameter::new("reserve_out", U256::cl_type()),
-        ],
-        U256::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "get_amount_in",
-        vec![
-            Parameter::new("amount_out", U256::cl_type()),
-            Parameter::new("reserve_in", U256::cl_type()),
-            Parameter::new("reserve_out", U256::cl_type()),
-        ],
-        U256::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "get_amounts_out",
-        vec![
-            Parameter::new("factory", Key::cl_type()),
-            Parameter::new("amount_in", U256::cl_type()),
-            Parameter::new("path", CLType::List(Box::new(Key::cl_type()))),
-        ],
-        CLType::List(Box::new(U256::cl_type())),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "get_amounts_in",
-        vec![
-            Parameter::new("factory", Key::cl_type()),
-            Parameter::new("amount_out", U256::cl_type()),
-            Parameter::new("path", CLType::List(Box::new(Key::cl_type()))),
-        ],
-        CLType::List(Box::new(U256::cl_type())),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "pair_for",
-        vec![
-            Parameter::new("factory", Key::cl_type()),
-            Parameter::new("token_a", Key::cl_type()),
-            Parameter::new("token_b", Key::cl_type()),
-        ],
-        CLType::Key,
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points
+#[no_mangle]
+fn observation_index_of() {
+    let timestamp: u64 = runtime::get_named_arg("timestamp");
+
+    let ret: u8 = Uniswap::default().observation_index_of(timestamp);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert())
+}
+
+#[no_mangle]
+// returns the pair's cumulative prices and the block timestamp they were computed at
+fn current_cumulative_prices() {
+    let pair: Key = runtime::get_named_arg("pair");
+
+    let ret: (U256, U256, u64) = Uniswap::default().current_cumulative_prices(pair);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert())
+}
+
+#[no_mangle]
+// records an observation of the pair's cumulative prices, at most once per period
+fn update() {
+    let token_a: Key = runtime::get_named_arg("token_a");
+    let token_b: Key = runtime::get_named_arg("token_b");
+
+    Uniswap::default().update(token_a, token_b);
+}
+
+#[no_mangle]
+// returns the amount out of token_out for amount_in of token_in at the time weighted average price
+fn consult() {
+    let token_in: Key = runtime::get_named_arg("token_in");
+    let amount_in: U256 = runtime::get_named_arg("amount_in");
+    let token_out: Key = runtime::get_named_arg("token_out");
+
+    let amount_out: U256 = Uniswap::default().consult(token_in, amount_in, token_out);
+    runtime::ret(CLValue::from_t(amount_out).unwrap_or_revert())
 }
 
 #[no_mangle]
@@ -251,10 +203,16 @@ fn call() {
         let (contract_hash, _) =
             storage::add_contract_version(package_hash, get_entry_points(), Default::default());
 
-        // Prepare constructor args
+        // Prepare constructor args, the oracle ones are optional so older install scripts still work
+        let factory: Option<Key> = get_optional_named_arg("factory");
+        let window_size: u64 = get_optional_named_arg("window_size").unwrap_or(DEFAULT_WINDOW_SIZE);
+        let granularity: u8 = get_optional_named_arg("granularity").unwrap_or(DEFAULT_GRANULARITY);
         let constructor_args = runtime_args! {
             "contract_hash" => contract_hash,
-            "package_hash" => package_hash
+            "package_hash" => package_hash,
+            "factory" => factory,
+            "window_size" => window_size,
+            "granularity" => granularity
         };
 
         // Add the constructor group to the package hash with a single URef.
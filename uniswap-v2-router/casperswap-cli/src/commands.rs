@@ -0,0 +1,337 @@
+use crate::{
+    deploy::{deploy_json, DeployOptions},
+    error::CliError,
+    flags::Flags,
+    snapshot::Snapshot,
+};
+use common::{errors::Errors, runtime_args, ContractPackageHash, Key, RuntimeArgs, U256};
+use serde_json::{json, Value};
+use std::{convert::TryFrom, fs};
+use uniswap_v2_router_sdk::{Path, Payload, RouterCall, SessionCall};
+
+// Flags of every subcommand that builds a deploy.
+const DEPLOY_FLAGS: [&str; 6] = [
+    "chain-name",
+    "payment-amount",
+    "session-wasm",
+    "output",
+    "deadline",
+    "valid-for",
+];
+
+// User errors are reported by the node as `ApiError::User`, offset by this value.
+const USER_ERROR_OFFSET: u32 = 65536;
+
+fn deploy_flags(args: &[String], flags: &[&str], switches: &[&str]) -> Result<Flags, CliError> {
+    let flags: Vec<&str> = flags.iter().chain(DEPLOY_FLAGS.iter()).copied().collect();
+    Flags::parse(args, &flags, switches)
+}
+
+// Amounts along `--path` for `--amount-in` or `--amount-out`, from the reserves of `--snapshot`.
+pub fn quote(args: &[String]) -> Result<String, CliError> {
+    let flags: Flags = Flags::parse(args, &["snapshot", "path", "amount-in", "amount-out"], &[])?;
+    let snapshot: Snapshot = Snapshot::load(flags.required("snapshot")?)?;
+    let path: Path = flags.path("path")?;
+    let amounts: Vec<U256> = match (
+        flags.optional_u256("amount-in")?,
+        flags.optional_u256("amount-out")?,
+    ) {
+        (Some(_), Some(_)) => return Err(CliError::ConflictingFlags("amount-in", "amount-out")),
+        (Some(amount_in), None) => snapshot.amounts_out(amount_in, &path)?,
+        (None, Some(amount_out)) => snapshot.amounts_in(amount_out, &path)?,
+        (None, None) => return Err(CliError::MissingFlag("amount-in")),
+    };
+    let amounts: Vec<String> = amounts.iter().map(U256::to_string).collect();
+    Ok(pretty(
+        &json!({ "path": path.to_strings(), "amounts": amounts }),
+    ))
+}
+
+// A swap along `--path`, exact in with `--amount-in` and `--amount-out-min` or exact out with
+// `--amount-out` and `--amount-in-max`. `--cspr-in` and `--cspr-out` swap from or to cspr.
+pub fn swap(args: &[String]) -> Result<String, CliError> {
+    let flags: Flags = deploy_flags(
+        args,
+        &[
+            "router",
+            "path",
+            "to",
+            "amount-in",
+            "amount-out-min",
+            "amount-out",
+            "amount-in-max",
+        ],
+        &["cspr-in", "cspr-out", "approve"],
+    )?;
+    if flags.switch("cspr-in") && flags.switch("cspr-out") {
+        return Err(CliError::ConflictingFlags("cspr-in", "cspr-out"));
+    }
+    let path: Path = flags.path("path")?;
+    let to: Key = flags.key("to")?;
+    let deadline = flags.deadline()?;
+    let exact_in: bool = match (
+        flags.optional("amount-in").is_some(),
+        flags.optional("amount-out").is_some(),
+    ) {
+        (true, true) => return Err(CliError::ConflictingFlags("amount-in", "amount-out")),
+        (false, false) => return Err(CliError::MissingFlag("amount-in")),
+        (exact_in, _) => exact_in,
+    };
+    let call: RouterCall = if exact_in {
+        let amount_in: U256 = flags.u256("amount-in")?;
+        let amount_out_min: U256 = flags.u256("amount-out-min")?;
+        if flags.switch("cspr-in") {
+            RouterCall::SwapExactCsprForTokens {
+                amount_in,
+                amount_out_min,
+                path,
+                to,
+                deadline,
+            }
+        } else if flags.switch("cspr-out") {
+            RouterCall::SwapExactTokensForCspr {
+                amount_in,
+                amount_out_min,
+                path,
+                to,
+                deadline,
+            }
+        } else {
+            RouterCall::SwapExactTokensForTokens {
+                amount_in,
+                amount_out_min,
+                path,
+                to,
+                deadline,
+            }
+        }
+    } else {
+        let amount_out: U256 = flags.u256("amount-out")?;
+        let amount_in_max: U256 = flags.u256("amount-in-max")?;
+        if flags.switch("cspr-in") {
+            RouterCall::SwapCsprForExactTokens {
+                amount_out,
+                amount_in_max,
+                path,
+                to,
+                deadline,
+            }
+        } else if flags.switch("cspr-out") {
+            RouterCall::SwapTokensForExactCspr {
+                amount_out,
+                amount_in_max,
+                path,
+                to,
+                deadline,
+            }
+        } else {
+            RouterCall::SwapTokensForExactTokens {
+                amount_out,
+                amount_in_max,
+                path,
+                to,
+                deadline,
+            }
+        }
+    };
+    router_deploy(&flags, call, None)
+}
+
+// Liquidity for `--token-a` and `--token-b`, or for `--token-a` and cspr with `--cspr`.
+pub fn add_liquidity(args: &[String]) -> Result<String, CliError> {
+    let flags: Flags = deploy_flags(
+        args,
+        &[
+            "router",
+            "token-a",
+            "token-b",
+            "amount-a-desired",
+            "amount-b-desired",
+            "amount-a-min",
+            "amount-b-min",
+            "to",
+            "pair",
+        ],
+        &["cspr", "approve"],
+    )?;
+    let pair: Option<Key> = flags.optional_key("pair")?;
+    let call: RouterCall = if flags.switch("cspr") {
+        if flags.optional("token-b").is_some() {
+            return Err(CliError::ConflictingFlags("cspr", "token-b"));
+        }
+        RouterCall::AddLiquidityCspr {
+            token: flags.key("token-a")?,
+            amount_token_desired: flags.u256("amount-a-desired")?,
+            amount_cspr_desired: flags.u256("amount-b-desired")?,
+            amount_token_min: flags.u256("amount-a-min")?,
+            amount_cspr_min: flags.u256("amount-b-min")?,
+            to: flags.key("to")?,
+            deadline: flags.deadline()?,
+            pair,
+        }
+    } else {
+        RouterCall::AddLiquidity {
+            token_a: flags.key("token-a")?,
+            token_b: flags.key("token-b")?,
+            amount_a_desired: flags.u256("amount-a-desired")?,
+            amount_b_desired: flags.u256("amount-b-desired")?,
+            amount_a_min: flags.u256("amount-a-min")?,
+            amount_b_min: flags.u256("amount-b-min")?,
+            to: flags.key("to")?,
+            deadline: flags.deadline()?,
+            pair,
+        }
+    };
+    router_deploy(&flags, call, None)
+}
+
+// Burns `--liquidity` of the pair of `--token-a` and `--token-b`, or of `--token-a` and cspr
+// with `--cspr`. Approving the router needs the `--pair` the liquidity tokens belong to.
+pub fn remove_liquidity(args: &[String]) -> Result<String, CliError> {
+    let flags: Flags = deploy_flags(
+        args,
+        &[
+            "router",
+            "token-a",
+            "token-b",
+            "liquidity",
+            "amount-a-min",
+            "amount-b-min",
+            "to",
+            "pair",
+        ],
+        &["cspr", "approve"],
+    )?;
+    let call: RouterCall = if flags.switch("cspr") {
+        if flags.optional("token-b").is_some() {
+            return Err(CliError::ConflictingFlags("cspr", "token-b"));
+        }
+        RouterCall::RemoveLiquidityCspr {
+            token: flags.key("token-a")?,
+            liquidity: flags.u256("liquidity")?,
+            amount_token_min: flags.u256("amount-a-min")?,
+            amount_cspr_min: flags.u256("amount-b-min")?,
+            to: flags.key("to")?,
+            deadline: flags.deadline()?,
+        }
+    } else {
+        RouterCall::RemoveLiquidity {
+            token_a: flags.key("token-a")?,
+            token_b: flags.key("token-b")?,
+            liquidity: flags.u256("liquidity")?,
+            amount_a_min: flags.u256("amount-a-min")?,
+            amount_b_min: flags.u256("amount-b-min")?,
+            to: flags.key("to")?,
+            deadline: flags.deadline()?,
+        }
+    };
+    router_deploy(&flags, call, flags.optional_key("pair")?)
+}
+
+// Whitelists `--router` in the `--factory` so that it may create pairs, or queues adding
+// (removing with `--remove`) an `--account` to the router's whitelist at `--eta`.
+pub fn whitelist(args: &[String]) -> Result<String, CliError> {
+    let flags: Flags = deploy_flags(args, &["factory", "router", "account", "eta"], &["remove"])?;
+    if flags.optional("factory").is_some() {
+        if flags.optional("account").is_some() {
+            return Err(CliError::ConflictingFlags("factory", "account"));
+        }
+        let factory: ContractPackageHash = flags.package_hash("factory")?;
+        let router: ContractPackageHash = flags.package_hash("router")?;
+        let args: RuntimeArgs = runtime_args! {
+            "white_list" => Key::Hash(router.value())
+        };
+        return write_deploy(
+            &flags,
+            &Payload::StoredContract {
+                package_hash: factory,
+                entry_point: "set_white_list".into(),
+                args,
+            },
+        );
+    }
+    let action: &str = if flags.switch("remove") {
+        "remove_from_whitelist"
+    } else {
+        "add_to_whitelist"
+    };
+    let eta: u64 = flags.u64("eta")?;
+    let call: RouterCall = RouterCall::QueueOperation {
+        action: action.to_string(),
+        target: flags.key("account")?,
+        value: 0,
+        eta,
+    };
+    router_deploy(&flags, call, None)
+}
+
+// Name and description of a user error code, as reported by the node (`User error: 64`) or
+// as `ApiError::User` codes offset by 65536.
+pub fn decode_error(args: &[String]) -> Result<String, CliError> {
+    let code: &String = args.first().ok_or(CliError::MissingFlag("code"))?;
+    if args.len() > 1 {
+        return Err(CliError::UnknownFlag(args[1].clone()));
+    }
+    let invalid = || CliError::InvalidValue {
+        flag: "code".into(),
+        value: code.clone(),
+    };
+    let value: u32 = code.parse().map_err(|_| invalid())?;
+    let value: u32 = value.checked_sub(USER_ERROR_OFFSET).unwrap_or(value);
+    let error: Errors = u16::try_from(value)
+        .ok()
+        .and_then(Errors::from_code)
+        .ok_or_else(invalid)?;
+    Ok(format!(
+        "{:?} ({}): {}",
+        error,
+        error.code(),
+        error.description()
+    ))
+}
+
+// Deploy of a router call, through the session code router when `--session-wasm` is given.
+fn router_deploy(flags: &Flags, call: RouterCall, pair: Option<Key>) -> Result<String, CliError> {
+    let router: ContractPackageHash = flags.package_hash("router")?;
+    let payload: Payload = if flags.optional("session-wasm").is_some() {
+        let mut session: SessionCall = SessionCall::new(call);
+        if flags.switch("approve") {
+            session = session.approve();
+        }
+        if let Some(pair) = pair {
+            session = session.pair(pair);
+        }
+        session.payload(router)?
+    } else if flags.switch("approve") {
+        return Err(CliError::MissingFlag("session-wasm"));
+    } else {
+        call.payload(router)?
+    };
+    write_deploy(flags, &payload)
+}
+
+// Prints the deploy, or writes it to `--output`.
+fn write_deploy(flags: &Flags, payload: &Payload) -> Result<String, CliError> {
+    let session_wasm: Option<Vec<u8>> = flags
+        .optional("session-wasm")
+        .map(|file| fs::read(file).map_err(|error| CliError::Io(format!("{}: {}", file, error))))
+        .transpose()?;
+    let options: DeployOptions = DeployOptions {
+        chain_name: flags.required("chain-name")?.into(),
+        payment_amount: flags.u512("payment-amount")?,
+        session_wasm,
+    };
+    let deploy: String = pretty(&deploy_json(payload, &options)?);
+    match flags.optional("output") {
+        Some(file) => {
+            fs::write(file, &deploy)
+                .map_err(|error| CliError::Io(format!("{}: {}", file, error)))?;
+            Ok(format!("deploy written to {}", file))
+        }
+        None => Ok(deploy),
+    }
+}
+
+fn pretty(json: &Value) -> String {
+    serde_json::to_string_pretty(json).expect("json values serialize")
+}
@@ -0,0 +1,61 @@
+use crate::error::CliError;
+use common::{CLValue, RuntimeArgs, U512};
+use serde_json::{json, Value};
+use uniswap_v2_router_sdk::Payload;
+
+// What a deploy pays and runs, besides the account and timestamp of its header which are set
+// when it is signed.
+pub struct DeployOptions {
+    pub chain_name: String,
+    pub payment_amount: U512,
+    // wasm of the session code router, needed by session payloads
+    pub session_wasm: Option<Vec<u8>>,
+}
+
+// The chain name, payment and session of a deploy, with the executable items in the json format
+// of casper-node deploys.
+pub fn deploy_json(payload: &Payload, options: &DeployOptions) -> Result<Value, CliError> {
+    let mut payment_args: RuntimeArgs = RuntimeArgs::new();
+    payment_args
+        .insert("amount", options.payment_amount)
+        .expect("payment amount is serializable");
+    let session: Value = match payload {
+        Payload::Session { args } => {
+            let wasm: &Vec<u8> = options
+                .session_wasm
+                .as_ref()
+                .ok_or(CliError::MissingFlag("session-wasm"))?;
+            json!({ "ModuleBytes": { "module_bytes": hex::encode(wasm), "args": args_json(args) } })
+        }
+        Payload::StoredContract {
+            package_hash,
+            entry_point,
+            args,
+        } => json!({
+            "StoredVersionedContractByHash": {
+                "hash": hex::encode(package_hash.value()),
+                "version": null,
+                "entry_point": entry_point,
+                "args": args_json(args),
+            }
+        }),
+    };
+    Ok(json!({
+        "chain_name": options.chain_name,
+        "payment": { "ModuleBytes": { "module_bytes": "", "args": args_json(&payment_args) } },
+        "session": session,
+    }))
+}
+
+// Named args as `[name, {"cl_type": ..., "bytes": ...}]`, the bytes hex encoded.
+fn args_json(args: &RuntimeArgs) -> Value {
+    args.named_args()
+        .map(|arg| {
+            let value: &CLValue = arg.cl_value();
+            json!([
+                arg.name(),
+                { "cl_type": value.cl_type(), "bytes": hex::encode(value.inner_bytes()) }
+            ])
+        })
+        .collect()
+}
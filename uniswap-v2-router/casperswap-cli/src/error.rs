@@ -0,0 +1,71 @@
+use common::errors::Errors;
+use std::fmt;
+use uniswap_v2_router_sdk::SdkError;
+
+#[derive(Debug)]
+pub enum CliError {
+    /// no subcommand of that name
+    UnknownCommand(String),
+    /// a flag the subcommand does not take
+    UnknownFlag(String),
+    /// a required flag is missing
+    MissingFlag(&'static str),
+    /// a flag that takes a value is the last argument
+    MissingValue(String),
+    /// a flag value that does not parse as the flag's type
+    InvalidValue { flag: String, value: String },
+    /// flags that select different calls were given together
+    ConflictingFlags(&'static str, &'static str),
+    /// the snapshot has no reserves for the pair of the two tokens
+    PairNotInSnapshot(String, String),
+    /// the snapshot file is not in the expected format
+    InvalidSnapshot(String),
+    /// a file could not be read or written
+    Io(String),
+    /// the call was rejected before building a deploy
+    Sdk(SdkError),
+    /// the library math reverts with this error for the quoted amounts
+    Quote(Errors),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::UnknownCommand(command) => write!(f, "unknown command `{}`", command),
+            CliError::UnknownFlag(flag) => write!(f, "unknown flag `--{}`", flag),
+            CliError::MissingFlag(flag) => write!(f, "missing flag `--{}`", flag),
+            CliError::MissingValue(flag) => write!(f, "missing value for `--{}`", flag),
+            CliError::InvalidValue { flag, value } => {
+                write!(f, "invalid value `{}` for `--{}`", value, flag)
+            }
+            CliError::ConflictingFlags(a, b) => {
+                write!(f, "`--{}` and `--{}` can't be used together", a, b)
+            }
+            CliError::PairNotInSnapshot(token_a, token_b) => {
+                write!(
+                    f,
+                    "no reserves for {} and {} in the snapshot",
+                    token_a, token_b
+                )
+            }
+            CliError::InvalidSnapshot(reason) => write!(f, "invalid snapshot: {}", reason),
+            CliError::Io(reason) => write!(f, "{}", reason),
+            CliError::Sdk(error) => write!(f, "{}", error),
+            CliError::Quote(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<SdkError> for CliError {
+    fn from(error: SdkError) -> CliError {
+        CliError::Sdk(error)
+    }
+}
+
+impl From<Errors> for CliError {
+    fn from(error: Errors) -> CliError {
+        CliError::Quote(error)
+    }
+}
@@ -0,0 +1,124 @@
+use crate::error::CliError;
+use common::{ContractPackageHash, Key, U256, U512};
+use std::collections::{BTreeMap, BTreeSet};
+use uniswap_v2_router_sdk::{Deadline, Path};
+
+// Validity of a deploy when neither `--deadline` nor `--valid-for` is given, 30 minutes.
+pub const DEFAULT_VALID_FOR: u64 = 30 * 60 * 1000;
+
+// `--name value` flags and `--name` switches of a subcommand.
+pub struct Flags {
+    values: BTreeMap<String, String>,
+    switches: BTreeSet<String>,
+}
+
+impl Flags {
+    // Every argument must be one of the subcommand's `flags`, followed by its value, or one of
+    // its `switches`.
+    pub fn parse(args: &[String], flags: &[&str], switches: &[&str]) -> Result<Flags, CliError> {
+        let mut values: BTreeMap<String, String> = BTreeMap::new();
+        let mut set: BTreeSet<String> = BTreeSet::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name: &str = arg
+                .strip_prefix("--")
+                .ok_or_else(|| CliError::UnknownFlag(arg.clone()))?;
+            if switches.contains(&name) {
+                set.insert(name.into());
+            } else if flags.contains(&name) {
+                let value: &String = args
+                    .next()
+                    .ok_or_else(|| CliError::MissingValue(name.into()))?;
+                values.insert(name.into(), value.clone());
+            } else {
+                return Err(CliError::UnknownFlag(name.into()));
+            }
+        }
+        Ok(Flags {
+            values,
+            switches: set,
+        })
+    }
+
+    pub fn switch(&self, name: &str) -> bool {
+        self.switches.contains(name)
+    }
+
+    pub fn optional(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    pub fn required(&self, name: &'static str) -> Result<&str, CliError> {
+        self.optional(name).ok_or(CliError::MissingFlag(name))
+    }
+
+    fn parse_optional<T>(
+        &self,
+        name: &str,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Result<Option<T>, CliError> {
+        self.optional(name)
+            .map(|value| {
+                parse(value).ok_or_else(|| CliError::InvalidValue {
+                    flag: name.into(),
+                    value: value.into(),
+                })
+            })
+            .transpose()
+    }
+
+    pub fn optional_u256(&self, name: &str) -> Result<Option<U256>, CliError> {
+        self.parse_optional(name, |value| U256::from_dec_str(value).ok())
+    }
+
+    pub fn u256(&self, name: &'static str) -> Result<U256, CliError> {
+        self.optional_u256(name)?.ok_or(CliError::MissingFlag(name))
+    }
+
+    pub fn u512(&self, name: &'static str) -> Result<U512, CliError> {
+        self.parse_optional(name, |value| U512::from_dec_str(value).ok())?
+            .ok_or(CliError::MissingFlag(name))
+    }
+
+    pub fn u64(&self, name: &'static str) -> Result<u64, CliError> {
+        self.parse_optional(name, |value| value.parse().ok())?
+            .ok_or(CliError::MissingFlag(name))
+    }
+
+    pub fn optional_key(&self, name: &str) -> Result<Option<Key>, CliError> {
+        self.parse_optional(name, |value| Key::from_formatted_str(value).ok())
+    }
+
+    pub fn key(&self, name: &'static str) -> Result<Key, CliError> {
+        self.optional_key(name)?.ok_or(CliError::MissingFlag(name))
+    }
+
+    // A contract package given as its `hash-...` key.
+    pub fn package_hash(&self, name: &'static str) -> Result<ContractPackageHash, CliError> {
+        let key: Key = self.key(name)?;
+        key.into_hash()
+            .map(ContractPackageHash::from)
+            .ok_or_else(|| CliError::InvalidValue {
+                flag: name.into(),
+                value: key.to_formatted_string(),
+            })
+    }
+
+    // Tokens separated by commas, e.g. `hash-...,hash-...`.
+    pub fn path(&self, name: &'static str) -> Result<Path, CliError> {
+        let tokens: Vec<&str> = self.required(name)?.split(',').collect();
+        Ok(Path::parse(&tokens)?)
+    }
+
+    // `--deadline` as a block time in milliseconds, or `--valid-for` milliseconds from now.
+    pub fn deadline(&self) -> Result<Deadline, CliError> {
+        let deadline: Option<u64> = self.parse_optional("deadline", |value| value.parse().ok())?;
+        let valid_for: Option<u64> =
+            self.parse_optional("valid-for", |value| value.parse().ok())?;
+        match (deadline, valid_for) {
+            (Some(_), Some(_)) => Err(CliError::ConflictingFlags("deadline", "valid-for")),
+            (Some(deadline), None) => Ok(Deadline::At(deadline)),
+            (None, valid_for) => Ok(Deadline::from_now(valid_for.unwrap_or(DEFAULT_VALID_FOR))),
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// Offline tool for the router: quotes from a snapshot of pair reserves and deploys of router
+// calls, printed as json to be signed and sent with the account's own tooling.
+mod commands;
+mod deploy;
+pub mod error;
+mod flags;
+mod snapshot;
+
+pub use error::CliError;
+
+pub const USAGE: &str = "\
+usage: casperswap-cli <command> [flags]
+
+commands:
+  quote             --snapshot FILE --path TOKEN,TOKEN[,..] (--amount-in N | --amount-out N)
+  swap              --router HASH --path TOKEN,TOKEN[,..] --to KEY
+                    (--amount-in N --amount-out-min N | --amount-out N --amount-in-max N)
+                    [--cspr-in | --cspr-out] [--approve]
+  add-liquidity     --router HASH --token-a KEY (--token-b KEY | --cspr) --to KEY
+                    --amount-a-desired N --amount-b-desired N --amount-a-min N --amount-b-min N
+                    [--pair KEY] [--approve]
+  remove-liquidity  --router HASH --token-a KEY (--token-b KEY | --cspr) --to KEY
+                    --liquidity N --amount-a-min N --amount-b-min N [--pair KEY --approve]
+  whitelist         --factory HASH --router HASH
+                    | --router HASH --account KEY --eta MILLIS [--remove]
+  decode-error      CODE
+
+deploy flags of every command but quote and decode-error:
+  --chain-name NAME --payment-amount MOTES [--session-wasm FILE] [--output FILE]
+  [--deadline MILLIS | --valid-for MILLIS]
+
+Calls go to the router directly unless --session-wasm is given, in which case they go through
+the session code router. Cspr calls and --approve need the session code router.";
+
+// Runs a command given the arguments after the binary's name, returning what to print.
+pub fn run(args: &[String]) -> Result<String, CliError> {
+    let (command, args): (&String, &[String]) = match args.split_first() {
+        Some(split) => split,
+        None => return Ok(USAGE.into()),
+    };
+    match command.as_str() {
+        "quote" => commands::quote(args),
+        "swap" => commands::swap(args),
+        "add-liquidity" => commands::add_liquidity(args),
+        "remove-liquidity" => commands::remove_liquidity(args),
+        "whitelist" => commands::whitelist(args),
+        "decode-error" => commands::decode_error(args),
+        "help" | "--help" | "-h" => Ok(USAGE.into()),
+        _ => Err(CliError::UnknownCommand(command.clone())),
+    }
+}
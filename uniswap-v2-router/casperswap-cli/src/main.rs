@@ -0,0 +1,13 @@
+use std::{env, process};
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match casperswap_cli::run(&args) {
+        Ok(output) => println!("{}", output),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            eprintln!("run `casperswap-cli help` for usage");
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::error::CliError;
+use common::{Key, U256};
+use serde_json::Value;
+use std::fs;
+use uniswap_v2_library_crate::math;
+use uniswap_v2_router_sdk::Path;
+
+// Reserves of pairs saved to a local file, so that quotes need no node:
+//
+// {"pairs": [{"token0": "hash-...", "token1": "hash-...", "reserve0": "1000", "reserve1": "2000"}]}
+//
+// Reserves are decimal strings as they may not fit a json number.
+pub struct Snapshot {
+    pairs: Vec<PairReserves>,
+}
+
+struct PairReserves {
+    token0: Key,
+    token1: Key,
+    reserve0: U256,
+    reserve1: U256,
+}
+
+impl Snapshot {
+    pub fn load(file: &str) -> Result<Snapshot, CliError> {
+        let contents: String = fs::read_to_string(file)
+            .map_err(|error| CliError::Io(format!("{}: {}", file, error)))?;
+        let json: Value = serde_json::from_str(&contents)
+            .map_err(|error| CliError::InvalidSnapshot(error.to_string()))?;
+        Snapshot::from_json(&json)
+    }
+
+    pub fn from_json(json: &Value) -> Result<Snapshot, CliError> {
+        let pairs: &Vec<Value> = json["pairs"]
+            .as_array()
+            .ok_or_else(|| CliError::InvalidSnapshot("`pairs` is not a list".into()))?;
+        let pairs: Vec<PairReserves> = pairs
+            .iter()
+            .map(|pair| {
+                Ok(PairReserves {
+                    token0: token(pair, "token0")?,
+                    token1: token(pair, "token1")?,
+                    reserve0: reserve(pair, "reserve0")?,
+                    reserve1: reserve(pair, "reserve1")?,
+                })
+            })
+            .collect::<Result<_, CliError>>()?;
+        Ok(Snapshot { pairs })
+    }
+
+    // Reserves of `token_a` and `token_b`, in that order.
+    pub fn reserves(&self, token_a: Key, token_b: Key) -> Result<(U256, U256), CliError> {
+        self.pairs
+            .iter()
+            .find_map(|pair| {
+                if (pair.token0, pair.token1) == (token_a, token_b) {
+                    Some((pair.reserve0, pair.reserve1))
+                } else if (pair.token0, pair.token1) == (token_b, token_a) {
+                    Some((pair.reserve1, pair.reserve0))
+                } else {
+                    None
+                }
+            })
+            .ok_or_else(|| {
+                CliError::PairNotInSnapshot(
+                    token_a.to_formatted_string(),
+                    token_b.to_formatted_string(),
+                )
+            })
+    }
+
+    // Amounts along the path for an exact input, as `get_amounts_out` of the router.
+    pub fn amounts_out(&self, amount_in: U256, path: &Path) -> Result<Vec<U256>, CliError> {
+        let mut amounts: Vec<U256> = vec![amount_in];
+        for hop in path.tokens().windows(2) {
+            let (reserve_in, reserve_out) = self.reserves(hop[0], hop[1])?;
+            let amount_out: U256 =
+                math::try_get_amount_out(amounts[amounts.len() - 1], reserve_in, reserve_out)?;
+            amounts.push(amount_out);
+        }
+        Ok(amounts)
+    }
+
+    // Amounts along the path for an exact output, as `get_amounts_in` of the router.
+    pub fn amounts_in(&self, amount_out: U256, path: &Path) -> Result<Vec<U256>, CliError> {
+        let mut amounts: Vec<U256> = vec![amount_out];
+        for hop in path.tokens().windows(2).rev() {
+            let (reserve_in, reserve_out) = self.reserves(hop[0], hop[1])?;
+            let amount_in: U256 = math::try_get_amount_in(amounts[0], reserve_in, reserve_out)?;
+            amounts.insert(0, amount_in);
+        }
+        Ok(amounts)
+    }
+}
+
+fn token(pair: &Value, name: &str) -> Result<Key, CliError> {
+    pair[name]
+        .as_str()
+        .and_then(|token| Key::from_formatted_str(token).ok())
+        .ok_or_else(|| CliError::InvalidSnapshot(format!("`{}` is not a key", name)))
+}
+
+fn reserve(pair: &Value, name: &str) -> Result<U256, CliError> {
+    let reserve: Option<U256> = match &pair[name] {
+        Value::String(reserve) => U256::from_dec_str(reserve).ok(),
+        Value::Number(reserve) => reserve.as_u64().map(U256::from),
+        _ => None,
+    };
+    reserve.ok_or_else(|| CliError::InvalidSnapshot(format!("`{}` is not an amount", name)))
+}
@@ -0,0 +1,110 @@
+#![no_main]
+
+use common::{
+    bytesrepr::{FromBytes, ToBytes},
+    contract_api::{account, runtime, storage, system},
+    unwrap_or_revert::UnwrapOrRevert,
+    *,
+};
+
+// Test fixture standing in for a contract the router pays cspr to. `receive` moves the cspr into
+// the fixture's purse and adds it to `received`. The same purse is handed out with add access as
+// `deposit_purse`, for swaps that pay a purse directly, and `record_balance` writes its balance to
+// `balance`. The installing account's main purse is kept with add access as `main_purse`, for
+// swaps that pay an account's own purse.
+
+const PURSE: &str = "purse";
+const RECEIVED: &str = "received";
+const BALANCE: &str = "balance";
+const DEPOSIT_PURSE: &str = "deposit_purse";
+const MAIN_PURSE: &str = "main_purse";
+
+fn named_uref(name: &str) -> URef {
+    runtime::get_key(name)
+        .unwrap_or_revert()
+        .into_uref()
+        .unwrap_or_revert()
+}
+
+fn read<T: CLTyped + FromBytes>(name: &str) -> T {
+    storage::read(named_uref(name))
+        .unwrap_or_revert()
+        .unwrap_or_revert()
+}
+
+fn write<T: CLTyped + ToBytes>(name: &str, value: T) {
+    storage::write(named_uref(name), value);
+}
+
+#[no_mangle]
+fn receive() {
+    let amount: U512 = runtime::get_named_arg("amount");
+    let purse: URef = runtime::get_named_arg("purse");
+    system::transfer_from_purse_to_purse(purse, named_uref(PURSE), amount, None).unwrap_or_revert();
+    let received: U512 = read(RECEIVED);
+    write(RECEIVED, received + amount);
+}
+
+#[no_mangle]
+fn record_balance() {
+    let balance: U512 = system::get_purse_balance(named_uref(PURSE)).unwrap_or_revert();
+    write(BALANCE, balance);
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "receive",
+        vec![
+            Parameter::new("amount", CLType::U512),
+            Parameter::new("purse", CLType::URef),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "record_balance",
+        vec![],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+#[no_mangle]
+fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    let purse: URef = system::create_purse();
+    let deposit_purse: Key = Key::URef(purse.into_add());
+
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(PURSE.into(), purse.into());
+    named_keys.insert(RECEIVED.into(), storage::new_uref(U512::zero()).into());
+    named_keys.insert(BALANCE.into(), storage::new_uref(U512::zero()).into());
+    named_keys.insert(
+        DEPOSIT_PURSE.into(),
+        storage::new_uref(deposit_purse).into(),
+    );
+
+    let (contract_hash, _) = storage::new_contract(
+        get_entry_points(),
+        Some(named_keys),
+        Some(format!("{}_package_hash", contract_name)),
+        None,
+    );
+    runtime::put_key(
+        &format!("{}_contract_hash", contract_name),
+        contract_hash.into(),
+    );
+    // deploys of this account may pass the purse as a recipient
+    runtime::put_key(
+        &format!("{}_{}", contract_name, DEPOSIT_PURSE),
+        deposit_purse,
+    );
+    runtime::put_key(
+        &format!("{}_{}", contract_name, MAIN_PURSE),
+        Key::URef(account::get_main_purse().into_add()),
+    );
+}
@@ -0,0 +1,79 @@
+#![no_main]
+
+use common::{contract_api::runtime, errors::Errors, unwrap_or_revert::UnwrapOrRevert, *};
+
+// Registers a deployment after the library and the router were installed from this account, it
+// installs nothing itself: checks the router points at the given factory, wcspr and library,
+// registers the router on the factory and records every hash as
+// `{deployment_name}_{component}_{package|contract}_hash`.
+//
+// A contract's entry points are the exports of the wasm that installs it, and the library and
+// router export entry points of the same names, so they cannot be installed by a single wasm.
+
+fn named_hash(name: &str) -> [u8; 32] {
+    runtime::get_key(name)
+        .unwrap_or_revert()
+        .into_hash()
+        .unwrap_or_revert()
+}
+
+fn put_hash(deployment_name: &str, component: &str, kind: &str, hash: [u8; 32]) {
+    runtime::put_key(
+        &format!("{}_{}_{}_hash", deployment_name, component, kind),
+        Key::Hash(hash),
+    );
+}
+
+fn check_reference(router: ContractPackageHash, entry_point: &str, expected: Key) {
+    let reference: ContractPackageHash =
+        runtime::call_versioned_contract(router, None, entry_point, runtime_args! {});
+    if Key::from(reference) != expected {
+        runtime::revert(Errors::DeploymentReferenceMismatch);
+    }
+}
+
+#[no_mangle]
+fn call() {
+    let deployment_name: String = runtime::get_named_arg("deployment_name");
+    let library_name: String = runtime::get_named_arg("library_name");
+    let router_name: String = runtime::get_named_arg("router_name");
+    let factory: Key = runtime::get_named_arg("factory");
+    let wcspr: Key = runtime::get_named_arg("wcspr");
+
+    let library_package: [u8; 32] = named_hash(&format!("{}_package_hash", library_name));
+    let library_contract: [u8; 32] = named_hash(&format!("{}_contract_hash", library_name));
+    let router_package: [u8; 32] = named_hash(&format!("{}_package_hash", router_name));
+    let router_contract: [u8; 32] = named_hash(&format!("{}_contract_hash", router_name));
+
+    let router: ContractPackageHash = router_package.into();
+    check_reference(router, "get_factory", factory);
+    check_reference(router, "get_wcspr", wcspr);
+    check_reference(router, "get_library_hash", Key::Hash(library_package));
+
+    // only the router may create pairs, the registration must be run by the factory owner
+    let _: () = runtime::call_versioned_contract(
+        factory.into_hash().unwrap_or_revert().into(),
+        None,
+        "set_white_list",
+        runtime_args! {
+            "white_list" => Key::Hash(router_package)
+        },
+    );
+
+    put_hash(
+        &deployment_name,
+        "factory",
+        "package",
+        factory.into_hash().unwrap_or_revert(),
+    );
+    put_hash(
+        &deployment_name,
+        "wcspr",
+        "package",
+        wcspr.into_hash().unwrap_or_revert(),
+    );
+    put_hash(&deployment_name, "library", "package", library_package);
+    put_hash(&deployment_name, "library", "contract", library_contract);
+    put_hash(&deployment_name, "router", "package", router_package);
+    put_hash(&deployment_name, "router", "contract", router_contract);
+}
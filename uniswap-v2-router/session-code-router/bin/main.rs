@@ -1,13 +1,47 @@
 #![no_main]
 
 use common::{
-    bytesrepr::ToBytes,
+    bytesrepr::{self, Bytes, FromBytes, ToBytes},
     contract_api::{account, runtime, storage, system},
+    errors::Errors,
+    functions::{block_timestamp, get_optional_named_arg, u256_to_u512, u512_to_u256},
     keys::*,
+    session::{ScriptStep, SessionResult, INPUT_U256, INPUT_U512, INPUT_U64},
+    timelock::TimelockOperation,
     unwrap_or_revert::UnwrapOrRevert,
     *,
 };
 
+// Arguments of a destination, either those of the deploy or those of a script step.
+enum Args {
+    Deploy,
+    Step(RuntimeArgs),
+}
+
+impl Args {
+    fn get<T: CLTyped + FromBytes>(&self, name: &str) -> T {
+        match self {
+            Args::Deploy => runtime::get_named_arg(name),
+            Args::Step(_) => self
+                .get_optional(name)
+                .unwrap_or_revert_with(ApiError::MissingArgument),
+        }
+    }
+
+    fn get_optional<T: CLTyped + FromBytes>(&self, name: &str) -> Option<T> {
+        match self {
+            Args::Deploy => get_optional_named_arg(name),
+            Args::Step(args) => args.get(name).map(|value| {
+                value
+                    .clone()
+                    .into_t()
+                    .ok()
+                    .unwrap_or_revert_with(ApiError::CLTypeMismatch)
+            }),
+        }
+    }
+}
+
 // Key is the same a destination
 fn store<T: CLTyped + ToBytes>(key: &str, value: T) {
     // Store `value` under a new unforgeable reference.
@@ -20,6 +54,58 @@ fn store<T: CLTyped + ToBytes>(key: &str, value: T) {
     runtime::put_key(key, value_key);
 }
 
+// Every run gets the next run id of the account, its typed result is kept under `session_result`
+// and in the `session_results` dictionary under the run id.
+fn record(
+    destination: &str,
+    values: &[(&str, U256)],
+    amounts: Vec<U256>,
+    operation_hash: Option<String>,
+) -> SessionResult {
+    let run_id: u64 = match runtime::get_key(SESSION_RUN_ID) {
+        Some(key) => {
+            let run_id_ref: URef = key.into_uref().unwrap_or_revert();
+            let run_id: u64 = storage::read(run_id_ref)
+                .unwrap_or_revert()
+                .unwrap_or_revert();
+            storage::write(run_id_ref, run_id + 1);
+            run_id + 1
+        }
+        None => {
+            store(SESSION_RUN_ID, 1u64);
+            1
+        }
+    };
+    let results: URef = match runtime::get_key(SESSION_RESULTS) {
+        Some(key) => key.into_uref().unwrap_or_revert(),
+        None => storage::new_dictionary(SESSION_RESULTS).unwrap_or_revert(),
+    };
+    let result: SessionResult = SessionResult {
+        run_id,
+        destination: destination.into(),
+        block_time: block_timestamp(),
+        values: values
+            .iter()
+            .map(|(name, value)| ((*name).into(), *value))
+            .collect(),
+        amounts,
+        operation_hash,
+    };
+    storage::dictionary_put(results, &run_id.to_string(), result.clone());
+    store(SESSION_RESULT, result.clone());
+    result
+}
+
+// The input and output ends of a path of amounts.
+fn path_values(amounts: &[U256]) -> Vec<(&'static str, U256)> {
+    match (amounts.first(), amounts.last()) {
+        (Some(amount_in), Some(amount_out)) => {
+            vec![("amount_in", *amount_in), ("amount_out", *amount_out)]
+        }
+        _ => Vec::new(),
+    }
+}
+
 fn purse(amount: U512) -> URef {
     let main_purse: URef = account::get_main_purse();
     let secondary_purse: URef = system::create_purse();
@@ -28,22 +114,182 @@ fn purse(amount: U512) -> URef {
     secondary_purse
 }
 
+// Moves whatever the router left in `caller_purse` back to the main purse, so only the cspr the
+// router used leaves the account.
+fn sweep(caller_purse: URef, amount: U512) -> [(&'static str, U256); 2] {
+    let refunded: U512 = system::get_purse_balance(caller_purse).unwrap_or_revert();
+    if !refunded.is_zero() {
+        system::transfer_from_purse_to_purse(
+            caller_purse,
+            account::get_main_purse(),
+            refunded,
+            None,
+        )
+        .unwrap_or_revert();
+    }
+    [
+        ("cspr_spent", u512_to_u256(amount - refunded)),
+        ("cspr_refunded", u512_to_u256(refunded)),
+    ]
+}
+
+// Deadlines are block times in milliseconds, given either as an absolute `name` argument or as
+// `valid_for` milliseconds after the block time the deploy runs at. The router rejects sums
+// beyond u64.
+fn deadline(args: &Args, name: &str) -> U256 {
+    match args.get_optional::<U256>(name) {
+        Some(deadline) => deadline,
+        None => {
+            let valid_for: u64 = args.get("valid_for");
+            U256::from(block_timestamp()) + U256::from(valid_for)
+        }
+    }
+}
+
+// An `approve_and_` destination approves the router for exactly what the call spends, and clears
+// the allowances again once the router returns, so no allowance outlives the deploy.
+fn needed_allowances<F: FnOnce() -> Vec<(Key, U256)>>(
+    approve: bool,
+    needed: F,
+) -> Vec<(Key, U256)> {
+    if approve {
+        needed()
+    } else {
+        Vec::new()
+    }
+}
+
+fn approve(token: Key, spender: ContractPackageHash, amount: U256) {
+    runtime::call_versioned_contract::<()>(
+        token.into_hash().unwrap_or_revert().into(),
+        None,
+        "approve",
+        runtime_args! {
+            "spender" => Address::Contract(spender),
+            "amount" => amount
+        },
+    );
+}
+
+fn grant(allowances: &[(Key, U256)], spender: ContractPackageHash) {
+    for (token, amount) in allowances {
+        approve(*token, spender, *amount);
+    }
+}
+
+fn revoke(allowances: &[(Key, U256)], spender: ContractPackageHash) {
+    for (token, _) in allowances {
+        approve(*token, spender, U256::zero());
+    }
+}
+
+fn path_token(path: &[String]) -> Key {
+    path.first()
+        .and_then(|token| Key::from_formatted_str(token).ok())
+        .unwrap_or_revert_with(ApiError::InvalidArgument)
+}
+
+// The input the router will take for an exact output, priced in this deploy so it matches the
+// swap that follows.
+fn amount_in(package_hash: ContractPackageHash, amount_out: U256, path: &[String]) -> U256 {
+    let path: Vec<Key> = path
+        .iter()
+        .map(|token| {
+            Key::from_formatted_str(token)
+                .ok()
+                .unwrap_or_revert_with(ApiError::InvalidArgument)
+        })
+        .collect();
+    let amounts: Vec<U256> = runtime::call_versioned_contract(
+        package_hash,
+        None,
+        DESTINATION_GET_AMOUNTS_IN,
+        runtime_args! {
+            "amount_out" => amount_out,
+            "path" => path,
+        },
+    );
+    amounts[0]
+}
+
 #[no_mangle]
 pub extern "C" fn call() {
-    let entrypoint: String = runtime::get_named_arg("entrypoint");
+    let destination: String = runtime::get_named_arg("entrypoint");
     let package_hash: Key = runtime::get_named_arg("package_hash");
     let package_hash: ContractPackageHash = package_hash.into_hash().unwrap_or_revert().into();
-    match entrypoint.as_str() {
+    if destination == DESTINATION_SCRIPT {
+        let steps: Bytes = runtime::get_named_arg("steps");
+        run_script(
+            package_hash,
+            bytesrepr::deserialize(steps.into())
+                .ok()
+                .unwrap_or_revert_with(ApiError::InvalidArgument),
+        );
+    } else {
+        run(package_hash, &destination, &Args::Deploy);
+    }
+}
+
+// Runs the steps of a script in order, the deploy reverts as a whole on the first failing step.
+// The inputs of a step are taken from the named values of earlier steps.
+fn run_script(package_hash: ContractPackageHash, steps: Vec<ScriptStep>) {
+    let mut results: Vec<SessionResult> = Vec::new();
+    for step in steps {
+        let mut args: RuntimeArgs = step.args;
+        for input in step.inputs {
+            if args.get(&input.arg).is_some() {
+                runtime::revert(ApiError::InvalidArgument);
+            }
+            let value: U256 = results
+                .get(input.step as usize)
+                .and_then(|result| result.values.get(&input.value))
+                .copied()
+                .unwrap_or_revert_with(ApiError::InvalidArgument);
+            let inserted = match input.input_type {
+                INPUT_U256 => args.insert(input.arg, value),
+                INPUT_U512 => args.insert(input.arg, u256_to_u512(value)),
+                INPUT_U64 => {
+                    if value > U256::from(u64::MAX) {
+                        runtime::revert(ApiError::InvalidArgument);
+                    }
+                    args.insert(input.arg, value.as_u64())
+                }
+                _ => runtime::revert(ApiError::InvalidArgument),
+            };
+            inserted
+                .ok()
+                .unwrap_or_revert_with(ApiError::InvalidArgument);
+        }
+        results.push(run(package_hash, &step.destination, &Args::Step(args)));
+    }
+}
+
+fn run(package_hash: ContractPackageHash, destination: &str, args: &Args) -> SessionResult {
+    let (destination, approve): (&str, bool) =
+        match destination.strip_prefix(DESTINATION_APPROVE_PREFIX) {
+            Some(destination) => {
+                if !APPROVABLE_DESTINATIONS.contains(&destination) {
+                    runtime::revert(ApiError::UnexpectedKeyVariant);
+                }
+                (destination, true)
+            }
+            None => (destination, false),
+        };
+    match destination {
         DESTINATION_ADD_LIQUIDITY => {
-            let token_a: Key = runtime::get_named_arg("token_a");
-            let token_b: Key = runtime::get_named_arg("token_b");
-            let amount_a_desired: U256 = runtime::get_named_arg("amount_a_desired");
-            let amount_b_desired: U256 = runtime::get_named_arg("amount_b_desired");
-            let amount_a_min: U256 = runtime::get_named_arg("amount_a_min");
-            let amount_b_min: U256 = runtime::get_named_arg("amount_b_min");
-            let to: Key = runtime::get_named_arg("to");
-            let pair: Option<Key> = runtime::get_named_arg("pair");
-            let deadline: U256 = runtime::get_named_arg("deadline");
+            let token_a: Key = args.get("token_a");
+            let token_b: Key = args.get("token_b");
+            let amount_a_desired: U256 = args.get("amount_a_desired");
+            let amount_b_desired: U256 = args.get("amount_b_desired");
+            let amount_a_min: U256 = args.get("amount_a_min");
+            let amount_b_min: U256 = args.get("amount_b_min");
+            let to: Key = args.get("to");
+            let pair: Option<Key> = args.get("pair");
+            let deadline: U256 = deadline(args, "deadline");
+            let allowances = needed_allowances(approve, || {
+                vec![(token_a, amount_a_desired), (token_b, amount_b_desired)]
+            });
+            grant(&allowances, package_hash);
             let ret: (U256, U256, U256) = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -60,18 +306,32 @@ pub extern "C" fn call() {
                     "deadline" => deadline,
                 },
             );
+            revoke(&allowances, package_hash);
             store(DESTINATION_ADD_LIQUIDITY, ret);
+            record(
+                DESTINATION_ADD_LIQUIDITY,
+                &[
+                    ("amount_a", ret.0),
+                    ("amount_b", ret.1),
+                    ("liquidity", ret.2),
+                ],
+                Vec::new(),
+                None,
+            )
         }
         DESTINATION_ADD_LIQUIDITY_CSPR => {
-            let amount: U512 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG);
-            let token: Key = runtime::get_named_arg("token");
-            let amount_token_desired: U256 = runtime::get_named_arg("amount_token_desired");
-            let amount_cspr_desired: U256 = runtime::get_named_arg("amount_cspr_desired");
-            let amount_token_min: U256 = runtime::get_named_arg("amount_token_min");
-            let amount_cspr_min: U256 = runtime::get_named_arg("amount_cspr_min");
-            let to: Key = runtime::get_named_arg("to");
-            let deadline: U256 = runtime::get_named_arg("deadline");
-            let pair: Option<Key> = runtime::get_named_arg("pair");
+            let amount: U512 = args.get(AMOUNT_RUNTIME_ARG);
+            let token: Key = args.get("token");
+            let amount_token_desired: U256 = args.get("amount_token_desired");
+            let amount_cspr_desired: U256 = args.get("amount_cspr_desired");
+            let amount_token_min: U256 = args.get("amount_token_min");
+            let amount_cspr_min: U256 = args.get("amount_cspr_min");
+            let to: Key = args.get("to");
+            let deadline: U256 = deadline(args, "deadline");
+            let pair: Option<Key> = args.get("pair");
+            let allowances = needed_allowances(approve, || vec![(token, amount_token_desired)]);
+            grant(&allowances, package_hash);
+            let caller_purse: URef = purse(amount);
             let ret: (U256, U256, U256) = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -85,19 +345,35 @@ pub extern "C" fn call() {
                     "to" => to,
                     "deadline" => deadline,
                     "pair" => pair,
-                    "purse" => purse(amount)
+                    "purse" => caller_purse
                 },
             );
+            revoke(&allowances, package_hash);
+            let spend: [(&str, U256); 2] = sweep(caller_purse, amount);
             store(DESTINATION_ADD_LIQUIDITY_CSPR, ret);
+            record(
+                DESTINATION_ADD_LIQUIDITY_CSPR,
+                &[
+                    ("amount_token", ret.0),
+                    ("amount_cspr", ret.1),
+                    ("liquidity", ret.2),
+                    spend[0],
+                    spend[1],
+                ],
+                Vec::new(),
+                None,
+            )
         }
         DESTINATION_REMOVE_LIQUIDITY => {
-            let token_a: Key = runtime::get_named_arg("token_a");
-            let token_b: Key = runtime::get_named_arg("token_b");
-            let liquidity: U256 = runtime::get_named_arg("liquidity");
-            let amount_a_min: U256 = runtime::get_named_arg("amount_a_min");
-            let amount_b_min: U256 = runtime::get_named_arg("amount_b_min");
-            let to: Key = runtime::get_named_arg("to");
-            let deadline: U256 = runtime::get_named_arg("deadline");
+            let token_a: Key = args.get("token_a");
+            let token_b: Key = args.get("token_b");
+            let liquidity: U256 = args.get("liquidity");
+            let amount_a_min: U256 = args.get("amount_a_min");
+            let amount_b_min: U256 = args.get("amount_b_min");
+            let to: Key = args.get("to");
+            let deadline: U256 = deadline(args, "deadline");
+            let allowances = needed_allowances(approve, || vec![(args.get("pair"), liquidity)]);
+            grant(&allowances, package_hash);
             let ret: (U256, U256) = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -112,16 +388,24 @@ pub extern "C" fn call() {
                     "deadline" => deadline
                 },
             );
+            revoke(&allowances, package_hash);
             store(DESTINATION_REMOVE_LIQUIDITY, ret);
+            record(
+                DESTINATION_REMOVE_LIQUIDITY,
+                &[("amount_a", ret.0), ("amount_b", ret.1)],
+                Vec::new(),
+                None,
+            )
         }
         DESTINATION_REMOVE_LIQUIDITY_CSPR => {
-            let token: Key = runtime::get_named_arg("token");
-            let liquidity: U256 = runtime::get_named_arg("liquidity");
-            let amount_token_min: U256 = runtime::get_named_arg("amount_token_min");
-            let amount_cspr_min: U256 = runtime::get_named_arg("amount_cspr_min");
-            let to: Key = runtime::get_named_arg("to");
-            let deadline: U256 = runtime::get_named_arg("deadline");
-            // let to_purse: URef = runtime::get_named_arg("to_purse");
+            let token: Key = args.get("token");
+            let liquidity: U256 = args.get("liquidity");
+            let amount_token_min: U256 = args.get("amount_token_min");
+            let amount_cspr_min: U256 = args.get("amount_cspr_min");
+            let to: Key = args.get("to");
+            let deadline: U256 = deadline(args, "deadline");
+            let allowances = needed_allowances(approve, || vec![(args.get("pair"), liquidity)]);
+            grant(&allowances, package_hash);
             let ret: (U256, U256) = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -133,18 +417,25 @@ pub extern "C" fn call() {
                     "amount_cspr_min" => amount_cspr_min,
                     "to" => to,
                     "deadline" => deadline,
-                    // "to_purse" => to_purse,
-                    "to_purse" => account::get_main_purse().into_add(),
                 },
             );
+            revoke(&allowances, package_hash);
             store(DESTINATION_REMOVE_LIQUIDITY_CSPR, ret);
+            record(
+                DESTINATION_REMOVE_LIQUIDITY_CSPR,
+                &[("amount_token", ret.0), ("amount_cspr", ret.1)],
+                Vec::new(),
+                None,
+            )
         }
         DESTINATION_SWAP_EXACT_TOKENS_FOR_TOKENS => {
-            let amount_in: U256 = runtime::get_named_arg("amount_in");
-            let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
-            let path: Vec<String> = runtime::get_named_arg("path");
-            let to: Key = runtime::get_named_arg("to");
-            let deadline: U256 = runtime::get_named_arg("deadline");
+            let amount_in: U256 = args.get("amount_in");
+            let amount_out_min: U256 = args.get("amount_out_min");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let deadline: U256 = deadline(args, "deadline");
+            let allowances = needed_allowances(approve, || vec![(path_token(&path), amount_in)]);
+            grant(&allowances, package_hash);
             let ret: Vec<U256> = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -157,14 +448,28 @@ pub extern "C" fn call() {
                     "deadline" => deadline,
                 },
             );
-            store(DESTINATION_SWAP_EXACT_TOKENS_FOR_TOKENS, ret);
+            revoke(&allowances, package_hash);
+            store(DESTINATION_SWAP_EXACT_TOKENS_FOR_TOKENS, ret.clone());
+            record(
+                DESTINATION_SWAP_EXACT_TOKENS_FOR_TOKENS,
+                &path_values(&ret),
+                ret,
+                None,
+            )
         }
         DESTINATION_SWAP_TOKENS_FOR_EXACT_TOKENS => {
-            let amount_out: U256 = runtime::get_named_arg("amount_out");
-            let amount_in_max: U256 = runtime::get_named_arg("amount_in_max");
-            let path: Vec<String> = runtime::get_named_arg("path");
-            let to: Key = runtime::get_named_arg("to");
-            let deadline: U256 = runtime::get_named_arg("deadline");
+            let amount_out: U256 = args.get("amount_out");
+            let amount_in_max: U256 = args.get("amount_in_max");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let deadline: U256 = deadline(args, "deadline");
+            let allowances = needed_allowances(approve, || {
+                vec![(
+                    path_token(&path),
+                    amount_in(package_hash, amount_out, &path),
+                )]
+            });
+            grant(&allowances, package_hash);
             let ret: Vec<U256> = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -177,15 +482,23 @@ pub extern "C" fn call() {
                     "deadline" => deadline,
                 },
             );
-            store(DESTINATION_SWAP_TOKENS_FOR_EXACT_TOKENS, ret);
+            revoke(&allowances, package_hash);
+            store(DESTINATION_SWAP_TOKENS_FOR_EXACT_TOKENS, ret.clone());
+            record(
+                DESTINATION_SWAP_TOKENS_FOR_EXACT_TOKENS,
+                &path_values(&ret),
+                ret,
+                None,
+            )
         }
         DESTINATION_SWAP_EXACT_CSPR_FOR_TOKENS => {
-            let amount: U512 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG);
-            let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
-            let amount_in: U256 = runtime::get_named_arg("amount_in");
-            let path: Vec<String> = runtime::get_named_arg("path");
-            let to: Key = runtime::get_named_arg("to");
-            let deadline: U256 = runtime::get_named_arg("deadline");
+            let amount: U512 = args.get(AMOUNT_RUNTIME_ARG);
+            let amount_out_min: U256 = args.get("amount_out_min");
+            let amount_in: U256 = args.get("amount_in");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let deadline: U256 = deadline(args, "deadline");
+            let caller_purse: URef = purse(amount);
             let ret: Vec<U256> = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -196,18 +509,26 @@ pub extern "C" fn call() {
                     "path" => path,
                     "to" => to,
                     "deadline" => deadline,
-                    "purse" => purse(amount)
+                    "purse" => caller_purse
                 },
             );
-            store(DESTINATION_SWAP_EXACT_CSPR_FOR_TOKENS, ret);
+            let spend: [(&str, U256); 2] = sweep(caller_purse, amount);
+            store(DESTINATION_SWAP_EXACT_CSPR_FOR_TOKENS, ret.clone());
+            record(
+                DESTINATION_SWAP_EXACT_CSPR_FOR_TOKENS,
+                &[&path_values(&ret)[..], &spend].concat(),
+                ret,
+                None,
+            )
         }
         DESTINATION_SWAP_CSPR_FOR_EXACT_TOKENS => {
-            let amount: U512 = runtime::get_named_arg(AMOUNT_RUNTIME_ARG);
-            let amount_out: U256 = runtime::get_named_arg("amount_out");
-            let amount_in_max: U256 = runtime::get_named_arg("amount_in_max");
-            let path: Vec<String> = runtime::get_named_arg("path");
-            let to: Key = runtime::get_named_arg("to");
-            let deadline: U256 = runtime::get_named_arg("deadline");
+            let amount: U512 = args.get(AMOUNT_RUNTIME_ARG);
+            let amount_out: U256 = args.get("amount_out");
+            let amount_in_max: U256 = args.get("amount_in_max");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let deadline: U256 = deadline(args, "deadline");
+            let caller_purse: URef = purse(amount);
             let ret: Vec<U256> = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -218,17 +539,26 @@ pub extern "C" fn call() {
                     "path" => path,
                     "to" => to,
                     "deadline" => deadline,
-                    "purse" => purse(amount)
+                    "purse" => caller_purse
                 },
             );
-            store(DESTINATION_SWAP_CSPR_FOR_EXACT_TOKENS, ret);
+            let spend: [(&str, U256); 2] = sweep(caller_purse, amount);
+            store(DESTINATION_SWAP_CSPR_FOR_EXACT_TOKENS, ret.clone());
+            record(
+                DESTINATION_SWAP_CSPR_FOR_EXACT_TOKENS,
+                &[&path_values(&ret)[..], &spend].concat(),
+                ret,
+                None,
+            )
         }
         DESTINATION_SWAP_EXACT_TOKENS_FOR_CSPR => {
-            let amount_in: U256 = runtime::get_named_arg("amount_in");
-            let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
-            let path: Vec<String> = runtime::get_named_arg("path");
-            // let to: URef = runtime::get_named_arg("to");
-            let deadline: U256 = runtime::get_named_arg("deadline");
+            let amount_in: U256 = args.get("amount_in");
+            let amount_out_min: U256 = args.get("amount_out_min");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let deadline: U256 = deadline(args, "deadline");
+            let allowances = needed_allowances(approve, || vec![(path_token(&path), amount_in)]);
+            grant(&allowances, package_hash);
             let ret: Vec<U256> = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -237,19 +567,32 @@ pub extern "C" fn call() {
                     "amount_in" => amount_in,
                     "amount_out_min" => amount_out_min,
                     "path" => path,
-                    // "to" => to,
-                    "to" => account::get_main_purse().into_add(),
+                    "to" => to,
                     "deadline" => deadline
                 },
             );
-            store(DESTINATION_SWAP_EXACT_TOKENS_FOR_CSPR, ret);
+            revoke(&allowances, package_hash);
+            store(DESTINATION_SWAP_EXACT_TOKENS_FOR_CSPR, ret.clone());
+            record(
+                DESTINATION_SWAP_EXACT_TOKENS_FOR_CSPR,
+                &path_values(&ret),
+                ret,
+                None,
+            )
         }
         DESTINATION_SWAP_TOKENS_FOR_EXACT_CSPR => {
-            let amount_out: U256 = runtime::get_named_arg("amount_out");
-            let amount_in_max: U256 = runtime::get_named_arg("amount_in_max");
-            let path: Vec<String> = runtime::get_named_arg("path");
-            // let to: URef = runtime::get_named_arg("to");
-            let deadline: U256 = runtime::get_named_arg("deadline");
+            let amount_out: U256 = args.get("amount_out");
+            let amount_in_max: U256 = args.get("amount_in_max");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let deadline: U256 = deadline(args, "deadline");
+            let allowances = needed_allowances(approve, || {
+                vec![(
+                    path_token(&path),
+                    amount_in(package_hash, amount_out, &path),
+                )]
+            });
+            grant(&allowances, package_hash);
             let ret: Vec<U256> = runtime::call_versioned_contract(
                 package_hash,
                 None,
@@ -258,13 +601,340 @@ pub extern "C" fn call() {
                     "amount_out" => amount_out,
                     "amount_in_max" => amount_in_max,
                     "path" => path,
-                    // "to" => to,
-                    "to" => account::get_main_purse().into_add(),
+                    "to" => to,
                     "deadline" => deadline
                 },
             );
-            store(DESTINATION_SWAP_TOKENS_FOR_EXACT_CSPR, ret);
+            revoke(&allowances, package_hash);
+            store(DESTINATION_SWAP_TOKENS_FOR_EXACT_CSPR, ret.clone());
+            record(
+                DESTINATION_SWAP_TOKENS_FOR_EXACT_CSPR,
+                &path_values(&ret),
+                ret,
+                None,
+            )
+        }
+        DESTINATION_CREATE_LIMIT_ORDER => {
+            let amount_in: U256 = args.get("amount_in");
+            let amount_out_min: U256 = args.get("amount_out_min");
+            let bounty: U256 = args.get("bounty");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let expiry: U256 = deadline(args, "expiry");
+            // the order escrows the bounty next to the input
+            let escrow: U256 = amount_in
+                .checked_add(bounty)
+                .unwrap_or_revert_with(Errors::ArithmeticOverflow);
+            let allowances = needed_allowances(approve, || vec![(path_token(&path), escrow)]);
+            grant(&allowances, package_hash);
+            let ret: U256 = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_CREATE_LIMIT_ORDER,
+                runtime_args! {
+                    "amount_in" => amount_in,
+                    "amount_out_min" => amount_out_min,
+                    "bounty" => bounty,
+                    "path" => path,
+                    "to" => to,
+                    "expiry" => expiry,
+                },
+            );
+            revoke(&allowances, package_hash);
+            store(DESTINATION_CREATE_LIMIT_ORDER, ret);
+            record(
+                DESTINATION_CREATE_LIMIT_ORDER,
+                &[("order_id", ret)],
+                Vec::new(),
+                None,
+            )
+        }
+        DESTINATION_CREATE_LIMIT_ORDER_CSPR => {
+            let amount: U512 = args.get(AMOUNT_RUNTIME_ARG);
+            let amount_in: U256 = args.get("amount_in");
+            let amount_out_min: U256 = args.get("amount_out_min");
+            let bounty: U256 = args.get("bounty");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let expiry: U256 = deadline(args, "expiry");
+            let caller_purse: URef = purse(amount);
+            let ret: U256 = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_CREATE_LIMIT_ORDER_CSPR,
+                runtime_args! {
+                    "amount_in" => amount_in,
+                    "amount_out_min" => amount_out_min,
+                    "bounty" => bounty,
+                    "path" => path,
+                    "to" => to,
+                    "expiry" => expiry,
+                    "purse" => caller_purse
+                },
+            );
+            let spend: [(&str, U256); 2] = sweep(caller_purse, amount);
+            store(DESTINATION_CREATE_LIMIT_ORDER_CSPR, ret);
+            record(
+                DESTINATION_CREATE_LIMIT_ORDER_CSPR,
+                &[("order_id", ret), spend[0], spend[1]],
+                Vec::new(),
+                None,
+            )
+        }
+        DESTINATION_FILL_ORDER => {
+            let order_id: U256 = args.get("order_id");
+            let ret: Vec<U256> = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_FILL_ORDER,
+                runtime_args! {
+                    "order_id" => order_id,
+                },
+            );
+            store(DESTINATION_FILL_ORDER, ret.clone());
+            record(DESTINATION_FILL_ORDER, &path_values(&ret), ret, None)
+        }
+        DESTINATION_CREATE_DCA_ORDER => {
+            let budget: U256 = args.get("budget");
+            let amount_per_interval: U256 = args.get("amount_per_interval");
+            let interval: u64 = args.get("interval");
+            let amount_out_min: U256 = args.get("amount_out_min");
+            let fee: U256 = args.get("fee");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let allowances = needed_allowances(approve, || vec![(path_token(&path), budget)]);
+            grant(&allowances, package_hash);
+            let ret: U256 = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_CREATE_DCA_ORDER,
+                runtime_args! {
+                    "budget" => budget,
+                    "amount_per_interval" => amount_per_interval,
+                    "interval" => interval,
+                    "amount_out_min" => amount_out_min,
+                    "fee" => fee,
+                    "path" => path,
+                    "to" => to,
+                },
+            );
+            revoke(&allowances, package_hash);
+            store(DESTINATION_CREATE_DCA_ORDER, ret);
+            record(
+                DESTINATION_CREATE_DCA_ORDER,
+                &[("order_id", ret)],
+                Vec::new(),
+                None,
+            )
+        }
+        DESTINATION_CREATE_DCA_ORDER_CSPR => {
+            let amount: U512 = args.get(AMOUNT_RUNTIME_ARG);
+            let budget: U256 = args.get("budget");
+            let amount_per_interval: U256 = args.get("amount_per_interval");
+            let interval: u64 = args.get("interval");
+            let amount_out_min: U256 = args.get("amount_out_min");
+            let fee: U256 = args.get("fee");
+            let path: Vec<String> = args.get("path");
+            let to: Key = args.get("to");
+            let caller_purse: URef = purse(amount);
+            let ret: U256 = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_CREATE_DCA_ORDER_CSPR,
+                runtime_args! {
+                    "budget" => budget,
+                    "amount_per_interval" => amount_per_interval,
+                    "interval" => interval,
+                    "amount_out_min" => amount_out_min,
+                    "fee" => fee,
+                    "path" => path,
+                    "to" => to,
+                    "purse" => caller_purse
+                },
+            );
+            let spend: [(&str, U256); 2] = sweep(caller_purse, amount);
+            store(DESTINATION_CREATE_DCA_ORDER_CSPR, ret);
+            record(
+                DESTINATION_CREATE_DCA_ORDER_CSPR,
+                &[("order_id", ret), spend[0], spend[1]],
+                Vec::new(),
+                None,
+            )
+        }
+        DESTINATION_EXECUTE_DCA_ORDER => {
+            let order_id: U256 = args.get("order_id");
+            let ret: Vec<U256> = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_EXECUTE_DCA_ORDER,
+                runtime_args! {
+                    "order_id" => order_id,
+                },
+            );
+            store(DESTINATION_EXECUTE_DCA_ORDER, ret.clone());
+            record(DESTINATION_EXECUTE_DCA_ORDER, &path_values(&ret), ret, None)
+        }
+        DESTINATION_CONSULT => {
+            let token_in: Key = args.get("token_in");
+            let amount_in: U256 = args.get("amount_in");
+            let token_out: Key = args.get("token_out");
+            let ret: U256 = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_CONSULT,
+                runtime_args! {
+                    "token_in" => token_in,
+                    "amount_in" => amount_in,
+                    "token_out" => token_out,
+                },
+            );
+            store(DESTINATION_CONSULT, ret);
+            record(
+                DESTINATION_CONSULT,
+                &[("amount_out", ret)],
+                Vec::new(),
+                None,
+            )
+        }
+        DESTINATION_CHECK_DEADLINE => {
+            let deadline: U256 = deadline(args, "deadline");
+            let ret: u8 = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_CHECK_DEADLINE,
+                runtime_args! {
+                    "deadline" => deadline,
+                },
+            );
+            store(DESTINATION_CHECK_DEADLINE, ret);
+            record(
+                DESTINATION_CHECK_DEADLINE,
+                &[("reason", U256::from(ret))],
+                Vec::new(),
+                None,
+            )
+        }
+        DESTINATION_QUOTE => {
+            let amount_a: U256 = args.get("amount_a");
+            let reserve_a: U256 = args.get("reserve_a");
+            let reserve_b: U256 = args.get("reserve_b");
+            let ret: U256 = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_QUOTE,
+                runtime_args! {
+                    "amount_a" => amount_a,
+                    "reserve_a" => reserve_a,
+                    "reserve_b" => reserve_b,
+                },
+            );
+            store(DESTINATION_QUOTE, ret);
+            record(DESTINATION_QUOTE, &[("amount_b", ret)], Vec::new(), None)
+        }
+        DESTINATION_GET_AMOUNTS_OUT => {
+            let amount_in: U256 = args.get("amount_in");
+            let path: Vec<Key> = args.get("path");
+            let ret: Vec<U256> = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_GET_AMOUNTS_OUT,
+                runtime_args! {
+                    "amount_in" => amount_in,
+                    "path" => path,
+                },
+            );
+            store(DESTINATION_GET_AMOUNTS_OUT, ret.clone());
+            record(DESTINATION_GET_AMOUNTS_OUT, &path_values(&ret), ret, None)
+        }
+        DESTINATION_GET_AMOUNTS_IN => {
+            let amount_out: U256 = args.get("amount_out");
+            let path: Vec<Key> = args.get("path");
+            let ret: Vec<U256> = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_GET_AMOUNTS_IN,
+                runtime_args! {
+                    "amount_out" => amount_out,
+                    "path" => path,
+                },
+            );
+            store(DESTINATION_GET_AMOUNTS_IN, ret.clone());
+            record(DESTINATION_GET_AMOUNTS_IN, &path_values(&ret), ret, None)
+        }
+        DESTINATION_RESCUE_CSPR => {
+            let amount: U512 = args.get("amount");
+            runtime::call_versioned_contract::<()>(
+                package_hash,
+                None,
+                DESTINATION_RESCUE_CSPR,
+                runtime_args! {
+                    "amount" => amount,
+                    "to_purse" => account::get_main_purse().into_add(),
+                },
+            );
+            record(
+                DESTINATION_RESCUE_CSPR,
+                &[("amount", u512_to_u256(amount))],
+                Vec::new(),
+                None,
+            )
+        }
+        DESTINATION_QUEUE_OPERATION => {
+            let action: String = args.get("action");
+            let target: Key = args.get("target");
+            let value: u64 = args.get("value");
+            let eta: u64 = args.get("eta");
+            let ret: String = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_QUEUE_OPERATION,
+                runtime_args! {
+                    "action" => action,
+                    "target" => target,
+                    "value" => value,
+                    "eta" => eta,
+                },
+            );
+            store(DESTINATION_QUEUE_OPERATION, ret.clone());
+            record(DESTINATION_QUEUE_OPERATION, &[], Vec::new(), Some(ret))
+        }
+        DESTINATION_GET_OPERATION => {
+            let operation_hash: String = args.get("operation_hash");
+            let ret: TimelockOperation = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_GET_OPERATION,
+                runtime_args! {
+                    "operation_hash" => operation_hash.clone(),
+                },
+            );
+            store(DESTINATION_GET_OPERATION, ret.clone());
+            record(
+                DESTINATION_GET_OPERATION,
+                &[
+                    ("value", ret.value.into()),
+                    ("eta", ret.eta.into()),
+                    ("status", ret.status.into()),
+                ],
+                Vec::new(),
+                Some(operation_hash),
+            )
+        }
+        DESTINATION_GET_STORAGE_VERSION => {
+            let ret: u32 = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                DESTINATION_GET_STORAGE_VERSION,
+                runtime_args! {},
+            );
+            store(DESTINATION_GET_STORAGE_VERSION, ret);
+            record(
+                DESTINATION_GET_STORAGE_VERSION,
+                &[("storage_version", ret.into())],
+                Vec::new(),
+                None,
+            )
         }
         _ => runtime::revert(ApiError::UnexpectedKeyVariant),
-    };
+    }
 }
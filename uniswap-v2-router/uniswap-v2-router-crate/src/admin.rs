@@ -0,0 +1,145 @@
+use crate::{
+    data::*,
+    migrations,
+    transfer_helper::transfer_helper_mod,
+    uniswap_v2_router::{ROUTEREvent, UniswapV2Router},
+};
+use casper_types::system::CallStackElement;
+use common::{
+    contract_api::{runtime, system},
+    errors::Errors,
+    functions::*,
+    keys::*,
+    unwrap_or_revert::UnwrapOrRevert,
+    *,
+};
+
+// Owner-only changes of the router's configuration. Apart from `pause`, `migrate`, the version
+// records and the rescues they are reached through the timelock, see `Timelock::execute_operation`.
+pub trait RouterAdmin<Storage: ContractStorage>: UniswapV2Router<Storage> {
+    fn set_factory(&self, factory: ContractPackageHash) {
+        self._change_reference(FACTORY, factory);
+    }
+
+    // Open cspr orders hold the current wcspr and release their escrow by unwrapping it.
+    fn set_wcspr(&self, wcspr: ContractPackageHash) {
+        if EscrowBalances::instance().get(&Key::from(crate::data::wcspr())) > 0.into() {
+            runtime::revert(Errors::WcsprHeldInEscrow);
+        }
+        self._change_reference(WCSPR, wcspr);
+    }
+
+    fn set_library_hash(&self, library_hash: ContractPackageHash) {
+        self._change_reference(LIBRARY_HASH, library_hash);
+    }
+
+    fn set_timelock_delay(&self, delay: u64) {
+        self._only_owner();
+        set_timelock_delay(delay);
+    }
+
+    // Takes effect immediately so the owner can stop swaps and deposits in an emergency.
+    fn pause(&self) {
+        self._only_owner();
+        set_paused(true);
+        self.emit(&ROUTEREvent::Paused {
+            account: self.get_caller(),
+        });
+    }
+
+    fn unpause(&self) {
+        self._only_owner();
+        set_paused(false);
+        self.emit(&ROUTEREvent::Unpaused {
+            account: self.get_caller(),
+        });
+    }
+
+    // Run after adding a contract version, it is a no-op when storage is already current.
+    fn migrate(&self) -> u32 {
+        self._only_owner();
+        migrations::migrate()
+    }
+
+    fn enabled_versions(&self) -> Vec<ContractHash> {
+        enabled_versions()
+    }
+
+    // Called by the install session after it added a contract version, through the package so it
+    // runs in the new version, which is read from the call stack. The entry point is restricted to
+    // the upgrader group, whose URef the install session keeps next to the package access token,
+    // so the account that can disable versions is also the one recording it, whoever the owner is.
+    fn record_versions(&self, disable_previous_versions: bool) {
+        let contract_hash: ContractHash = runtime::get_call_stack()
+            .into_iter()
+            .rev()
+            .find_map(|element| match element {
+                CallStackElement::StoredContract {
+                    contract_package_hash,
+                    contract_hash,
+                } if contract_package_hash == get_package_hash() => Some(contract_hash),
+                _ => None,
+            })
+            .unwrap_or_revert();
+        let mut versions: Vec<ContractHash> = if disable_previous_versions {
+            Vec::new()
+        } else {
+            enabled_versions()
+        };
+        if !versions.contains(&contract_hash) {
+            versions.push(contract_hash);
+        }
+        set_enabled_versions(versions);
+    }
+
+    // Cspr left in the router's purse, order escrow is never held as cspr.
+    fn rescue_cspr(&self, amount: U512, to_purse: URef) {
+        self._only_owner();
+        system::transfer_from_purse_to_purse(get_purse(), to_purse, amount, None)
+            .unwrap_or_revert();
+        self.emit(&ROUTEREvent::RescuedCspr { amount, to_purse });
+    }
+
+    // Tokens sent to the router outside of an order, escrow of open orders cannot be rescued.
+    fn rescue_tokens(&self, token: Key, amount: U256, to: Key) {
+        self._only_owner();
+        let balance: U256 = runtime::call_versioned_contract(
+            token.into_hash().unwrap_or_revert().into(),
+            None,
+            "balance_of",
+            runtime_args! {
+                "owner" => Address::from(Key::from(get_package_hash()))
+            },
+        );
+        if amount > balance.saturating_sub(EscrowBalances::instance().get(&token)) {
+            runtime::revert(Errors::RescueExceedsFreeBalance);
+        }
+        transfer_helper_mod::safe_transfer(token, to, amount);
+        self.emit(&ROUTEREvent::RescuedTokens { token, amount, to });
+    }
+
+    fn _change_reference(&self, name: &str, value: ContractPackageHash) {
+        self._only_owner();
+        let old: ContractPackageHash = match name {
+            FACTORY => factory(),
+            WCSPR => wcspr(),
+            _ => library_hash(),
+        };
+        match name {
+            FACTORY => set_factory(value),
+            WCSPR => set_wcspr(value),
+            _ => set_library_hash(value),
+        }
+        self.emit(&ROUTEREvent::ReferenceChanged {
+            name: name.into(),
+            old,
+            new: value,
+        });
+    }
+
+    fn _only_owner(&self) {
+        if self.get_caller() != get_owner() {
+            runtime::revert(Errors::NotOwner);
+        }
+    }
+}
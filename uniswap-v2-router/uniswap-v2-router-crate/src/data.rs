@@ -1,4 +1,7 @@
 use casper_types_derive::{CLTyped, FromBytes, ToBytes};
+pub use common::timelock::{
+    TimelockOperation, OPERATION_CANCELLED, OPERATION_EXECUTED, OPERATION_QUEUED,
+};
 use common::{functions::account_zero_address, keys::*, unwrap_or_revert::UnwrapOrRevert, *};
 
 #[derive(CLTyped, ToBytes, FromBytes)]
@@ -26,6 +29,114 @@ impl Whitelist {
     }
 }
 
+// Pairs resolved through the factory, keyed by their sorted tokens. Entries remember the factory
+// that resolved them and are ignored once the router points at another factory.
+#[derive(CLTyped, ToBytes, FromBytes)]
+pub struct PairCache {
+    dict: Dict,
+}
+
+impl PairCache {
+    pub fn instance() -> PairCache {
+        PairCache {
+            dict: Dict::instance(PAIR_CACHE),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(PAIR_CACHE)
+    }
+
+    pub fn get(&self, token0: &Key, token1: &Key) -> Option<Key> {
+        let cached: Option<(ContractPackageHash, Key)> = self.dict.get_by_keys((token0, token1));
+        cached
+            .filter(|(pair_factory, _)| *pair_factory == factory())
+            .map(|(_, pair)| pair)
+    }
+
+    pub fn set(&self, token0: &Key, token1: &Key, pair: Key) {
+        self.dict.set_by_keys((token0, token1), (factory(), pair));
+    }
+}
+
+// Amount of each token the router holds for open limit and dca orders, which cannot be rescued.
+#[derive(CLTyped, ToBytes, FromBytes)]
+pub struct EscrowBalances {
+    dict: Dict,
+}
+
+impl EscrowBalances {
+    pub fn instance() -> EscrowBalances {
+        EscrowBalances {
+            dict: Dict::instance(ESCROW_BALANCES),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(ESCROW_BALANCES)
+    }
+
+    pub fn get(&self, token: &Key) -> U256 {
+        self.dict.get_by_key(token).unwrap_or_default()
+    }
+
+    pub fn lock(&self, token: &Key, amount: U256) {
+        self.dict.set_by_key(token, self.get(token) + amount);
+    }
+
+    pub fn release(&self, token: &Key, amount: U256) {
+        self.dict.set_by_key(token, self.get(token) - amount);
+    }
+}
+
+#[derive(CLTyped, ToBytes, FromBytes)]
+pub struct TimelockOperations {
+    dict: Dict,
+}
+
+impl TimelockOperations {
+    pub fn instance() -> TimelockOperations {
+        TimelockOperations {
+            dict: Dict::instance(TIMELOCK_OPERATIONS),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(TIMELOCK_OPERATIONS)
+    }
+
+    pub fn get(&self, operation_hash: &str) -> Option<TimelockOperation> {
+        self.dict.get(operation_hash)
+    }
+
+    pub fn set(&self, operation_hash: &str, operation: TimelockOperation) {
+        self.dict.set(operation_hash, operation);
+    }
+}
+
+// Minimum delay, in milliseconds, between queueing a timelock operation and executing it.
+pub fn timelock_delay() -> u64 {
+    get_key(TIMELOCK_DELAY).unwrap_or_revert()
+}
+pub fn set_timelock_delay(delay: u64) {
+    set_key(TIMELOCK_DELAY, delay);
+}
+
+// Cspr the router may still be sent by wcspr for the withdraw in progress.
+pub fn pending_cspr() -> U512 {
+    get_key(PENDING_CSPR).unwrap_or_default()
+}
+pub fn set_pending_cspr(amount: U512) {
+    set_key(PENDING_CSPR, amount);
+}
+
+pub fn paused() -> bool {
+    get_key(PAUSED).unwrap_or_default()
+}
+pub fn set_paused(paused: bool) {
+    set_key(PAUSED, paused);
+}
+
 pub fn wcspr() -> ContractPackageHash {
     get_key(WCSPR).unwrap_or_revert()
 }
@@ -47,6 +158,34 @@ pub fn set_library_hash(library_hash: ContractPackageHash) {
     set_key(LIBRARY_HASH, library_hash);
 }
 
+// Routes the pricing math through the library contract instead of the linked library crate, for
+// deployments that rely on the library contract's behaviour.
+pub fn use_library_contract() -> bool {
+    get_key(USE_LIBRARY_CONTRACT).unwrap_or_default()
+}
+pub fn set_use_library_contract(use_library_contract: bool) {
+    set_key(USE_LIBRARY_CONTRACT, use_library_contract);
+}
+
+// Layout of the router's named keys and dictionaries, routers installed before it was recorded
+// are at version 1.
+pub fn storage_version() -> u32 {
+    get_key(STORAGE_VERSION).unwrap_or(1)
+}
+pub fn set_storage_version(version: u32) {
+    set_key(STORAGE_VERSION, version);
+}
+
+// Contract versions of the router package that have not been disabled, as recorded by the install
+// session. Routers installed before versions were recorded only know the version they were
+// installed with.
+pub fn enabled_versions() -> Vec<ContractHash> {
+    get_key(ENABLED_VERSIONS).unwrap_or_else(|| vec![get_contract_hash()])
+}
+pub fn set_enabled_versions(versions: Vec<ContractHash>) {
+    set_key(ENABLED_VERSIONS, versions);
+}
+
 pub fn set_owner(owner: Key) {
     set_key(OWNER, owner)
 }
@@ -54,3 +193,109 @@ pub fn set_owner(owner: Key) {
 pub fn get_owner() -> Key {
     get_key(OWNER).unwrap_or_else(account_zero_address)
 }
+
+pub const ORDER_OPEN: u8 = 0;
+pub const ORDER_FILLED: u8 = 1;
+pub const ORDER_CANCELLED: u8 = 2;
+
+#[derive(Clone, CLTyped, ToBytes, FromBytes)]
+pub struct LimitOrder {
+    pub owner: Key,
+    pub path: Vec<Key>,
+    pub amount_in: U256,
+    pub amount_out_min: U256,
+    pub bounty: U256,
+    pub to: Key,
+    pub expiry: U256,
+    // escrow was deposited as cspr and is wrapped into wcspr while the order is open
+    pub cspr: bool,
+    pub status: u8,
+}
+
+#[derive(CLTyped, ToBytes, FromBytes)]
+pub struct LimitOrders {
+    dict: Dict,
+}
+
+impl LimitOrders {
+    pub fn instance() -> LimitOrders {
+        LimitOrders {
+            dict: Dict::instance(LIMIT_ORDERS),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(LIMIT_ORDERS)
+    }
+
+    pub fn get(&self, order_id: &U256) -> Option<LimitOrder> {
+        self.dict.get(&order_id.to_string())
+    }
+
+    pub fn set(&self, order_id: &U256, order: LimitOrder) {
+        self.dict.set(&order_id.to_string(), order);
+    }
+}
+
+pub fn limit_order_count() -> U256 {
+    get_key(LIMIT_ORDER_COUNT).unwrap_or_default()
+}
+pub fn set_limit_order_count(count: U256) {
+    set_key(LIMIT_ORDER_COUNT, count);
+}
+
+pub const DCA_ACTIVE: u8 = 0;
+pub const DCA_PAUSED: u8 = 1;
+pub const DCA_CLOSED: u8 = 2;
+// longest interval of a dca order, one year in milliseconds
+pub const DCA_MAX_INTERVAL: u64 = 365 * 24 * 60 * 60 * 1000;
+
+#[derive(Clone, CLTyped, ToBytes, FromBytes)]
+pub struct DcaOrder {
+    pub owner: Key,
+    pub path: Vec<Key>,
+    // escrow left for the remaining executions and their keeper fees
+    pub budget: U256,
+    pub amount_per_interval: U256,
+    pub interval: u64,
+    // slippage bound of a single execution
+    pub amount_out_min: U256,
+    pub fee: U256,
+    pub to: Key,
+    pub next_execution: u64,
+    // budget was deposited as cspr and is wrapped into wcspr while the order is open
+    pub cspr: bool,
+    pub status: u8,
+}
+
+#[derive(CLTyped, ToBytes, FromBytes)]
+pub struct DcaOrders {
+    dict: Dict,
+}
+
+impl DcaOrders {
+    pub fn instance() -> DcaOrders {
+        DcaOrders {
+            dict: Dict::instance(DCA_ORDERS),
+        }
+    }
+
+    pub fn init() {
+        Dict::init(DCA_ORDERS)
+    }
+
+    pub fn get(&self, order_id: &U256) -> Option<DcaOrder> {
+        self.dict.get(&order_id.to_string())
+    }
+
+    pub fn set(&self, order_id: &U256, order: DcaOrder) {
+        self.dict.set(&order_id.to_string(), order);
+    }
+}
+
+pub fn dca_order_count() -> U256 {
+    get_key(DCA_ORDER_COUNT).unwrap_or_default()
+}
+pub fn set_dca_order_count(count: U256) {
+    set_key(DCA_ORDER_COUNT, count);
+}
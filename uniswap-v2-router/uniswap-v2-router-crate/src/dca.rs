@@ -0,0 +1,251 @@
+use crate::{
+    data::*,
+    recipient::Recipient,
+    transfer_helper::transfer_helper_mod,
+    uniswap_v2_router::{ROUTEREvent, UniswapV2Router},
+};
+use common::{
+    contract_api::{runtime, system},
+    errors::Errors,
+    functions::*,
+    keys::*,
+    unwrap_or_revert::UnwrapOrRevert,
+    *,
+};
+
+// Dollar cost averaging orders escrow a budget in the router which keepers spend in equal swaps,
+// at most once per interval, for a fee. Cspr budgets are kept wrapped as wcspr.
+pub trait DcaScheduler<Storage: ContractStorage>: UniswapV2Router<Storage> {
+    #[allow(clippy::too_many_arguments)]
+    fn create_dca_order(
+        &self,
+        budget: U256,
+        amount_per_interval: U256,
+        interval: u64,
+        amount_out_min: U256,
+        fee: U256,
+        _path: Vec<String>,
+        to: Key,
+    ) -> U256 {
+        Self::_when_not_paused();
+        let path: Vec<Key> = Self::_parse_path(&_path);
+        self._validate_dca_order(&path, budget, amount_per_interval, interval, fee);
+        // router must be approved by the caller for the budget
+        transfer_helper_mod::safe_transfer_from(
+            path[0],
+            self.get_caller(),
+            Key::from(get_package_hash()),
+            budget,
+        );
+        self._store_dca_order(DcaOrder {
+            owner: self.get_caller(),
+            path,
+            budget,
+            amount_per_interval,
+            interval,
+            amount_out_min,
+            fee,
+            to: Recipient::from_key(to).token_key(),
+            next_execution: block_timestamp(),
+            cspr: false,
+            status: DCA_ACTIVE,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_dca_order_cspr(
+        &self,
+        budget: U256,
+        amount_per_interval: U256,
+        interval: u64,
+        amount_out_min: U256,
+        fee: U256,
+        _path: Vec<String>,
+        to: Key,
+        caller_purse: URef,
+    ) -> U256 {
+        Self::_when_not_paused();
+        let path: Vec<Key> = Self::_parse_path(&_path);
+        if path.is_empty() || path[0] != Key::from(wcspr()) {
+            runtime::revert(Errors::InvalidDcaOrder);
+        }
+        self._validate_dca_order(&path, budget, amount_per_interval, interval, fee);
+        system::transfer_from_purse_to_purse(
+            caller_purse,
+            get_purse().into_add(),
+            u256_to_u512(budget),
+            None,
+        )
+        .unwrap_or_revert_with(Errors::InsufficientCspr);
+        // wrap the budget so that executions can go through the pairs like any token order
+        runtime::call_versioned_contract::<()>(
+            wcspr(),
+            None,
+            WCSPR_DEPOSIT,
+            runtime_args! {
+                "amount" => u256_to_u512(budget),
+                "purse" => get_purse()
+            },
+        );
+        self._store_dca_order(DcaOrder {
+            owner: self.get_caller(),
+            path,
+            budget,
+            amount_per_interval,
+            interval,
+            amount_out_min,
+            fee,
+            to: Recipient::from_key(to).token_key(),
+            next_execution: block_timestamp(),
+            cspr: true,
+            status: DCA_ACTIVE,
+        })
+    }
+
+    fn execute_dca_order(&self, order_id: U256) -> Vec<U256> {
+        Self::_when_not_paused();
+        let orders = DcaOrders::instance();
+        let mut order: DcaOrder = orders
+            .get(&order_id)
+            .unwrap_or_revert_with(Errors::DcaOrderNotFound);
+        if order.status != DCA_ACTIVE {
+            runtime::revert(Errors::DcaOrderNotActive);
+        }
+        if block_timestamp() < order.next_execution {
+            runtime::revert(Errors::DcaOrderNotDue);
+        }
+        // amount_per_interval + fee was checked for overflow when the order was created
+        let cost: U256 = order.amount_per_interval + order.fee;
+        if order.budget < cost {
+            runtime::revert(Errors::DcaBudgetExhausted);
+        }
+        let pairs: Vec<Key> = Self::_pairs_for(&order.path);
+        let amounts: Vec<U256> = Self::_amounts_out(order.amount_per_interval, &order.path, &pairs);
+        if amounts[amounts.len() - 1] < order.amount_out_min {
+            runtime::revert(Errors::DcaInsufficientOutputAmount);
+        }
+        // book the execution before moving any escrow
+        order.budget -= cost;
+        // interval was bounded by DCA_MAX_INTERVAL when the order was created
+        order.next_execution = block_timestamp() + order.interval;
+        orders.set(&order_id, order.clone());
+        EscrowBalances::instance().release(&order.path[0], amounts[0]);
+        transfer_helper_mod::safe_transfer(order.path[0], pairs[0], amounts[0]);
+        Self::_swap(&amounts, &order.path, &pairs, order.to);
+        if order.fee > 0.into() {
+            transfer_helper_mod::release_escrow(
+                order.path[0],
+                order.cspr,
+                self.get_caller(),
+                order.fee,
+            );
+        }
+        self.emit(&ROUTEREvent::DcaOrderExecuted {
+            order_id,
+            keeper: self.get_caller(),
+            amount_in: amounts[0],
+            amount_out: amounts[amounts.len() - 1],
+            budget: order.budget,
+        });
+        amounts
+    }
+
+    fn pause_dca_order(&self, order_id: U256) {
+        Self::_when_not_paused();
+        let orders = DcaOrders::instance();
+        let mut order: DcaOrder = self._owned_dca_order(&orders, order_id);
+        if order.status != DCA_ACTIVE {
+            runtime::revert(Errors::DcaOrderNotActive);
+        }
+        order.status = DCA_PAUSED;
+        orders.set(&order_id, order);
+        self.emit(&ROUTEREvent::DcaOrderPaused { order_id });
+    }
+
+    fn resume_dca_order(&self, order_id: U256) {
+        Self::_when_not_paused();
+        let orders = DcaOrders::instance();
+        let mut order: DcaOrder = self._owned_dca_order(&orders, order_id);
+        if order.status != DCA_PAUSED {
+            runtime::revert(Errors::DcaOrderNotPaused);
+        }
+        order.status = DCA_ACTIVE;
+        orders.set(&order_id, order);
+        self.emit(&ROUTEREvent::DcaOrderResumed { order_id });
+    }
+
+    // Closes the order and refunds the remaining budget to its owner.
+    fn withdraw_dca_order(&self, order_id: U256) -> U256 {
+        let orders = DcaOrders::instance();
+        let mut order: DcaOrder = self._owned_dca_order(&orders, order_id);
+        if order.status == DCA_CLOSED {
+            runtime::revert(Errors::DcaOrderNotActive);
+        }
+        let remaining: U256 = order.budget;
+        order.budget = 0.into();
+        order.status = DCA_CLOSED;
+        orders.set(&order_id, order.clone());
+        if remaining > 0.into() {
+            transfer_helper_mod::release_escrow(order.path[0], order.cspr, order.owner, remaining);
+        }
+        self.emit(&ROUTEREvent::DcaOrderWithdrawn {
+            order_id,
+            owner: order.owner,
+            amount: remaining,
+        });
+        remaining
+    }
+
+    // *************************************** Helper methods ****************************************
+    fn _validate_dca_order(
+        &self,
+        path: &[Key],
+        budget: U256,
+        amount_per_interval: U256,
+        interval: u64,
+        fee: U256,
+    ) {
+        if path.len() < 2
+            || amount_per_interval == 0.into()
+            || interval == 0
+            || interval > DCA_MAX_INTERVAL
+        {
+            runtime::revert(Errors::InvalidDcaOrder);
+        }
+        // the budget is escrowed before any swap, so the path's pairs must already exist
+        Self::_pairs_for(path);
+        // the budget has to cover at least one execution
+        let cost: U256 = amount_per_interval
+            .checked_add(fee)
+            .unwrap_or_revert_with(Errors::InvalidDcaOrder);
+        if budget < cost {
+            runtime::revert(Errors::InvalidDcaOrder);
+        }
+    }
+
+    fn _store_dca_order(&self, order: DcaOrder) -> U256 {
+        let order_id: U256 = dca_order_count();
+        set_dca_order_count(order_id + 1);
+        self.emit(&ROUTEREvent::DcaOrderCreated {
+            order_id,
+            owner: order.owner,
+            budget: order.budget,
+            amount_per_interval: order.amount_per_interval,
+            interval: order.interval,
+            fee: order.fee,
+        });
+        EscrowBalances::instance().lock(&order.path[0], order.budget);
+        DcaOrders::instance().set(&order_id, order);
+        order_id
+    }
+
+    fn _owned_dca_order(&self, orders: &DcaOrders, order_id: U256) -> DcaOrder {
+        let order: DcaOrder = orders
+            .get(&order_id)
+            .unwrap_or_revert_with(Errors::DcaOrderNotFound);
+        if self.get_caller() != order.owner {
+            runtime::revert(Errors::NotDcaOrderOwner);
+        }
+        order
+    }
+}
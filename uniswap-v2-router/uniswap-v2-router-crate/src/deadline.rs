@@ -0,0 +1,35 @@
+use common::{functions::block_timestamp, *};
+
+// Deadlines are absolute block times in milliseconds since the unix epoch, the unit of
+// `runtime::get_blocktime`. A deadline equal to the block time is still valid.
+//
+// Every deadline failure reverts with `TimedOut`, the reason can be looked up
+// with the `check_deadline` entry point.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeadlineError {
+    /// the deadline is before the current block time
+    Expired = 1,
+    /// the deadline does not fit in a u64 block time
+    ExceedsU64 = 2,
+}
+
+// Reason code of a valid deadline, returned by `check_deadline`.
+pub const DEADLINE_VALID: u8 = 0;
+
+pub fn check_deadline(deadline: U256) -> Result<(), DeadlineError> {
+    if deadline > U256::from(u64::MAX) {
+        return Err(DeadlineError::ExceedsU64);
+    }
+    if deadline.as_u64() < block_timestamp() {
+        return Err(DeadlineError::Expired);
+    }
+    Ok(())
+}
+
+pub fn deadline_reason(deadline: U256) -> u8 {
+    match check_deadline(deadline) {
+        Ok(()) => DEADLINE_VALID,
+        Err(reason) => reason as u8,
+    }
+}
@@ -0,0 +1,756 @@
+// Entry points of the router contract. They are installed from the router wasm and exported
+// as a json schema by `contract-schema`, so both are built from the definitions below.
+use crate::data::TimelockOperation;
+use common::{
+    errors::{Errors, Side},
+    *,
+};
+
+pub fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("factory", CLType::Key),
+            Parameter::new("wcspr", CLType::Key),
+            Parameter::new("library_hash", CLType::Key),
+            Parameter::new("use_library_contract", CLType::Bool),
+            Parameter::new("contract_hash", ContractHash::cl_type()),
+            Parameter::new("package_hash", ContractPackageHash::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_liquidity",
+        vec![
+            Parameter::new("token_a", CLType::Key),
+            Parameter::new("token_b", CLType::Key),
+            Parameter::new("amount_a_desired", CLType::U256),
+            Parameter::new("amount_b_desired", CLType::U256),
+            Parameter::new("amount_a_min", CLType::U256),
+            Parameter::new("amount_b_min", CLType::U256),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("deadline", CLType::U256),
+            Parameter::new("pair", CLType::Option(Box::new(CLType::Key))),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::U256),
+            Box::new(CLType::U256),
+            Box::new(CLType::U256),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_liquidity_cspr",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("amount_token_desired", CLType::U256),
+            Parameter::new("amount_cspr_desired", CLType::U256),
+            Parameter::new("amount_token_min", CLType::U256),
+            Parameter::new("amount_cspr_min", CLType::U256),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("deadline", CLType::U256),
+            Parameter::new("pair", CLType::Option(Box::new(CLType::Key))),
+            Parameter::new("purse", CLType::URef),
+        ],
+        CLType::Tuple3([
+            Box::new(CLType::U256),
+            Box::new(CLType::U256),
+            Box::new(CLType::U256),
+        ]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "remove_liquidity",
+        vec![
+            Parameter::new("token_a", Key::cl_type()),
+            Parameter::new("token_b", Key::cl_type()),
+            Parameter::new("liquidity", CLType::U256),
+            Parameter::new("amount_a_min", CLType::U256),
+            Parameter::new("amount_b_min", CLType::U256),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("deadline", CLType::U256),
+        ],
+        CLType::Tuple2([Box::new(CLType::U256), Box::new(CLType::U256)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "remove_liquidity_cspr",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("liquidity", CLType::U256),
+            Parameter::new("amount_token_min", CLType::U256),
+            Parameter::new("amount_cspr_min", CLType::U256),
+            Parameter::new("to", Key::cl_type()),
+            Parameter::new("deadline", CLType::U256),
+        ],
+        CLType::Tuple2([Box::new(CLType::U256), Box::new(CLType::U256)]),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "swap_exact_tokens_for_tokens",
+        vec![
+            Parameter::new("amount_in", CLType::U256),
+            Parameter::new("amount_out_min", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("deadline", CLType::U256),
+        ],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "swap_tokens_for_exact_tokens",
+        vec![
+            Parameter::new("amount_out", CLType::U256),
+            Parameter::new("amount_in_max", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("deadline", CLType::U256),
+        ],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "swap_exact_cspr_for_tokens",
+        vec![
+            Parameter::new("amount_out_min", CLType::U256),
+            Parameter::new("amount_in", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("deadline", CLType::U256),
+            Parameter::new("purse", CLType::URef),
+        ],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "swap_tokens_for_exact_cspr",
+        vec![
+            Parameter::new("amount_out", CLType::U256),
+            Parameter::new("amount_in_max", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("deadline", CLType::U256),
+        ],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "swap_exact_tokens_for_cspr",
+        vec![
+            Parameter::new("amount_in", CLType::U256),
+            Parameter::new("amount_out_min", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("deadline", CLType::U256),
+        ],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "swap_cspr_for_exact_tokens",
+        vec![
+            Parameter::new("amount_out", CLType::U256),
+            Parameter::new("amount_in_max", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("deadline", CLType::U256),
+            Parameter::new("purse", CLType::URef),
+        ],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "quote",
+        vec![
+            Parameter::new("amount_a", U256::cl_type()),
+            Parameter::new("reserve_a", U256::cl_type()),
+            Parameter::new("reserve_b", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amount_out",
+        vec![
+            Parameter::new("amount_in", U256::cl_type()),
+            Parameter::new("reserve_in", U256::cl_type()),
+            Parameter::new("reserve_out", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amount_in",
+        vec![
+            Parameter::new("amount_out", U256::cl_type()),
+            Parameter::new("reserve_in", U256::cl_type()),
+            Parameter::new("reserve_out", U256::cl_type()),
+        ],
+        U256::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amounts_out",
+        vec![
+            Parameter::new("amount_in", U256::cl_type()),
+            Parameter::new("path", CLType::List(Box::new(Key::cl_type()))),
+        ],
+        CLType::List(Box::new(U256::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_amounts_in",
+        vec![
+            Parameter::new("amount_out", U256::cl_type()),
+            Parameter::new("path", CLType::List(Box::new(Key::cl_type()))),
+        ],
+        CLType::List(Box::new(U256::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "check_deadline",
+        vec![Parameter::new("deadline", U256::cl_type())],
+        u8::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_limit_order",
+        vec![
+            Parameter::new("amount_in", CLType::U256),
+            Parameter::new("amount_out_min", CLType::U256),
+            Parameter::new("bounty", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("expiry", CLType::U256),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_limit_order_cspr",
+        vec![
+            Parameter::new("amount_in", CLType::U256),
+            Parameter::new("amount_out_min", CLType::U256),
+            Parameter::new("bounty", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("expiry", CLType::U256),
+            Parameter::new("purse", CLType::URef),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "fill_order",
+        vec![Parameter::new("order_id", CLType::U256)],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel_order",
+        vec![Parameter::new("order_id", CLType::U256)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_dca_order",
+        vec![
+            Parameter::new("budget", CLType::U256),
+            Parameter::new("amount_per_interval", CLType::U256),
+            Parameter::new("interval", CLType::U64),
+            Parameter::new("amount_out_min", CLType::U256),
+            Parameter::new("fee", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_dca_order_cspr",
+        vec![
+            Parameter::new("budget", CLType::U256),
+            Parameter::new("amount_per_interval", CLType::U256),
+            Parameter::new("interval", CLType::U64),
+            Parameter::new("amount_out_min", CLType::U256),
+            Parameter::new("fee", CLType::U256),
+            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
+            Parameter::new("to", CLType::Key),
+            Parameter::new("purse", CLType::URef),
+        ],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "execute_dca_order",
+        vec![Parameter::new("order_id", CLType::U256)],
+        CLType::List(Box::new(CLType::U256)),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pause_dca_order",
+        vec![Parameter::new("order_id", CLType::U256)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "resume_dca_order",
+        vec![Parameter::new("order_id", CLType::U256)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "withdraw_dca_order",
+        vec![Parameter::new("order_id", CLType::U256)],
+        CLType::U256,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "receive",
+        vec![
+            Parameter::new("amount", U512::cl_type()),
+            Parameter::new("purse", URef::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_factory",
+        vec![],
+        ContractPackageHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_wcspr",
+        vec![],
+        ContractPackageHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_library_hash",
+        vec![],
+        ContractPackageHash::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "queue_operation",
+        vec![
+            Parameter::new("action", String::cl_type()),
+            Parameter::new("target", Key::cl_type()),
+            Parameter::new("value", u64::cl_type()),
+            Parameter::new("eta", u64::cl_type()),
+        ],
+        String::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "cancel_operation",
+        vec![Parameter::new("operation_hash", String::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "execute_operation",
+        vec![Parameter::new("operation_hash", String::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_factory",
+        vec![
+            Parameter::new("factory", Key::cl_type()),
+            Parameter::new("eta", u64::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_wcspr",
+        vec![
+            Parameter::new("wcspr", Key::cl_type()),
+            Parameter::new("eta", u64::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_library_hash",
+        vec![
+            Parameter::new("library_hash", Key::cl_type()),
+            Parameter::new("eta", u64::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_operation",
+        vec![Parameter::new("operation_hash", String::cl_type())],
+        TimelockOperation::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "pause",
+        vec![],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "rescue_cspr",
+        vec![
+            Parameter::new("amount", U512::cl_type()),
+            Parameter::new("to_purse", URef::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "rescue_tokens",
+        vec![
+            Parameter::new("token", Key::cl_type()),
+            Parameter::new("amount", U256::cl_type()),
+            Parameter::new("to", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_purse_balance",
+        vec![],
+        U512::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "migrate",
+        vec![],
+        u32::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_enabled_versions",
+        vec![],
+        CLType::List(Box::new(ContractHash::cl_type())),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "record_versions",
+        vec![Parameter::new("disable_previous_versions", CLType::Bool)],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("upgrader")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_storage_version",
+        vec![],
+        u32::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "is_paused",
+        vec![],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "get_timelock_delay",
+        vec![],
+        u64::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points
+}
+
+// Errors of the pricing math along a path, from the linked library or the library contract.
+const PRICING: [Errors; 7] = [
+    Errors::InvalidPath,
+    Errors::PairNotFound,
+    Errors::IdenticalAddresses,
+    Errors::InsufficientInputAmount,
+    Errors::InsufficientOutputAmount,
+    Errors::InsufficientLiquidity,
+    Errors::ArithmeticOverflow,
+];
+
+// Checks of every entry point that moves tokens for the caller.
+const TRADE: [Errors; 3] = [Errors::Paused, Errors::TimedOut, Errors::InvalidRecipient];
+
+const ADD_LIQUIDITY: [Errors; 6] = [
+    Errors::PairNotFound,
+    Errors::NotInWhitelist,
+    Errors::OptimalAmountExceedsDesired,
+    Errors::IdenticalAddresses,
+    Errors::InsufficientAmount,
+    Errors::InsufficientLiquidity,
+];
+
+const REMOVE_LIQUIDITY: [Errors; 6] = [
+    Errors::Paused,
+    Errors::TimedOut,
+    Errors::InvalidRecipient,
+    Errors::PairNotFound,
+    Errors::IdenticalAddresses,
+    Errors::SlippageExceeded { side: Side::Output },
+];
+
+/// Errors an entry point can revert with, `None` for a name that is not an entry point.
+pub fn entry_point_errors(entry_point: &str) -> Option<Vec<Errors>> {
+    let errors: Vec<Errors> = match entry_point {
+        "constructor"
+        | "check_deadline"
+        | "get_factory"
+        | "get_wcspr"
+        | "get_library_hash"
+        | "get_purse_balance"
+        | "get_storage_version"
+        | "get_enabled_versions"
+        | "record_versions"
+        | "is_paused"
+        | "get_timelock_delay" => {
+            vec![]
+        }
+        "add_liquidity" => [
+            &TRADE[..],
+            &[Errors::AmountADesiredIsZero, Errors::AmountBDesiredIsZero],
+            &ADD_LIQUIDITY,
+        ]
+        .concat(),
+        "add_liquidity_cspr" => [
+            &TRADE[..],
+            &[Errors::AmountTokenIsZero, Errors::InsufficientCspr],
+            &ADD_LIQUIDITY,
+        ]
+        .concat(),
+        "remove_liquidity" | "remove_liquidity_cspr" => REMOVE_LIQUIDITY.to_vec(),
+        "swap_exact_tokens_for_tokens" => [
+            &TRADE[..],
+            &PRICING,
+            &[Errors::SlippageExceeded { side: Side::Output }],
+        ]
+        .concat(),
+        "swap_tokens_for_exact_tokens" => [
+            &TRADE[..],
+            &PRICING,
+            &[Errors::SlippageExceeded { side: Side::Input }],
+        ]
+        .concat(),
+        "swap_exact_cspr_for_tokens" => [
+            &TRADE[..],
+            &PRICING,
+            &[
+                Errors::PathMustStartWithWcspr,
+                Errors::SlippageExceeded { side: Side::Output },
+                Errors::InsufficientCspr,
+            ],
+        ]
+        .concat(),
+        "swap_cspr_for_exact_tokens" => [
+            &TRADE[..],
+            &PRICING,
+            &[
+                Errors::PathMustStartWithWcspr,
+                Errors::SlippageExceeded { side: Side::Input },
+                Errors::InsufficientCspr,
+            ],
+        ]
+        .concat(),
+        "swap_exact_tokens_for_cspr" => [
+            &TRADE[..],
+            &PRICING,
+            &[
+                Errors::PathMustEndWithWcspr,
+                Errors::SlippageExceeded { side: Side::Output },
+            ],
+        ]
+        .concat(),
+        "swap_tokens_for_exact_cspr" => [
+            &TRADE[..],
+            &PRICING,
+            &[
+                Errors::PathMustEndWithWcspr,
+                Errors::SlippageExceeded { side: Side::Input },
+            ],
+        ]
+        .concat(),
+        "quote" => vec![Errors::InsufficientAmount, Errors::InsufficientLiquidity],
+        "get_amount_out" => vec![
+            Errors::InsufficientInputAmount,
+            Errors::InsufficientLiquidity,
+            Errors::ArithmeticOverflow,
+        ],
+        "get_amount_in" => vec![
+            Errors::InsufficientOutputAmount,
+            Errors::InsufficientLiquidity,
+            Errors::ArithmeticOverflow,
+        ],
+        "get_amounts_out" | "get_amounts_in" => PRICING.to_vec(),
+        "create_limit_order" => vec![
+            Errors::Paused,
+            Errors::InvalidPath,
+            Errors::PairNotFound,
+            Errors::IdenticalAddresses,
+            Errors::InvalidRecipient,
+            Errors::InvalidOrder,
+            Errors::OrderExpired,
+            Errors::OrderEscrowOverflow,
+        ],
+        "create_limit_order_cspr" => vec![
+            Errors::Paused,
+            Errors::InvalidPath,
+            Errors::PairNotFound,
+            Errors::IdenticalAddresses,
+            Errors::InvalidRecipient,
+            Errors::InvalidOrder,
+            Errors::OrderExpired,
+            Errors::OrderEscrowOverflow,
+            Errors::InsufficientCspr,
+        ],
+        "fill_order" => [
+            &[
+                Errors::Paused,
+                Errors::OrderNotFound,
+                Errors::OrderNotOpen,
+                Errors::OrderExpired,
+                Errors::OrderLimitNotReached,
+            ][..],
+            &PRICING,
+        ]
+        .concat(),
+        "cancel_order" => vec![
+            Errors::OrderNotFound,
+            Errors::NotOrderOwner,
+            Errors::OrderNotOpen,
+        ],
+        "create_dca_order" => vec![
+            Errors::Paused,
+            Errors::InvalidPath,
+            Errors::PairNotFound,
+            Errors::IdenticalAddresses,
+            Errors::InvalidRecipient,
+            Errors::InvalidDcaOrder,
+        ],
+        "create_dca_order_cspr" => vec![
+            Errors::Paused,
+            Errors::InvalidPath,
+            Errors::PairNotFound,
+            Errors::IdenticalAddresses,
+            Errors::InvalidRecipient,
+            Errors::InvalidDcaOrder,
+            Errors::InsufficientCspr,
+        ],
+        "execute_dca_order" => [
+            &[
+                Errors::Paused,
+                Errors::DcaOrderNotFound,
+                Errors::DcaOrderNotActive,
+                Errors::DcaOrderNotDue,
+                Errors::DcaBudgetExhausted,
+                Errors::DcaInsufficientOutputAmount,
+            ][..],
+            &PRICING,
+        ]
+        .concat(),
+        "pause_dca_order" => vec![
+            Errors::Paused,
+            Errors::DcaOrderNotFound,
+            Errors::NotDcaOrderOwner,
+            Errors::DcaOrderNotActive,
+        ],
+        "withdraw_dca_order" => vec![
+            Errors::DcaOrderNotFound,
+            Errors::NotDcaOrderOwner,
+            Errors::DcaOrderNotActive,
+        ],
+        "resume_dca_order" => vec![
+            Errors::Paused,
+            Errors::DcaOrderNotFound,
+            Errors::NotDcaOrderOwner,
+            Errors::DcaOrderNotPaused,
+        ],
+        "receive" => vec![Errors::ReceiveNotFromWcspr, Errors::UnexpectedCspr],
+        "queue_operation" => vec![
+            Errors::NotOwner,
+            Errors::InvalidOperation,
+            Errors::OperationEtaTooEarly,
+            Errors::OperationEtaOutOfRange,
+        ],
+        "cancel_operation" => vec![
+            Errors::NotOwner,
+            Errors::OperationNotFound,
+            Errors::OperationNotQueued,
+        ],
+        "execute_operation" => vec![
+            Errors::NotOwner,
+            Errors::OperationNotFound,
+            Errors::OperationNotQueued,
+            Errors::OperationTimelocked,
+            Errors::OperationStale,
+            Errors::OperationEtaOutOfRange,
+            Errors::InvalidOperation,
+            Errors::WcsprHeldInEscrow,
+        ],
+        "set_factory" | "set_wcspr" | "set_library_hash" => vec![
+            Errors::NotOwner,
+            Errors::OperationEtaTooEarly,
+            Errors::OperationEtaOutOfRange,
+            Errors::OperationNotQueued,
+            Errors::OperationTimelocked,
+            Errors::OperationStale,
+            Errors::WcsprHeldInEscrow,
+        ],
+        "get_operation" => vec![Errors::OperationNotFound],
+        "pause" | "rescue_cspr" => vec![Errors::NotOwner],
+        "rescue_tokens" => vec![Errors::NotOwner, Errors::RescueExceedsFreeBalance],
+        "migrate" => vec![Errors::NotOwner, Errors::StorageVersionUnsupported],
+        _ => return None,
+    };
+    Some(errors)
+}
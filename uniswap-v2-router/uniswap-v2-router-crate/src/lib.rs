@@ -1,6 +1,19 @@
+pub mod admin;
 pub mod data;
+pub mod dca;
+pub mod deadline;
+pub mod entry_points;
+pub mod limit_orders;
+pub mod migrations;
+pub mod recipient;
+pub mod timelock;
 pub mod transfer_helper;
 pub mod uniswap_v2_router;
 
+pub use admin::RouterAdmin;
 pub use common::*;
+pub use dca::DcaScheduler;
+pub use limit_orders::LimitOrderBook;
+pub use recipient::Recipient;
+pub use timelock::Timelock;
 pub use uniswap_v2_router::UniswapV2Router;
@@ -0,0 +1,197 @@
+use crate::{
+    data::*,
+    deadline::check_deadline,
+    recipient::Recipient,
+    transfer_helper::transfer_helper_mod,
+    uniswap_v2_router::{ROUTEREvent, UniswapV2Router},
+};
+use common::{
+    contract_api::{runtime, system},
+    errors::Errors,
+    functions::*,
+    keys::*,
+    unwrap_or_revert::UnwrapOrRevert,
+    *,
+};
+
+// Limit orders escrow the input asset in the router until anyone fills them at or above the
+// requested output, or until the owner cancels them. Cspr escrow is kept wrapped as wcspr.
+pub trait LimitOrderBook<Storage: ContractStorage>: UniswapV2Router<Storage> {
+    #[allow(clippy::too_many_arguments)]
+    fn create_limit_order(
+        &self,
+        amount_in: U256,
+        amount_out_min: U256,
+        bounty: U256,
+        _path: Vec<String>,
+        to: Key,
+        expiry: U256,
+    ) -> U256 {
+        Self::_when_not_paused();
+        let path: Vec<Key> = Self::_parse_path(&_path);
+        let escrow: U256 = self._validate_order(&path, amount_in, bounty, expiry);
+        // router must be approved by the caller for amount_in + bounty
+        transfer_helper_mod::safe_transfer_from(
+            path[0],
+            self.get_caller(),
+            Key::from(get_package_hash()),
+            escrow,
+        );
+        self._store_order(LimitOrder {
+            owner: self.get_caller(),
+            path,
+            amount_in,
+            amount_out_min,
+            bounty,
+            to: Recipient::from_key(to).token_key(),
+            expiry,
+            cspr: false,
+            status: ORDER_OPEN,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_limit_order_cspr(
+        &self,
+        amount_in: U256,
+        amount_out_min: U256,
+        bounty: U256,
+        _path: Vec<String>,
+        to: Key,
+        caller_purse: URef,
+        expiry: U256,
+    ) -> U256 {
+        Self::_when_not_paused();
+        let path: Vec<Key> = Self::_parse_path(&_path);
+        if path.is_empty() || path[0] != Key::from(wcspr()) {
+            runtime::revert(Errors::InvalidOrder);
+        }
+        let escrow: U256 = self._validate_order(&path, amount_in, bounty, expiry);
+        system::transfer_from_purse_to_purse(
+            caller_purse,
+            get_purse().into_add(),
+            u256_to_u512(escrow),
+            None,
+        )
+        .unwrap_or_revert_with(Errors::InsufficientCspr);
+        // wrap the escrow so that filling the order can go through the pairs like any token order
+        runtime::call_versioned_contract::<()>(
+            wcspr(),
+            None,
+            WCSPR_DEPOSIT,
+            runtime_args! {
+                "amount" => u256_to_u512(escrow),
+                "purse" => get_purse()
+            },
+        );
+        self._store_order(LimitOrder {
+            owner: self.get_caller(),
+            path,
+            amount_in,
+            amount_out_min,
+            bounty,
+            to: Recipient::from_key(to).token_key(),
+            expiry,
+            cspr: true,
+            status: ORDER_OPEN,
+        })
+    }
+
+    fn fill_order(&self, order_id: U256) -> Vec<U256> {
+        Self::_when_not_paused();
+        let orders = LimitOrders::instance();
+        let mut order: LimitOrder = orders
+            .get(&order_id)
+            .unwrap_or_revert_with(Errors::OrderNotFound);
+        if order.status != ORDER_OPEN {
+            runtime::revert(Errors::OrderNotOpen);
+        }
+        if check_deadline(order.expiry).is_err() {
+            runtime::revert(Errors::OrderExpired);
+        }
+        let pairs: Vec<Key> = Self::_pairs_for(&order.path);
+        let amounts: Vec<U256> = Self::_amounts_out(order.amount_in, &order.path, &pairs);
+        if amounts[amounts.len() - 1] < order.amount_out_min {
+            runtime::revert(Errors::OrderLimitNotReached);
+        }
+        // close the order before moving any escrow
+        order.status = ORDER_FILLED;
+        orders.set(&order_id, order.clone());
+        EscrowBalances::instance().release(&order.path[0], amounts[0]);
+        transfer_helper_mod::safe_transfer(order.path[0], pairs[0], amounts[0]);
+        Self::_swap(&amounts, &order.path, &pairs, order.to);
+        if order.bounty > 0.into() {
+            transfer_helper_mod::release_escrow(
+                order.path[0],
+                order.cspr,
+                self.get_caller(),
+                order.bounty,
+            );
+        }
+        self.emit(&ROUTEREvent::LimitOrderFilled {
+            order_id,
+            filler: self.get_caller(),
+            amount_in: amounts[0],
+            amount_out: amounts[amounts.len() - 1],
+        });
+        amounts
+    }
+
+    fn cancel_order(&self, order_id: U256) {
+        let orders = LimitOrders::instance();
+        let mut order: LimitOrder = orders
+            .get(&order_id)
+            .unwrap_or_revert_with(Errors::OrderNotFound);
+        if self.get_caller() != order.owner {
+            runtime::revert(Errors::NotOrderOwner);
+        }
+        if order.status != ORDER_OPEN {
+            runtime::revert(Errors::OrderNotOpen);
+        }
+        order.status = ORDER_CANCELLED;
+        orders.set(&order_id, order.clone());
+        // amount_in + bounty was checked for overflow when the order was created
+        transfer_helper_mod::release_escrow(
+            order.path[0],
+            order.cspr,
+            order.owner,
+            order.amount_in + order.bounty,
+        );
+        self.emit(&ROUTEREvent::LimitOrderCancelled {
+            order_id,
+            owner: order.owner,
+        });
+    }
+
+    // *************************************** Helper methods ****************************************
+    fn _validate_order(&self, path: &[Key], amount_in: U256, bounty: U256, expiry: U256) -> U256 {
+        if path.len() < 2 || amount_in == 0.into() {
+            runtime::revert(Errors::InvalidOrder);
+        }
+        // escrow is taken in the path's first token, the path's pairs must already exist
+        Self::_pairs_for(path);
+        if check_deadline(expiry).is_err() {
+            runtime::revert(Errors::OrderExpired);
+        }
+        amount_in
+            .checked_add(bounty)
+            .unwrap_or_revert_with(Errors::OrderEscrowOverflow)
+    }
+
+    fn _store_order(&self, order: LimitOrder) -> U256 {
+        let order_id: U256 = limit_order_count();
+        set_limit_order_count(order_id + 1);
+        self.emit(&ROUTEREvent::LimitOrderCreated {
+            order_id,
+            owner: order.owner,
+            amount_in: order.amount_in,
+            amount_out_min: order.amount_out_min,
+            bounty: order.bounty,
+            expiry: order.expiry,
+        });
+        // amount_in + bounty was checked for overflow by _validate_order
+        EscrowBalances::instance().lock(&order.path[0], order.amount_in + order.bounty);
+        LimitOrders::instance().set(&order_id, order);
+        order_id
+    }
+}
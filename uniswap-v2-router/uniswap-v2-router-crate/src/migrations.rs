@@ -0,0 +1,35 @@
+use crate::{data::*, timelock::TIMELOCK_DEFAULT_DELAY};
+use common::{contract_api::runtime, errors::Errors};
+
+// Storage version written by `init` and reached by `migrate`.
+pub const CURRENT_STORAGE_VERSION: u32 = 2;
+
+// Brings storage written by an older contract version up to the current layout, one version at a
+// time. New named keys and dictionaries an upgrade depends on are created here.
+pub fn migrate() -> u32 {
+    let mut version: u32 = storage_version();
+    if version > CURRENT_STORAGE_VERSION {
+        runtime::revert(Errors::StorageVersionUnsupported);
+    }
+    while version < CURRENT_STORAGE_VERSION {
+        match version {
+            1 => migrate_v1_to_v2(),
+            _ => runtime::revert(Errors::StorageVersionUnsupported),
+        }
+        version += 1;
+        set_storage_version(version);
+    }
+    version
+}
+
+// Version 2 added limit and dca orders, the pair cache, the timelock and escrow accounting.
+fn migrate_v1_to_v2() {
+    LimitOrders::init();
+    set_limit_order_count(0.into());
+    DcaOrders::init();
+    set_dca_order_count(0.into());
+    PairCache::init();
+    TimelockOperations::init();
+    set_timelock_delay(TIMELOCK_DEFAULT_DELAY);
+    EscrowBalances::init();
+}
@@ -0,0 +1,111 @@
+use crate::data::{set_pending_cspr, wcspr};
+use casper_types::system::CallStackElement;
+use common::{
+    account::AccountHash,
+    contract_api::{runtime, system},
+    errors::Errors,
+    functions::*,
+    keys::*,
+    unwrap_or_revert::UnwrapOrRevert,
+    *,
+};
+
+// Receiver of the output of an entry point, passed to the router as a `to: Key`.
+// Accounts and contract packages can receive tokens and cspr, purses can only receive cspr.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Recipient {
+    Account(AccountHash),
+    Contract(ContractPackageHash),
+    Purse(URef),
+}
+
+impl Recipient {
+    pub fn from_key(to: Key) -> Recipient {
+        match to {
+            Key::Account(account) => Recipient::Account(account),
+            Key::Hash(hash) => Recipient::Contract(package_of(hash)),
+            Key::URef(purse) => Recipient::Purse(purse),
+            _ => runtime::revert(Errors::InvalidRecipient),
+        }
+    }
+
+    // Key that tokens are sent to, purses can't hold tokens.
+    pub fn token_key(&self) -> Key {
+        match self {
+            Recipient::Account(account) => Key::Account(*account),
+            Recipient::Contract(package) => Key::from(*package),
+            Recipient::Purse(_) => runtime::revert(Errors::InvalidRecipient),
+        }
+    }
+
+    // Unwraps amount of wcspr held by the router and delivers the cspr. Accounts are paid into
+    // their main purse, contracts through their `receive` entry point.
+    pub fn send_cspr(&self, amount: U256) {
+        match self {
+            Recipient::Purse(purse) => withdraw_wcspr(*purse, amount),
+            Recipient::Account(account) => {
+                withdraw_wcspr(get_purse().into_add(), amount);
+                system::transfer_from_purse_to_account(
+                    get_purse(),
+                    *account,
+                    u256_to_u512(amount),
+                    None,
+                )
+                .unwrap_or_revert();
+            }
+            Recipient::Contract(package) => {
+                withdraw_wcspr(get_purse().into_add(), amount);
+                // hand over a purse holding only the amount, never the router's own purse
+                let purse: URef = system::create_purse();
+                system::transfer_from_purse_to_purse(
+                    get_purse(),
+                    purse,
+                    u256_to_u512(amount),
+                    None,
+                )
+                .unwrap_or_revert();
+                runtime::call_versioned_contract::<()>(
+                    *package,
+                    None,
+                    RECIPIENT_RECEIVE,
+                    runtime_args! {
+                        "amount" => u256_to_u512(amount),
+                        "purse" => purse
+                    },
+                );
+            }
+        }
+    }
+}
+
+// A `Key::Hash` recipient must be a contract package, a contract hash can't receive cspr as the
+// router calls `receive` through the package. Contract code can't read what a hash names, but the
+// contracts on the call stack are known with both hashes, so a caller passing its own contract
+// hash is paid through its package. Other hashes are taken to be packages.
+fn package_of(hash: HashAddr) -> ContractPackageHash {
+    runtime::get_call_stack()
+        .into_iter()
+        .find_map(|element| match element {
+            CallStackElement::StoredContract {
+                contract_package_hash,
+                contract_hash,
+            } if contract_hash.value() == hash => Some(contract_package_hash),
+            _ => None,
+        })
+        .unwrap_or_else(|| hash.into())
+}
+
+// Only while the withdraw runs can wcspr hand the cspr to the router's `receive`.
+fn withdraw_wcspr(purse: URef, amount: U256) {
+    set_pending_cspr(u256_to_u512(amount));
+    runtime::call_versioned_contract::<()>(
+        wcspr(),
+        None,
+        WCSPR_WITHDRAW,
+        runtime_args! {
+            "purse" => purse,
+            "amount" => u256_to_u512(amount)
+        },
+    );
+    set_pending_cspr(0.into());
+}
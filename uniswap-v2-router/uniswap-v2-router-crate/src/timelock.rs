@@ -0,0 +1,161 @@
+use crate::{admin::RouterAdmin, data::*, uniswap_v2_router::ROUTEREvent};
+use common::{
+    bytesrepr::ToBytes, contract_api::runtime, errors::Errors, functions::block_timestamp,
+    unwrap_or_revert::UnwrapOrRevert, *,
+};
+
+// Delay of a new router, in milliseconds.
+pub const TIMELOCK_DEFAULT_DELAY: u64 = 2 * 24 * 60 * 60 * 1000;
+// Time after the eta in which a queued operation can still be executed.
+pub const TIMELOCK_GRACE_PERIOD: u64 = 14 * 24 * 60 * 60 * 1000;
+
+// Privileged calls are queued by the owner with an eta at least the timelock delay away, and
+// executed by the owner between the eta and the end of the grace period. Operations are kept in
+// the `timelock_operations` dictionary under their hash.
+//
+// Actions and the argument they take:
+// change_owner, add_to_whitelist, remove_from_whitelist, set_factory, set_wcspr and
+// set_library_hash take `target`, set_timelock_delay takes `value`, unpause takes neither.
+pub trait Timelock<Storage: ContractStorage>: RouterAdmin<Storage> {
+    fn queue_operation(&self, action: String, target: Key, value: u64, eta: u64) -> String {
+        self._only_owner();
+        if !Self::_is_timelock_action(&action) {
+            runtime::revert(Errors::InvalidOperation);
+        }
+        let earliest: u64 = block_timestamp()
+            .checked_add(timelock_delay())
+            .unwrap_or_revert_with(Errors::OperationEtaOutOfRange);
+        if eta < earliest {
+            runtime::revert(Errors::OperationEtaTooEarly);
+        }
+        // the grace period has to end within the block time for the operation to be executable
+        if eta.checked_add(TIMELOCK_GRACE_PERIOD).is_none() {
+            runtime::revert(Errors::OperationEtaOutOfRange);
+        }
+        let operation = TimelockOperation {
+            action,
+            target,
+            value,
+            eta,
+            status: OPERATION_QUEUED,
+        };
+        let operation_hash: String = Self::operation_hash(&operation);
+        TimelockOperations::instance().set(&operation_hash, operation.clone());
+        self.emit(&ROUTEREvent::OperationQueued {
+            operation_hash: operation_hash.clone(),
+            action: operation.action,
+            eta,
+        });
+        operation_hash
+    }
+
+    fn cancel_operation(&self, operation_hash: String) {
+        self._only_owner();
+        let operations = TimelockOperations::instance();
+        let mut operation: TimelockOperation = self._queued_operation(&operations, &operation_hash);
+        operation.status = OPERATION_CANCELLED;
+        operations.set(&operation_hash, operation);
+        self.emit(&ROUTEREvent::OperationCancelled { operation_hash });
+    }
+
+    fn execute_operation(&self, operation_hash: String) {
+        self._only_owner();
+        let operations = TimelockOperations::instance();
+        let mut operation: TimelockOperation = self._queued_operation(&operations, &operation_hash);
+        let now: u64 = block_timestamp();
+        if now < operation.eta {
+            runtime::revert(Errors::OperationTimelocked);
+        }
+        let expiry: u64 = operation
+            .eta
+            .checked_add(TIMELOCK_GRACE_PERIOD)
+            .unwrap_or_revert_with(Errors::OperationEtaOutOfRange);
+        if now > expiry {
+            runtime::revert(Errors::OperationStale);
+        }
+        operation.status = OPERATION_EXECUTED;
+        operations.set(&operation_hash, operation.clone());
+        let target =
+            || -> ContractPackageHash { operation.target.into_hash().unwrap_or_revert().into() };
+        match operation.action.as_str() {
+            "change_owner" => self.change_owner(operation.target),
+            "add_to_whitelist" => self.add_to_whitelist(operation.target),
+            "remove_from_whitelist" => self.remove_from_whitelist(operation.target),
+            "set_factory" => self.set_factory(target()),
+            "set_wcspr" => self.set_wcspr(target()),
+            "set_library_hash" => self.set_library_hash(target()),
+            "set_timelock_delay" => self.set_timelock_delay(operation.value),
+            "unpause" => self.unpause(),
+            _ => runtime::revert(Errors::InvalidOperation),
+        }
+        self.emit(&ROUTEREvent::OperationExecuted { operation_hash });
+    }
+
+    // The `set_factory`, `set_wcspr` and `set_library_hash` entry points: the first call queues
+    // the change for `eta`, calling again with the same arguments once eta has passed executes it.
+    fn set_reference(&self, action: &str, target: Key, eta: u64) {
+        let operation = TimelockOperation {
+            action: action.into(),
+            target,
+            value: 0,
+            eta,
+            status: OPERATION_QUEUED,
+        };
+        let operation_hash: String = Self::operation_hash(&operation);
+        if TimelockOperations::instance()
+            .get(&operation_hash)
+            .is_some()
+        {
+            self.execute_operation(operation_hash);
+        } else {
+            self.queue_operation(operation.action, target, 0, eta);
+        }
+    }
+
+    // Anyone can look up an operation, to see what is pending before its eta.
+    fn operation(&self, operation_hash: String) -> TimelockOperation {
+        TimelockOperations::instance()
+            .get(&operation_hash)
+            .unwrap_or_revert_with(Errors::OperationNotFound)
+    }
+
+    // Hex encoded blake2b hash of the action, its arguments and the eta.
+    fn operation_hash(operation: &TimelockOperation) -> String {
+        let mut bytes: Vec<u8> = operation.action.to_bytes().unwrap_or_revert();
+        bytes.append(&mut operation.target.to_bytes().unwrap_or_revert());
+        bytes.append(&mut operation.value.to_bytes().unwrap_or_revert());
+        bytes.append(&mut operation.eta.to_bytes().unwrap_or_revert());
+        runtime::blake2b(bytes)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    fn _queued_operation(
+        &self,
+        operations: &TimelockOperations,
+        operation_hash: &str,
+    ) -> TimelockOperation {
+        let operation: TimelockOperation = operations
+            .get(operation_hash)
+            .unwrap_or_revert_with(Errors::OperationNotFound);
+        if operation.status != OPERATION_QUEUED {
+            runtime::revert(Errors::OperationNotQueued);
+        }
+        operation
+    }
+
+    fn _is_timelock_action(action: &str) -> bool {
+        matches!(
+            action,
+            "change_owner"
+                | "add_to_whitelist"
+                | "remove_from_whitelist"
+                | "set_factory"
+                | "set_wcspr"
+                | "set_library_hash"
+                | "set_timelock_delay"
+                | "unpause"
+        )
+    }
+}
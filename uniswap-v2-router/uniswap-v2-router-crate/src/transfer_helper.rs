@@ -1,4 +1,5 @@
 pub mod transfer_helper_mod {
+    use crate::{data::EscrowBalances, recipient::Recipient};
     use common::{contract_api::runtime, *};
 
     pub fn safe_transfer(token: Key, to: Key, value: U256) {
@@ -27,4 +28,14 @@ pub mod transfer_helper_mod {
             },
         );
     }
+
+    // Pays out escrow held by the router. Cspr escrow is kept as wcspr and unwrapped on release.
+    pub fn release_escrow(token: Key, cspr: bool, recipient: Key, amount: U256) {
+        EscrowBalances::instance().release(&token, amount);
+        if cspr {
+            Recipient::from_key(recipient).send_cspr(amount);
+        } else {
+            safe_transfer(token, recipient, amount);
+        }
+    }
 }
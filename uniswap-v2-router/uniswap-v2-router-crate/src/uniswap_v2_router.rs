@@ -1,14 +1,18 @@
 use std::collections::BTreeMap;
 
-use crate::{data::*, transfer_helper::transfer_helper_mod};
+use crate::{
+    data::*, deadline::check_deadline, migrations::CURRENT_STORAGE_VERSION, recipient::Recipient,
+    timelock::TIMELOCK_DEFAULT_DELAY, transfer_helper::transfer_helper_mod,
+};
 use common::{
     contract_api::{runtime, storage, system},
-    errors::Errors,
+    errors::{Errors, Side},
     functions::*,
     keys::*,
     unwrap_or_revert::UnwrapOrRevert,
     *,
 };
+use uniswap_v2_library_crate::math;
 
 pub enum ROUTEREvent {
     AddReserves {
@@ -23,6 +27,81 @@ pub enum ROUTEREvent {
         reserve1: U256,
         pair_contract_hash: ContractPackageHash,
     },
+    LimitOrderCreated {
+        order_id: U256,
+        owner: Key,
+        amount_in: U256,
+        amount_out_min: U256,
+        bounty: U256,
+        expiry: U256,
+    },
+    LimitOrderFilled {
+        order_id: U256,
+        filler: Key,
+        amount_in: U256,
+        amount_out: U256,
+    },
+    LimitOrderCancelled {
+        order_id: U256,
+        owner: Key,
+    },
+    DcaOrderCreated {
+        order_id: U256,
+        owner: Key,
+        budget: U256,
+        amount_per_interval: U256,
+        interval: u64,
+        fee: U256,
+    },
+    DcaOrderExecuted {
+        order_id: U256,
+        keeper: Key,
+        amount_in: U256,
+        amount_out: U256,
+        budget: U256,
+    },
+    DcaOrderPaused {
+        order_id: U256,
+    },
+    DcaOrderResumed {
+        order_id: U256,
+    },
+    DcaOrderWithdrawn {
+        order_id: U256,
+        owner: Key,
+        amount: U256,
+    },
+    ReferenceChanged {
+        name: String,
+        old: ContractPackageHash,
+        new: ContractPackageHash,
+    },
+    OperationQueued {
+        operation_hash: String,
+        action: String,
+        eta: u64,
+    },
+    OperationCancelled {
+        operation_hash: String,
+    },
+    OperationExecuted {
+        operation_hash: String,
+    },
+    RescuedCspr {
+        amount: U512,
+        to_purse: URef,
+    },
+    RescuedTokens {
+        token: Key,
+        amount: U256,
+        to: Key,
+    },
+    Paused {
+        account: Key,
+    },
+    Unpaused {
+        account: Key,
+    },
 }
 
 impl ROUTEREvent {
@@ -40,6 +119,69 @@ impl ROUTEREvent {
                 reserve1: _,
                 pair_contract_hash: _,
             } => "removereserves",
+            ROUTEREvent::LimitOrderCreated {
+                order_id: _,
+                owner: _,
+                amount_in: _,
+                amount_out_min: _,
+                bounty: _,
+                expiry: _,
+            } => "limitordercreated",
+            ROUTEREvent::LimitOrderFilled {
+                order_id: _,
+                filler: _,
+                amount_in: _,
+                amount_out: _,
+            } => "limitorderfilled",
+            ROUTEREvent::LimitOrderCancelled {
+                order_id: _,
+                owner: _,
+            } => "limitordercancelled",
+            ROUTEREvent::DcaOrderCreated {
+                order_id: _,
+                owner: _,
+                budget: _,
+                amount_per_interval: _,
+                interval: _,
+                fee: _,
+            } => "dcaordercreated",
+            ROUTEREvent::DcaOrderExecuted {
+                order_id: _,
+                keeper: _,
+                amount_in: _,
+                amount_out: _,
+                budget: _,
+            } => "dcaorderexecuted",
+            ROUTEREvent::DcaOrderPaused { order_id: _ } => "dcaorderpaused",
+            ROUTEREvent::DcaOrderResumed { order_id: _ } => "dcaorderresumed",
+            ROUTEREvent::DcaOrderWithdrawn {
+                order_id: _,
+                owner: _,
+                amount: _,
+            } => "dcaorderwithdrawn",
+            ROUTEREvent::ReferenceChanged {
+                name: _,
+                old: _,
+                new: _,
+            } => "referencechanged",
+            ROUTEREvent::OperationQueued {
+                operation_hash: _,
+                action: _,
+                eta: _,
+            } => "operationqueued",
+            ROUTEREvent::OperationCancelled { operation_hash: _ } => "operationcancelled",
+            ROUTEREvent::OperationExecuted { operation_hash: _ } => "operationexecuted",
+            ROUTEREvent::RescuedCspr {
+                amount: _,
+                to_purse: _,
+            } => "rescuedcspr",
+            ROUTEREvent::RescuedTokens {
+                token: _,
+                amount: _,
+                to: _,
+            } => "rescuedtokens",
+            ROUTEREvent::Paused { account: _ } => "paused",
+            ROUTEREvent::Unpaused { account: _ } => "unpaused",
         }
         .to_string()
     }
@@ -52,40 +194,65 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         factory: ContractPackageHash,
         wcspr: ContractPackageHash,
         library_hash: ContractPackageHash,
+        use_library_contract: bool,
         contract_hash: ContractHash,
         package_hash: ContractPackageHash,
     ) {
         set_factory(factory);
         set_wcspr(wcspr);
         set_library_hash(library_hash);
+        set_use_library_contract(use_library_contract);
         set_contract_hash(contract_hash);
         set_package_hash(package_hash);
         Whitelist::init();
+        LimitOrders::init();
+        set_limit_order_count(0.into());
+        DcaOrders::init();
+        PairCache::init();
+        TimelockOperations::init();
+        EscrowBalances::init();
+        set_storage_version(CURRENT_STORAGE_VERSION);
+        set_enabled_versions(vec![contract_hash]);
+        set_timelock_delay(TIMELOCK_DEFAULT_DELAY);
+        set_dca_order_count(0.into());
         set_owner(self.get_caller());
         set_purse(system::create_purse());
     }
 
     fn change_owner(&self, owner: Key) {
         if self.get_caller() != get_owner() {
-            runtime::revert(Errors::UniswapV2RouterNotOwner1);
+            runtime::revert(Errors::NotOwner);
         }
         set_owner(owner);
     }
 
     fn add_to_whitelist(&self, user: Key) {
         if self.get_caller() != get_owner() {
-            runtime::revert(Errors::UniswapV2RouterNotOwner2);
+            runtime::revert(Errors::NotOwner);
         }
         Whitelist::instance().set(&user, true);
     }
 
     fn remove_from_whitelist(&self, user: Key) {
         if self.get_caller() != get_owner() {
-            runtime::revert(Errors::UniswapV2RouterNotOwner3);
+            runtime::revert(Errors::NotOwner);
         }
         Whitelist::instance().set(&user, false);
     }
 
+    // Accepts cspr from wcspr up to the amount of the withdraw in progress.
+    fn receive(&self, amount: U512, purse: URef) {
+        if self.get_caller() != Key::from(wcspr()) {
+            runtime::revert(Errors::ReceiveNotFromWcspr);
+        }
+        let pending: U512 = pending_cspr();
+        if amount > pending {
+            runtime::revert(Errors::UnexpectedCspr);
+        }
+        set_pending_cspr(pending - amount);
+        system::transfer_from_purse_to_purse(purse, get_purse(), amount, None).unwrap_or_revert();
+    }
+
     #[inline(always)]
     #[allow(clippy::too_many_arguments)]
     fn add_liquidity(
@@ -100,14 +267,13 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         pair: Option<Key>,
         deadline: U256,
     ) -> (U256, U256, U256) {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut1 as u16));
-        }
+        self.ensure(deadline);
+        let to: Key = Recipient::from_key(to).token_key();
         if amount_a_desired <= 0.into() {
-            runtime::revert(Errors::UniswapV2RouterAmountADesiredIsZero);
+            runtime::revert(Errors::AmountADesiredIsZero);
         }
         if amount_b_desired <= 0.into() {
-            runtime::revert(Errors::UniswapV2RouterAmountBDesiredIsZero);
+            runtime::revert(Errors::AmountBDesiredIsZero);
         }
         let (amount_a, amount_b): (U256, U256) = self._add_liquidity(
             token_a,
@@ -118,17 +284,7 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             amount_b_min,
             pair,
         );
-        // call pair_for from library contract
-        let pair: Key = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_PAIR_FOR,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => Key::from(token_a),
-                "token_b" => Key::from(token_b)
-            },
-        );
+        let pair: Key = Self::_pairs_for(&[Key::from(token_a), Key::from(token_b)])[0];
         transfer_helper_mod::safe_transfer_from(
             Key::from(token_a),
             self.get_caller(),
@@ -141,7 +297,7 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             pair,
             amount_b,
         );
-        
+
         // call mint function from IUniswapV2Pair contract
         let liquidity: U256 = runtime::call_versioned_contract(
             pair.into_hash().unwrap_or_revert().into(),
@@ -174,9 +330,8 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         caller_purse: URef,
         deadline: U256,
     ) -> (U256, U256, U256) {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut2 as u16));
-        }
+        self.ensure(deadline);
+        let to: Key = Recipient::from_key(to).token_key();
         let (amount_token, amount_cspr): (U256, U256) = self._add_liquidity(
             token,
             wcspr(),
@@ -186,19 +341,9 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             amount_cspr_min,
             pair,
         );
-        // call pair_for from library contract
-        let pair: Key = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_PAIR_FOR,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => Key::from(token),
-                "token_b" => Key::from(wcspr())
-            },
-        );
+        let pair: Key = Self::_pairs_for(&[Key::from(token), Key::from(wcspr())])[0];
         if amount_token <= 0.into() {
-            runtime::revert(Errors::UniswapV2RouterAmountTokenIsZero);
+            runtime::revert(Errors::AmountTokenIsZero);
         }
         // call safe_transfer_from from TransferHelper
         transfer_helper_mod::safe_transfer_from(
@@ -213,7 +358,7 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             u256_to_u512(amount_cspr),
             None,
         )
-        .unwrap_or_revert();
+        .unwrap_or_revert_with(Errors::InsufficientCspr);
         // this call will submit cspr to the wcspr contract and in return get wcspr tokens which will be sent to pair
         runtime::call_versioned_contract::<()>(
             wcspr(),
@@ -265,20 +410,10 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         to: Key,
         deadline: U256,
     ) -> (U256, U256) {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut3 as u16));
-        }
-        // call pair_for from library contract
-        let pair: Key = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_PAIR_FOR,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => Key::from(token_a),
-                "token_b" => Key::from(token_b)
-            },
-        );
+        Self::_when_not_paused();
+        self.ensure(deadline);
+        let to: Key = Recipient::from_key(to).token_key();
+        let pair: Key = Self::_pairs_for(&[Key::from(token_a), Key::from(token_b)])[0];
         // call transferFrom from IUniSwapV2Pair
         runtime::call_versioned_contract::<()>(
             pair.into_hash().unwrap_or_revert().into(),
@@ -300,25 +435,14 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             },
         );
 
-        // call sortTokens from library contract
-        let (token0, _): (ContractPackageHash, ContractPackageHash) =
-            runtime::call_versioned_contract(
-                library_hash(),
-                None,
-                LIBRARY_SORT_TOKENS,
-                runtime_args! {
-                    "token_a" => Key::from(token_a),
-                    "token_b" => Key::from(token_b)
-                },
-            );
-
-        let (amount_a, amount_b): (U256, U256) = if token_a == token0 {
+        let (token0, _): (Key, Key) = Self::_sort_tokens(Key::from(token_a), Key::from(token_b));
+        let (amount_a, amount_b): (U256, U256) = if Key::from(token_a) == token0 {
             (amount0, amount1)
         } else {
             (amount1, amount0)
         };
         if amount_a < amount_a_min || amount_b < amount_b_min {
-            runtime::revert(Errors::UniswapV2RouterAbort1);
+            runtime::revert(Errors::SlippageExceeded { side: Side::Output });
         }
         self.emit(&ROUTEREvent::RemoveReserves {
             user: Key::from(runtime::get_caller()),
@@ -337,13 +461,13 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         liquidity: U256,
         amount_token_min: U256,
         amount_cspr_min: U256,
-        to: Key,        // to's key to transfer back token
-        to_purse: URef, // to's purse to transfer back cspr
+        to: Key,
         deadline: U256,
     ) -> (U256, U256) {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut4 as u16));
-        }
+        self.ensure(deadline);
+        // to receives both the token and the cspr, so it can't be a purse
+        let recipient: Recipient = Recipient::from_key(to);
+        let to: Key = recipient.token_key();
         // calling self contract's removeLiquidity
         let (amount_token, amount_cspr): (U256, U256) = self.remove_liquidity(
             token,
@@ -356,16 +480,8 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         );
         // transfer token to 'to'
         transfer_helper_mod::safe_transfer(Key::from(token), to, amount_token);
-        // call withdraw and transfer cspr to 'to'
-        runtime::call_versioned_contract::<()>(
-            wcspr(),
-            None,
-            WCSPR_WITHDRAW,
-            runtime_args! {
-                "purse" => to_purse,
-                "amount" => u256_to_u512(amount_cspr)
-            },
-        );
+        // unwrap wcspr and transfer cspr to 'to'
+        recipient.send_cspr(amount_cspr);
         (amount_token, amount_cspr)
     }
 
@@ -378,40 +494,16 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         to: Key,
         deadline: U256,
     ) -> Vec<U256> {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut5 as u16));
-        }
-        let mut path: Vec<Key> = Vec::new();
-        for i in &_path {
-            path.push(Key::from_formatted_str(i).unwrap());
-        }
-        // call getAmountsOut from Library contract
-        let amounts: Vec<U256> = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_GET_AMOUNTS_OUT,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "amount_in" => amount_in,
-                "path" => path.clone(),
-            },
-        );
+        self.ensure(deadline);
+        let to: Key = Recipient::from_key(to).token_key();
+        let path: Vec<Key> = Self::_swap_path(&_path);
+        let pairs: Vec<Key> = Self::_pairs_for(&path);
+        let amounts: Vec<U256> = Self::_amounts_out(amount_in, &path, &pairs);
         if amounts[amounts.len() - 1] < amount_out_min {
-            runtime::revert(Errors::UniswapV2RouterAbort2);
+            runtime::revert(Errors::SlippageExceeded { side: Side::Output });
         }
-        // get pair
-        let pair: Key = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_PAIR_FOR,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => path[0],
-                "token_b" => path[1],
-            },
-        );
-        transfer_helper_mod::safe_transfer_from(path[0], self.get_caller(), pair, amounts[0]);
-        Self::_swap(&amounts, &path, to);
+        transfer_helper_mod::safe_transfer_from(path[0], self.get_caller(), pairs[0], amounts[0]);
+        Self::_swap(&amounts, &path, &pairs, to);
         amounts
     }
 
@@ -424,39 +516,16 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         to: Key,
         deadline: U256,
     ) -> Vec<U256> {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut6 as u16));
-        }
-        let mut path: Vec<Key> = Vec::new();
-        for i in &_path {
-            path.push(Key::from_formatted_str(i).unwrap());
-        }
-        // call getAmountIn from Library contract
-        let amounts: Vec<U256> = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_GET_AMOUNTS_IN,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "amount_out" => amount_out,
-                "path" => path.clone(),
-            },
-        );
+        self.ensure(deadline);
+        let to: Key = Recipient::from_key(to).token_key();
+        let path: Vec<Key> = Self::_swap_path(&_path);
+        let pairs: Vec<Key> = Self::_pairs_for(&path);
+        let amounts: Vec<U256> = Self::_amounts_in(amount_out, &path, &pairs);
         if amounts[0] > amount_in_max {
-            runtime::revert(Errors::UniswapV2RouterAbort3);
+            runtime::revert(Errors::SlippageExceeded { side: Side::Input });
         }
-        let pair: Key = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_PAIR_FOR,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => path[0],
-                "token_b" => path[1],
-            },
-        );
-        transfer_helper_mod::safe_transfer_from(path[0], self.get_caller(), pair, amounts[0]);
-        Self::_swap(&amounts, &path, to);
+        transfer_helper_mod::safe_transfer_from(path[0], self.get_caller(), pairs[0], amounts[0]);
+        Self::_swap(&amounts, &path, &pairs, to);
         amounts
     }
 
@@ -470,29 +539,16 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         caller_purse: URef,
         deadline: U256,
     ) -> Vec<U256> {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut7 as u16));
-        }
-        let mut path: Vec<Key> = Vec::new();
-        for i in &_path {
-            path.push(Key::from_formatted_str(i).unwrap());
-        }
+        self.ensure(deadline);
+        let to: Key = Recipient::from_key(to).token_key();
+        let path: Vec<Key> = Self::_swap_path(&_path);
         if path[0] != Key::from(wcspr()) {
-            runtime::revert(Errors::UniswapV2RouterAbort4);
+            runtime::revert(Errors::PathMustStartWithWcspr);
         }
-        // call get_amounts_out
-        let amounts: Vec<U256> = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_GET_AMOUNTS_OUT,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "amount_in" => amount_in,
-                "path" => path.clone(),
-            },
-        );
+        let pairs: Vec<Key> = Self::_pairs_for(&path);
+        let amounts: Vec<U256> = Self::_amounts_out(amount_in, &path, &pairs);
         if amounts[amounts.len() - 1] < amount_out_min {
-            runtime::revert(Errors::UniswapV2RouterAbort5);
+            runtime::revert(Errors::SlippageExceeded { side: Side::Output });
         }
         system::transfer_from_purse_to_purse(
             caller_purse,
@@ -500,7 +556,7 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             u256_to_u512(amounts[0]),
             None,
         )
-        .unwrap_or_revert();
+        .unwrap_or_revert_with(Errors::InsufficientCspr);
         runtime::call_versioned_contract::<()>(
             wcspr(),
             None,
@@ -510,27 +566,17 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
                 "purse" => get_purse()
             },
         );
-        // call transfer method from IWETH, get pair
-        let pair: Key = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_PAIR_FOR,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => path[0],
-                "token_b" => path[1]
-            },
-        );
+        // transfer the wrapped cspr to the first pair
         runtime::call_versioned_contract::<()>(
             wcspr(),
             None,
             WCSPR_TRANSFER,
             runtime_args! {
-                "recipient" => pair,
+                "recipient" => pairs[0],
                 "amount" => amounts[0]
             },
         );
-        Self::_swap(&amounts, &path, to);
+        Self::_swap(&amounts, &path, &pairs, to);
         amounts
     }
 
@@ -540,56 +586,24 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         amount_out: U256,
         amount_in_max: U256,
         _path: Vec<String>,
-        to: URef, // recipient of cspr, must be a purse
+        to: Key,
         deadline: U256,
     ) -> Vec<U256> {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut8 as u16));
-        }
-        let mut path: Vec<Key> = Vec::new();
-        for i in &_path {
-            path.push(Key::from_formatted_str(i).unwrap());
-        }
+        self.ensure(deadline);
+        let recipient: Recipient = Recipient::from_key(to);
+        let path: Vec<Key> = Self::_swap_path(&_path);
         if path[path.len() - 1] != Key::from(wcspr()) {
-            runtime::revert(Errors::UniswapV2RouterAbort6);
+            runtime::revert(Errors::PathMustEndWithWcspr);
         }
-        // call getAmountIn from Library contract
-        let amounts: Vec<U256> = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_GET_AMOUNTS_IN,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "amount_out" => amount_out,
-                "path" => path.clone(),
-            },
-        );
+        let pairs: Vec<Key> = Self::_pairs_for(&path);
+        let amounts: Vec<U256> = Self::_amounts_in(amount_out, &path, &pairs);
         if amounts[0] > amount_in_max {
-            runtime::revert(Errors::UniswapV2RouterAbort7);
+            runtime::revert(Errors::SlippageExceeded { side: Side::Input });
         }
-        // call safeTransferFrom from TransferHelper, first need to get the pair
-        let pair: Key = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_PAIR_FOR,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => path[0],
-                "token_b" => path[1],
-            },
-        );
-        transfer_helper_mod::safe_transfer_from(path[0], self.get_caller(), pair, amounts[0]);
-        Self::_swap(&amounts, &path, Key::from(get_package_hash()));
-        // call withdraw from WCSPR and transfer cspr to 'to'
-        runtime::call_versioned_contract::<()>(
-            wcspr(),
-            None,
-            WCSPR_WITHDRAW,
-            runtime_args! {
-                "purse" => to,
-                "amount" => u256_to_u512(amounts[amounts.len() - 1])
-            },
-        );
+        transfer_helper_mod::safe_transfer_from(path[0], self.get_caller(), pairs[0], amounts[0]);
+        Self::_swap(&amounts, &path, &pairs, Key::from(get_package_hash()));
+        // unwrap wcspr and transfer cspr to 'to'
+        recipient.send_cspr(amounts[amounts.len() - 1]);
         amounts
     }
 
@@ -599,56 +613,24 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         amount_in: U256,
         amount_out_min: U256,
         _path: Vec<String>,
-        to: URef, // recipient of cspr, must be a purse
+        to: Key,
         deadline: U256,
     ) -> Vec<U256> {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut9 as u16));
-        }
-        let mut path: Vec<Key> = Vec::new();
-        for i in &_path {
-            path.push(Key::from_formatted_str(i).unwrap());
-        }
+        self.ensure(deadline);
+        let recipient: Recipient = Recipient::from_key(to);
+        let path: Vec<Key> = Self::_swap_path(&_path);
         if path[path.len() - 1] != Key::from(wcspr()) {
-            runtime::revert(Errors::UniswapV2RouterAbort8);
+            runtime::revert(Errors::PathMustEndWithWcspr);
         }
-        // call get_amounts_out
-        let amounts: Vec<U256> = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_GET_AMOUNTS_OUT,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "amount_in" => amount_in,
-                "path" => path.clone(),
-            },
-        );
+        let pairs: Vec<Key> = Self::_pairs_for(&path);
+        let amounts: Vec<U256> = Self::_amounts_out(amount_in, &path, &pairs);
         if amounts[amounts.len() - 1] < amount_out_min {
-            runtime::revert(Errors::UniswapV2RouterAbort9);
+            runtime::revert(Errors::SlippageExceeded { side: Side::Output });
         }
-        // call safeTransferFrom from TransferHelper, first need to get the pair
-        let pair: Key = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_PAIR_FOR,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => path[0],
-                "token_b" => path[1],
-            },
-        );
-        transfer_helper_mod::safe_transfer_from(path[0], self.get_caller(), pair, amounts[0]);
-        Self::_swap(&amounts, &path, Key::from(get_package_hash()));
-        // call withdraw from WCSPR and transfer cspr to 'to'
-        runtime::call_versioned_contract::<()>(
-            wcspr(),
-            None,
-            WCSPR_WITHDRAW,
-            runtime_args! {
-                "purse" => to,
-                "amount" => u256_to_u512(amounts[amounts.len() - 1])
-            },
-        );
+        transfer_helper_mod::safe_transfer_from(path[0], self.get_caller(), pairs[0], amounts[0]);
+        Self::_swap(&amounts, &path, &pairs, Key::from(get_package_hash()));
+        // unwrap wcspr and transfer cspr to 'to'
+        recipient.send_cspr(amounts[amounts.len() - 1]);
         amounts
     }
 
@@ -662,29 +644,16 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         caller_purse: URef,
         deadline: U256,
     ) -> Vec<U256> {
-        if !(self.ensure(deadline)) {
-            runtime::revert(ApiError::User(Errors::UniswapV2RouterTimedOut10 as u16));
-        }
-        let mut path: Vec<Key> = Vec::new();
-        for i in &_path {
-            path.push(Key::from_formatted_str(i).unwrap());
-        }
+        self.ensure(deadline);
+        let to: Key = Recipient::from_key(to).token_key();
+        let path: Vec<Key> = Self::_swap_path(&_path);
         if path[0] != Key::from(wcspr()) {
-            runtime::revert(Errors::UniswapV2RouterAbort10);
+            runtime::revert(Errors::PathMustStartWithWcspr);
         }
-        // call get_amounts_out
-        let amounts: Vec<U256> = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_GET_AMOUNTS_IN,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "amount_out" => amount_out,
-                "path" => path.clone(),
-            },
-        );
+        let pairs: Vec<Key> = Self::_pairs_for(&path);
+        let amounts: Vec<U256> = Self::_amounts_in(amount_out, &path, &pairs);
         if amounts[0] > amount_in_max {
-            runtime::revert(Errors::UniswapV2RouterAbort11);
+            runtime::revert(Errors::SlippageExceeded { side: Side::Input });
         }
         system::transfer_from_purse_to_purse(
             caller_purse,
@@ -692,7 +661,7 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             u256_to_u512(amounts[0]),
             None,
         )
-        .unwrap_or_revert();
+        .unwrap_or_revert_with(Errors::InsufficientCspr);
         // call deposit method from wcspr
         runtime::call_versioned_contract::<()>(
             wcspr(),
@@ -704,32 +673,24 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             },
         );
         // call transfer method from wcspr
-        // Get pair
-        let pair: Key = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_PAIR_FOR,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => path[0],
-                "token_b" => path[1],
-            },
-        );
         runtime::call_versioned_contract::<()>(
             wcspr(),
             None,
             WCSPR_TRANSFER,
             runtime_args! {
-                "recipient" => pair,
+                "recipient" => pairs[0],
                 "amount" => amounts[0]
             },
         );
-        Self::_swap(&amounts, &path, to);
+        Self::_swap(&amounts, &path, &pairs, to);
         // No need to refund extra cspr because we are already getting the exact required amount from the purse
         amounts
     }
 
     fn quote(amount_a: U256, reserve_a: U256, reserve_b: U256) -> U256 {
+        if !use_library_contract() {
+            return math::quote(amount_a, reserve_a, reserve_b);
+        }
         runtime::call_versioned_contract(
             library_hash(),
             None,
@@ -743,6 +704,9 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn get_amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+        if !use_library_contract() {
+            return math::get_amount_out(amount_in, reserve_in, reserve_out);
+        }
         runtime::call_versioned_contract(
             library_hash(),
             None,
@@ -756,6 +720,9 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn get_amount_in(amount_out: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+        if !use_library_contract() {
+            return math::get_amount_in(amount_out, reserve_in, reserve_out);
+        }
         runtime::call_versioned_contract(
             library_hash(),
             None,
@@ -769,29 +736,11 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
     }
 
     fn get_amounts_out(amount_in: U256, path: Vec<Key>) -> Vec<U256> {
-        runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_GET_AMOUNTS_OUT,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "amount_in" => amount_in,
-                "path" => path
-            },
-        )
+        Self::_amounts_out(amount_in, &path, &Self::_pairs_for(&path))
     }
 
     fn get_amounts_in(amount_out: U256, path: Vec<Key>) -> Vec<U256> {
-        runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_GET_AMOUNTS_IN,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "amount_out" => amount_out,
-                "path" => path
-            },
-        )
+        Self::_amounts_in(amount_out, &path, &Self::_pairs_for(&path))
     }
 
     // *************************************** Helper methods ****************************************
@@ -807,6 +756,7 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
         amount_b_min: U256,
         pair_received: Option<Key>,
     ) -> (U256, U256) {
+        Self::_when_not_paused();
         let pair: Key = runtime::call_versioned_contract(
             factory(),
             None,
@@ -817,23 +767,11 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             },
         );
 
-        let mut pair_already_exist: bool = false;
-        // If a pair is not passed, check if it already exists, if it doesnot, revert
-        if pair_received.is_none() {
-            if pair == zero_address() {
-                // if pair is none and it doesnot already exist, revert
-                runtime::revert(Errors::UniswapV2RouterZeroAddr);
-            } else {
-                pair_already_exist = true;
-            }
-        }
-        // If a pair is passed in, check if it exists already, if it does, no need to call factory's create_pair
-        if pair_received.is_some() && pair != zero_address() {
-            pair_already_exist = true;
-        }
-        if !pair_already_exist {
+        // If the pair doesn't exist yet, the pair passed in is initialized. Without one, revert
+        if pair == zero_address() {
+            let pair_received: Key = pair_received.unwrap_or_revert_with(Errors::PairNotFound);
             if !Whitelist::instance().get(&self.get_caller()) {
-                runtime::revert(Errors::UniswapV2RouterNotInWhitelist);
+                runtime::revert(Errors::NotInWhitelist);
             }
             // need to call create_pair only once for each pair. If a same pair is passed again, no need to call this again
             runtime::call_versioned_contract::<()>(
@@ -843,48 +781,23 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
                 runtime_args! {
                     "token_a" => Key::from(token_a),
                     "token_b" => Key::from(token_b),
-                    "pair_hash" => pair_received.unwrap()
+                    "pair_hash" => pair_received
                 },
             );
         }
-        let (reserve_a, reserve_b): (U128, U128) = runtime::call_versioned_contract(
-            library_hash(),
-            None,
-            LIBRARY_GET_RESERVES,
-            runtime_args! {
-                "factory" => Key::from(factory()),
-                "token_a" => Key::from(token_a),
-                "token_b" => Key::from(token_b),
-            },
-        );
+        let pair: Key = Self::_pairs_for(&[Key::from(token_a), Key::from(token_b)])[0];
+        let (reserve_a, reserve_b): (U256, U256) =
+            Self::_reserves(pair, Key::from(token_a), Key::from(token_b));
         if reserve_a == 0.into() && reserve_b == 0.into() {
             (amount_a_desired, amount_b_desired)
         } else {
-            let amount_b_optimal: U256 = runtime::call_versioned_contract(
-                library_hash(),
-                None,
-                LIBRARY_QUOTE,
-                runtime_args! {
-                    "amount_a" => amount_a_desired,
-                    "reserve_a" => reserve_a,
-                    "reserve_b" => reserve_b
-                },
-            );
+            let amount_b_optimal: U256 = Self::quote(amount_a_desired, reserve_a, reserve_b);
             if amount_b_optimal <= amount_b_desired && amount_b_optimal >= amount_b_min {
                 (amount_a_desired, amount_b_optimal)
             } else {
-                let amount_a_optimal: U256 = runtime::call_versioned_contract(
-                    library_hash(),
-                    None,
-                    LIBRARY_QUOTE,
-                    runtime_args! {
-                        "amount_a" => amount_b_desired,
-                        "reserve_a" => reserve_b,
-                        "reserve_b" => reserve_a
-                    },
-                );
+                let amount_a_optimal: U256 = Self::quote(amount_b_desired, reserve_b, reserve_a);
                 if amount_a_optimal > amount_a_desired {
-                    runtime::revert(Errors::UniswapV2RouterInvalidArguments);
+                    runtime::revert(Errors::OptimalAmountExceedsDesired);
                 }
                 if amount_a_optimal >= amount_a_min {
                     (amount_a_optimal, amount_b_desired)
@@ -894,72 +807,176 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
             }
         }
     }
-    
+
     #[inline(always)]
-    fn _swap(amounts: &[U256], path: &Vec<Key>, _to: Key) {
+    fn _swap(amounts: &[U256], path: &[Key], pairs: &[Key], _to: Key) {
+        Self::_when_not_paused();
         for i in 0..(path.len() - 1)
         // start ≤ x < end - 1
         {
             let (input, output): (Key, Key) = (path[i], path[i + 1]);
-            let (token0, _): (ContractPackageHash, ContractPackageHash) =
-                runtime::call_versioned_contract(
-                    library_hash(),
-                    None,
-                    LIBRARY_SORT_TOKENS,
-                    runtime_args! {
-                        "token_a" => input,
-                        "token_b" => output
-                    },
-                );
+            let (token0, _): (Key, Key) = Self::_sort_tokens(input, output);
             let amount_out: U256 = amounts[i + 1];
-            let (amount0_out, amount1_out): (U256, U256) = if input == Key::from(token0) {
+            let (amount0_out, amount1_out): (U256, U256) = if input == token0 {
                 (0.into(), amount_out)
             } else {
                 (amount_out, 0.into())
             };
-            let to: Key = {
-                if i < path.len() - 2 {
-                    runtime::call_versioned_contract(
-                        library_hash(),
+            // output of intermediate hops goes straight to the next pair
+            let to: Key = if i < path.len() - 2 {
+                pairs[i + 1]
+            } else {
+                _to
+            };
+            runtime::call_versioned_contract::<()>(
+                pairs[i].into_hash().unwrap_or_revert().into(),
+                None,
+                PAIR_SWAP,
+                runtime_args! {
+                    "amount0_out" => amount0_out,
+                    "amount1_out" => amount1_out,
+                    "to" => to,
+                    "data" => "",
+                },
+            );
+        }
+    }
+
+    // Tokens of a path given as formatted keys, reverting on any that doesn't parse.
+    fn _parse_path(path: &[String]) -> Vec<Key> {
+        path.iter()
+            .map(|token| Key::from_formatted_str(token).unwrap_or_revert_with(Errors::InvalidPath))
+            .collect()
+    }
+
+    // Path of a swap, which needs at least one hop before its ends can be checked.
+    fn _swap_path(path: &[String]) -> Vec<Key> {
+        let path: Vec<Key> = Self::_parse_path(path);
+        if path.len() < 2 {
+            runtime::revert(Errors::InvalidPath);
+        }
+        path
+    }
+
+    // Pairs of every hop of the path, resolved once per call. The factory is only asked the first
+    // time a pair is used through the router, pairs never change once created.
+    fn _pairs_for(path: &[Key]) -> Vec<Key> {
+        let cache = PairCache::instance();
+        path.windows(2)
+            .map(|hop| {
+                let (token0, token1): (Key, Key) = Self::_sort_tokens(hop[0], hop[1]);
+                cache.get(&token0, &token1).unwrap_or_else(|| {
+                    let pair: Key = runtime::call_versioned_contract(
+                        factory(),
                         None,
-                        LIBRARY_PAIR_FOR,
+                        FACTORY_GET_PAIR,
                         runtime_args! {
-                            "factory" => Key::from(factory()),
-                            "token_a" => output,
-                            "token_b" => path[i + 2]
+                            "token0" => token0,
+                            "token1" => token1
                         },
-                    )
-                } else {
-                    _to
-                }
-            };
-            // Call swap from UniswapV2Pair, but first need to call pair_for to get the pair
-            let pair: Key = runtime::call_versioned_contract(
+                    );
+                    if pair == zero_address() {
+                        runtime::revert(Errors::PairNotFound);
+                    }
+                    cache.set(&token0, &token1, pair);
+                    pair
+                })
+            })
+            .collect()
+    }
+
+    // Chained get_amount_out over the pairs of the path.
+    fn _amounts_out(amount_in: U256, path: &[Key], pairs: &[Key]) -> Vec<U256> {
+        if use_library_contract() {
+            return runtime::call_versioned_contract(
                 library_hash(),
                 None,
-                LIBRARY_PAIR_FOR,
+                LIBRARY_GET_AMOUNTS_OUT,
                 runtime_args! {
                     "factory" => Key::from(factory()),
-                    "token_a" => input,
-                    "token_b" => output
+                    "amount_in" => amount_in,
+                    "path" => path.to_vec()
                 },
             );
-            runtime::call_versioned_contract::<()>(
-                pair.into_hash().unwrap_or_revert().into(),
+        }
+        if path.len() < 2 {
+            runtime::revert(Errors::InvalidPath);
+        }
+        let reserves: Vec<(U256, U256)> = (0..(path.len() - 1))
+            .map(|i| Self::_reserves(pairs[i], path[i], path[i + 1]))
+            .collect();
+        math::get_amounts_out(amount_in, &reserves)
+    }
+
+    // Chained get_amount_in over the pairs of the path, from the last hop back.
+    fn _amounts_in(amount_out: U256, path: &[Key], pairs: &[Key]) -> Vec<U256> {
+        if use_library_contract() {
+            return runtime::call_versioned_contract(
+                library_hash(),
                 None,
-                PAIR_SWAP,
+                LIBRARY_GET_AMOUNTS_IN,
                 runtime_args! {
-                    "amount0_out" => amount0_out,
-                    "amount1_out" => amount1_out,
-                    "to" => to,
-                    "data" => "",
+                    "factory" => Key::from(factory()),
+                    "amount_out" => amount_out,
+                    "path" => path.to_vec()
                 },
             );
         }
+        if path.len() < 2 {
+            runtime::revert(Errors::InvalidPath);
+        }
+        let reserves: Vec<(U256, U256)> = (0..(path.len() - 1))
+            .map(|i| Self::_reserves(pairs[i], path[i], path[i + 1]))
+            .collect();
+        math::get_amounts_in(amount_out, &reserves)
     }
 
-    fn ensure(&self, deadline: U256) -> bool {
-        BlockTime::new(deadline.as_u64()) >= runtime::get_blocktime()
+    // Reserves of the pair ordered as (token_a, token_b).
+    fn _reserves(pair: Key, token_a: Key, token_b: Key) -> (U256, U256) {
+        let (reserve0, reserve1, _): (U128, U128, u64) = runtime::call_versioned_contract(
+            pair.into_hash().unwrap_or_revert().into(),
+            None,
+            PAIR_GET_RESERVES,
+            runtime_args! {},
+        );
+        let (reserve0, reserve1): (U256, U256) = (
+            U256::from(reserve0.as_u128()),
+            U256::from(reserve1.as_u128()),
+        );
+        let (token0, _): (Key, Key) = Self::_sort_tokens(token_a, token_b);
+        if token_a == token0 {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        }
+    }
+
+    // Same ordering as the library's sort_tokens, without the cross contract call.
+    fn _sort_tokens(token_a: Key, token_b: Key) -> (Key, Key) {
+        if token_a == token_b {
+            runtime::revert(Errors::IdenticalAddresses);
+        }
+        if token_a < token_b {
+            (token_a, token_b)
+        } else {
+            (token_b, token_a)
+        }
+    }
+
+    // Reverts while the owner has paused the router. Every entry point that trades, deposits or
+    // changes an order checks it. Cancelling orders, withdrawing dca budgets and the owner's
+    // entry points keep working, so funds can leave and the router can be unpaused.
+    fn _when_not_paused() {
+        if paused() {
+            runtime::revert(Errors::Paused);
+        }
+    }
+
+    // Reverts unless the deadline, in milliseconds, is at or after the current block time.
+    fn ensure(&self, deadline: U256) {
+        if check_deadline(deadline).is_err() {
+            runtime::revert(Errors::TimedOut);
+        }
     }
 
     fn emit(&self, router_event: &ROUTEREvent) {
@@ -994,6 +1011,159 @@ pub trait UniswapV2Router<Storage: ContractStorage>: ContractContext<Storage> {
                 event.insert("pair_contract_hash", pair_contract_hash.to_string());
                 storage::new_uref(event);
             }
+            ROUTEREvent::LimitOrderCreated {
+                order_id,
+                owner,
+                amount_in,
+                amount_out_min,
+                bounty,
+                expiry,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("order_id", order_id.to_string());
+                event.insert("owner", owner.to_string());
+                event.insert("amount_in", amount_in.to_string());
+                event.insert("amount_out_min", amount_out_min.to_string());
+                event.insert("bounty", bounty.to_string());
+                event.insert("expiry", expiry.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::LimitOrderFilled {
+                order_id,
+                filler,
+                amount_in,
+                amount_out,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("order_id", order_id.to_string());
+                event.insert("filler", filler.to_string());
+                event.insert("amount_in", amount_in.to_string());
+                event.insert("amount_out", amount_out.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::LimitOrderCancelled { order_id, owner } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("order_id", order_id.to_string());
+                event.insert("owner", owner.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::DcaOrderCreated {
+                order_id,
+                owner,
+                budget,
+                amount_per_interval,
+                interval,
+                fee,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("order_id", order_id.to_string());
+                event.insert("owner", owner.to_string());
+                event.insert("budget", budget.to_string());
+                event.insert("amount_per_interval", amount_per_interval.to_string());
+                event.insert("interval", interval.to_string());
+                event.insert("fee", fee.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::DcaOrderExecuted {
+                order_id,
+                keeper,
+                amount_in,
+                amount_out,
+                budget,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("order_id", order_id.to_string());
+                event.insert("keeper", keeper.to_string());
+                event.insert("amount_in", amount_in.to_string());
+                event.insert("amount_out", amount_out.to_string());
+                event.insert("budget", budget.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::DcaOrderPaused { order_id }
+            | ROUTEREvent::DcaOrderResumed { order_id } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("order_id", order_id.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::DcaOrderWithdrawn {
+                order_id,
+                owner,
+                amount,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("order_id", order_id.to_string());
+                event.insert("owner", owner.to_string());
+                event.insert("amount", amount.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::ReferenceChanged { name, old, new } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("name", name.to_string());
+                event.insert("old", old.to_string());
+                event.insert("new", new.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::OperationQueued {
+                operation_hash,
+                action,
+                eta,
+            } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("operation_hash", operation_hash.to_string());
+                event.insert("action", action.to_string());
+                event.insert("eta", eta.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::OperationCancelled { operation_hash }
+            | ROUTEREvent::OperationExecuted { operation_hash } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("operation_hash", operation_hash.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::RescuedCspr { amount, to_purse } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("amount", amount.to_string());
+                event.insert("to_purse", to_purse.to_formatted_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::RescuedTokens { token, amount, to } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("token", token.to_string());
+                event.insert("amount", amount.to_string());
+                event.insert("to", to.to_string());
+                storage::new_uref(event);
+            }
+            ROUTEREvent::Paused { account } | ROUTEREvent::Unpaused { account } => {
+                let mut event = BTreeMap::new();
+                event.insert("contract_package_hash", get_package_hash().to_string());
+                event.insert("event_type", router_event.type_name());
+                event.insert("account", account.to_string());
+                storage::new_uref(event);
+            }
         };
     }
 }
@@ -0,0 +1,22 @@
+use crate::SdkError;
+use common::{bytesrepr::ToBytes, CLTyped, RuntimeArgs, U256};
+
+pub(crate) fn non_zero(name: &'static str, amount: U256) -> Result<(), SdkError> {
+    if amount.is_zero() {
+        Err(SdkError::ZeroAmount(name))
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn at_most(name: &'static str, min: U256, desired: U256) -> Result<(), SdkError> {
+    if min > desired {
+        Err(SdkError::MinimumAboveDesired(name))
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn insert<T: CLTyped + ToBytes>(args: &mut RuntimeArgs, name: &str, value: T) {
+    args.insert(name, value).expect("args are serializable");
+}
@@ -0,0 +1,41 @@
+use crate::SdkError;
+use common::U256;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Block time in milliseconds since the unix epoch until which a call is valid, the unit the
+// router checks deadlines and expiries in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deadline {
+    At(u64),
+    After { submitted_at: u64, valid_for: u64 },
+}
+
+impl Deadline {
+    // Valid for `valid_for` milliseconds from the host's clock.
+    pub fn from_now(valid_for: u64) -> Deadline {
+        let now: u64 = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_millis() as u64)
+            .unwrap_or_default();
+        Deadline::After {
+            submitted_at: now,
+            valid_for,
+        }
+    }
+
+    pub fn millis(&self) -> Result<u64, SdkError> {
+        match *self {
+            Deadline::At(deadline) => Ok(deadline),
+            Deadline::After {
+                submitted_at,
+                valid_for,
+            } => submitted_at
+                .checked_add(valid_for)
+                .ok_or(SdkError::DeadlineOverflow),
+        }
+    }
+
+    pub fn to_u256(&self) -> Result<U256, SdkError> {
+        self.millis().map(U256::from)
+    }
+}
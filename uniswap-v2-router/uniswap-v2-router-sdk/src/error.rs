@@ -0,0 +1,55 @@
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SdkError {
+    /// a path names fewer than two tokens
+    PathTooShort,
+    /// a path token is not a contract package hash
+    InvalidPathToken(String),
+    /// a path goes from a token to the same token
+    IdenticalTokens(String),
+    /// the named amount must not be zero
+    ZeroAmount(&'static str),
+    /// the named minimum is above the amount it bounds
+    MinimumAboveDesired(&'static str),
+    /// a deadline beyond the u64 block times of the chain
+    DeadlineOverflow,
+    /// the entry point takes a purse, which only session code can pass
+    NeedsSession(&'static str),
+    /// the session code router has no destination for the entry point
+    NotASessionDestination(&'static str),
+    /// the session code router cannot approve the router for the entry point
+    NotApprovable(&'static str),
+}
+
+impl fmt::Display for SdkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SdkError::PathTooShort => write!(f, "a path needs at least two tokens"),
+            SdkError::InvalidPathToken(token) => {
+                write!(f, "path token {} is not a contract package hash", token)
+            }
+            SdkError::IdenticalTokens(token) => write!(f, "path swaps {} for itself", token),
+            SdkError::ZeroAmount(name) => write!(f, "{} must not be zero", name),
+            SdkError::MinimumAboveDesired(name) => {
+                write!(f, "{} is above the amount it bounds", name)
+            }
+            SdkError::DeadlineOverflow => write!(f, "deadline does not fit in a u64 block time"),
+            SdkError::NeedsSession(entry_point) => {
+                write!(
+                    f,
+                    "{} takes a purse and must be called through session code",
+                    entry_point
+                )
+            }
+            SdkError::NotASessionDestination(entry_point) => {
+                write!(f, "session code router has no {} destination", entry_point)
+            }
+            SdkError::NotApprovable(entry_point) => {
+                write!(f, "session code router cannot approve {}", entry_point)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SdkError {}
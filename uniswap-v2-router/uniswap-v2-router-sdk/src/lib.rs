@@ -0,0 +1,19 @@
+// Host side builders for the router, the library and the session code router. Every call is
+// checked before a deploy is made: paths, amounts and deadlines that the contracts would revert
+// on are rejected with an `SdkError`.
+mod args;
+pub mod deadline;
+pub mod error;
+pub mod library;
+pub mod path;
+pub mod payload;
+pub mod router;
+pub mod session;
+
+pub use deadline::Deadline;
+pub use error::SdkError;
+pub use library::LibraryCall;
+pub use path::Path;
+pub use payload::Payload;
+pub use router::RouterCall;
+pub use session::SessionCall;
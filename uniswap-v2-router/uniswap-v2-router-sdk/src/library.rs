@@ -0,0 +1,223 @@
+use crate::{args::insert, Path, Payload, SdkError};
+use common::{ContractPackageHash, Key, RuntimeArgs, U128, U256};
+
+// A call of a library entry point, `constructor` is left out as only the install calls it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LibraryCall {
+    SortTokens {
+        token_a: Key,
+        token_b: Key,
+    },
+    GetReserves {
+        factory: Key,
+        token_a: Key,
+        token_b: Key,
+    },
+    Quote {
+        amount_a: U256,
+        reserve_a: U128,
+        reserve_b: U128,
+    },
+    GetAmountOut {
+        amount_in: U256,
+        reserve_in: U256,
+        reserve_out: U256,
+    },
+    GetAmountIn {
+        amount_out: U256,
+        reserve_in: U256,
+        reserve_out: U256,
+    },
+    GetAmountsOut {
+        factory: Key,
+        amount_in: U256,
+        path: Path,
+    },
+    GetAmountsIn {
+        factory: Key,
+        amount_out: U256,
+        path: Path,
+    },
+    PairFor {
+        factory: Key,
+        token_a: Key,
+        token_b: Key,
+    },
+    ObservationIndexOf {
+        timestamp: u64,
+    },
+    CurrentCumulativePrices {
+        pair: Key,
+    },
+    Update {
+        token_a: Key,
+        token_b: Key,
+    },
+    Consult {
+        token_in: Key,
+        amount_in: U256,
+        token_out: Key,
+    },
+}
+
+impl LibraryCall {
+    pub fn entry_point(&self) -> &'static str {
+        match self {
+            LibraryCall::SortTokens { .. } => "sort_tokens",
+            LibraryCall::GetReserves { .. } => "get_reserves",
+            LibraryCall::Quote { .. } => "quote",
+            LibraryCall::GetAmountOut { .. } => "get_amount_out",
+            LibraryCall::GetAmountIn { .. } => "get_amount_in",
+            LibraryCall::GetAmountsOut { .. } => "get_amounts_out",
+            LibraryCall::GetAmountsIn { .. } => "get_amounts_in",
+            LibraryCall::PairFor { .. } => "pair_for",
+            LibraryCall::ObservationIndexOf { .. } => "observation_index_of",
+            LibraryCall::CurrentCumulativePrices { .. } => "current_cumulative_prices",
+            LibraryCall::Update { .. } => "update",
+            LibraryCall::Consult { .. } => "consult",
+        }
+    }
+
+    // Rejects the arguments the library would revert on.
+    pub fn validate(&self) -> Result<(), SdkError> {
+        let identical = |token_a: &Key, token_b: &Key| {
+            if token_a == token_b {
+                Err(SdkError::IdenticalTokens(token_a.to_formatted_string()))
+            } else {
+                Ok(())
+            }
+        };
+        match self {
+            LibraryCall::SortTokens { token_a, token_b }
+            | LibraryCall::GetReserves {
+                token_a, token_b, ..
+            }
+            | LibraryCall::PairFor {
+                token_a, token_b, ..
+            }
+            | LibraryCall::Update { token_a, token_b } => identical(token_a, token_b),
+            LibraryCall::Consult {
+                token_in,
+                amount_in,
+                token_out,
+            } => {
+                identical(token_in, token_out)?;
+                if amount_in.is_zero() {
+                    return Err(SdkError::ZeroAmount("amount_in"));
+                }
+                Ok(())
+            }
+            LibraryCall::Quote { amount_a, .. } if amount_a.is_zero() => {
+                Err(SdkError::ZeroAmount("amount_a"))
+            }
+            LibraryCall::GetAmountOut { amount_in, .. }
+            | LibraryCall::GetAmountsOut { amount_in, .. }
+                if amount_in.is_zero() =>
+            {
+                Err(SdkError::ZeroAmount("amount_in"))
+            }
+            LibraryCall::GetAmountIn { amount_out, .. }
+            | LibraryCall::GetAmountsIn { amount_out, .. }
+                if amount_out.is_zero() =>
+            {
+                Err(SdkError::ZeroAmount("amount_out"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn args(&self) -> Result<RuntimeArgs, SdkError> {
+        self.validate()?;
+        let mut args: RuntimeArgs = RuntimeArgs::new();
+        match self.clone() {
+            LibraryCall::SortTokens { token_a, token_b }
+            | LibraryCall::Update { token_a, token_b } => {
+                insert(&mut args, "token_a", token_a);
+                insert(&mut args, "token_b", token_b);
+            }
+            LibraryCall::GetReserves {
+                factory,
+                token_a,
+                token_b,
+            }
+            | LibraryCall::PairFor {
+                factory,
+                token_a,
+                token_b,
+            } => {
+                insert(&mut args, "factory", factory);
+                insert(&mut args, "token_a", token_a);
+                insert(&mut args, "token_b", token_b);
+            }
+            LibraryCall::Quote {
+                amount_a,
+                reserve_a,
+                reserve_b,
+            } => {
+                insert(&mut args, "amount_a", amount_a);
+                insert(&mut args, "reserve_a", reserve_a);
+                insert(&mut args, "reserve_b", reserve_b);
+            }
+            LibraryCall::GetAmountOut {
+                amount_in,
+                reserve_in,
+                reserve_out,
+            } => {
+                insert(&mut args, "amount_in", amount_in);
+                insert(&mut args, "reserve_in", reserve_in);
+                insert(&mut args, "reserve_out", reserve_out);
+            }
+            LibraryCall::GetAmountIn {
+                amount_out,
+                reserve_in,
+                reserve_out,
+            } => {
+                insert(&mut args, "amount_out", amount_out);
+                insert(&mut args, "reserve_in", reserve_in);
+                insert(&mut args, "reserve_out", reserve_out);
+            }
+            LibraryCall::GetAmountsOut {
+                factory,
+                amount_in,
+                path,
+            } => {
+                insert(&mut args, "factory", factory);
+                insert(&mut args, "amount_in", amount_in);
+                insert(&mut args, "path", path.tokens().to_vec());
+            }
+            LibraryCall::GetAmountsIn {
+                factory,
+                amount_out,
+                path,
+            } => {
+                insert(&mut args, "factory", factory);
+                insert(&mut args, "amount_out", amount_out);
+                insert(&mut args, "path", path.tokens().to_vec());
+            }
+            LibraryCall::ObservationIndexOf { timestamp } => {
+                insert(&mut args, "timestamp", timestamp);
+            }
+            LibraryCall::CurrentCumulativePrices { pair } => {
+                insert(&mut args, "pair", pair);
+            }
+            LibraryCall::Consult {
+                token_in,
+                amount_in,
+                token_out,
+            } => {
+                insert(&mut args, "token_in", token_in);
+                insert(&mut args, "amount_in", amount_in);
+                insert(&mut args, "token_out", token_out);
+            }
+        }
+        Ok(args)
+    }
+
+    pub fn payload(&self, library: ContractPackageHash) -> Result<Payload, SdkError> {
+        Ok(Payload::StoredContract {
+            package_hash: library,
+            entry_point: self.entry_point().into(),
+            args: self.args()?,
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use crate::SdkError;
+use common::Key;
+
+// Tokens a swap goes through, from the input token to the output token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path {
+    tokens: Vec<Key>,
+}
+
+impl Path {
+    pub fn new(tokens: Vec<Key>) -> Result<Path, SdkError> {
+        if tokens.len() < 2 {
+            return Err(SdkError::PathTooShort);
+        }
+        for token in &tokens {
+            if token.into_hash().is_none() {
+                return Err(SdkError::InvalidPathToken(token.to_formatted_string()));
+            }
+        }
+        for hop in tokens.windows(2) {
+            if hop[0] == hop[1] {
+                return Err(SdkError::IdenticalTokens(hop[0].to_formatted_string()));
+            }
+        }
+        Ok(Path { tokens })
+    }
+
+    // Parses the formatted keys the router takes for paths, e.g. `hash-...`.
+    pub fn parse(tokens: &[&str]) -> Result<Path, SdkError> {
+        let tokens: Vec<Key> = tokens
+            .iter()
+            .map(|token| {
+                Key::from_formatted_str(token)
+                    .map_err(|_| SdkError::InvalidPathToken((*token).into()))
+            })
+            .collect::<Result<_, _>>()?;
+        Path::new(tokens)
+    }
+
+    pub fn tokens(&self) -> &[Key] {
+        &self.tokens
+    }
+
+    pub fn input(&self) -> Key {
+        self.tokens[0]
+    }
+
+    pub fn output(&self) -> Key {
+        self.tokens[self.tokens.len() - 1]
+    }
+
+    // Swap entry points take the path as formatted keys.
+    pub fn to_strings(&self) -> Vec<String> {
+        self.tokens
+            .iter()
+            .map(|token| token.to_formatted_string())
+            .collect()
+    }
+}
@@ -0,0 +1,23 @@
+use common::{ContractPackageHash, RuntimeArgs};
+
+// What a deploy runs: the session code router wasm or an entry point of a stored package.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Payload {
+    Session {
+        args: RuntimeArgs,
+    },
+    StoredContract {
+        package_hash: ContractPackageHash,
+        entry_point: String,
+        args: RuntimeArgs,
+    },
+}
+
+impl Payload {
+    pub fn args(&self) -> &RuntimeArgs {
+        match self {
+            Payload::Session { args } => args,
+            Payload::StoredContract { args, .. } => args,
+        }
+    }
+}
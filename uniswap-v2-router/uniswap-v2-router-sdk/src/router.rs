@@ -0,0 +1,714 @@
+use crate::{
+    args::{at_most, insert, non_zero},
+    Deadline, Path, Payload, SdkError,
+};
+use common::{functions::u256_to_u512, ContractPackageHash, Key, RuntimeArgs, URef, U256, U512};
+
+// A call of a router entry point. `constructor` and `receive` are left out, only the router's
+// install and the wcspr contract call them.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RouterCall {
+    AddLiquidity {
+        token_a: Key,
+        token_b: Key,
+        amount_a_desired: U256,
+        amount_b_desired: U256,
+        amount_a_min: U256,
+        amount_b_min: U256,
+        to: Key,
+        deadline: Deadline,
+        pair: Option<Key>,
+    },
+    AddLiquidityCspr {
+        token: Key,
+        amount_token_desired: U256,
+        amount_cspr_desired: U256,
+        amount_token_min: U256,
+        amount_cspr_min: U256,
+        to: Key,
+        deadline: Deadline,
+        pair: Option<Key>,
+    },
+    RemoveLiquidity {
+        token_a: Key,
+        token_b: Key,
+        liquidity: U256,
+        amount_a_min: U256,
+        amount_b_min: U256,
+        to: Key,
+        deadline: Deadline,
+    },
+    RemoveLiquidityCspr {
+        token: Key,
+        liquidity: U256,
+        amount_token_min: U256,
+        amount_cspr_min: U256,
+        to: Key,
+        deadline: Deadline,
+    },
+    SwapExactTokensForTokens {
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Path,
+        to: Key,
+        deadline: Deadline,
+    },
+    SwapTokensForExactTokens {
+        amount_out: U256,
+        amount_in_max: U256,
+        path: Path,
+        to: Key,
+        deadline: Deadline,
+    },
+    SwapExactCsprForTokens {
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Path,
+        to: Key,
+        deadline: Deadline,
+    },
+    SwapCsprForExactTokens {
+        amount_out: U256,
+        amount_in_max: U256,
+        path: Path,
+        to: Key,
+        deadline: Deadline,
+    },
+    SwapExactTokensForCspr {
+        amount_in: U256,
+        amount_out_min: U256,
+        path: Path,
+        to: Key,
+        deadline: Deadline,
+    },
+    SwapTokensForExactCspr {
+        amount_out: U256,
+        amount_in_max: U256,
+        path: Path,
+        to: Key,
+        deadline: Deadline,
+    },
+    Quote {
+        amount_a: U256,
+        reserve_a: U256,
+        reserve_b: U256,
+    },
+    GetAmountOut {
+        amount_in: U256,
+        reserve_in: U256,
+        reserve_out: U256,
+    },
+    GetAmountIn {
+        amount_out: U256,
+        reserve_in: U256,
+        reserve_out: U256,
+    },
+    GetAmountsOut {
+        amount_in: U256,
+        path: Path,
+    },
+    GetAmountsIn {
+        amount_out: U256,
+        path: Path,
+    },
+    CheckDeadline {
+        deadline: U256,
+    },
+    CreateLimitOrder {
+        amount_in: U256,
+        amount_out_min: U256,
+        bounty: U256,
+        path: Path,
+        to: Key,
+        expiry: Deadline,
+    },
+    CreateLimitOrderCspr {
+        amount_in: U256,
+        amount_out_min: U256,
+        bounty: U256,
+        path: Path,
+        to: Key,
+        expiry: Deadline,
+    },
+    FillOrder {
+        order_id: U256,
+    },
+    CancelOrder {
+        order_id: U256,
+    },
+    CreateDcaOrder {
+        budget: U256,
+        amount_per_interval: U256,
+        interval: u64,
+        amount_out_min: U256,
+        fee: U256,
+        path: Path,
+        to: Key,
+    },
+    CreateDcaOrderCspr {
+        budget: U256,
+        amount_per_interval: U256,
+        interval: u64,
+        amount_out_min: U256,
+        fee: U256,
+        path: Path,
+        to: Key,
+    },
+    ExecuteDcaOrder {
+        order_id: U256,
+    },
+    PauseDcaOrder {
+        order_id: U256,
+    },
+    ResumeDcaOrder {
+        order_id: U256,
+    },
+    WithdrawDcaOrder {
+        order_id: U256,
+    },
+    GetFactory,
+    GetWcspr,
+    GetLibraryHash,
+    SetFactory {
+        factory: Key,
+        eta: u64,
+    },
+    SetWcspr {
+        wcspr: Key,
+        eta: u64,
+    },
+    SetLibraryHash {
+        library_hash: Key,
+        eta: u64,
+    },
+    QueueOperation {
+        action: String,
+        target: Key,
+        value: u64,
+        eta: u64,
+    },
+    CancelOperation {
+        operation_hash: String,
+    },
+    ExecuteOperation {
+        operation_hash: String,
+    },
+    GetOperation {
+        operation_hash: String,
+    },
+    Pause,
+    RescueCspr {
+        amount: U512,
+        to_purse: Option<URef>,
+    },
+    RescueTokens {
+        token: Key,
+        amount: U256,
+        to: Key,
+    },
+    GetPurseBalance,
+    Migrate,
+    GetEnabledVersions,
+    RecordVersions {
+        disable_previous_versions: bool,
+    },
+    GetStorageVersion,
+    IsPaused,
+    GetTimelockDelay,
+}
+
+impl RouterCall {
+    pub fn entry_point(&self) -> &'static str {
+        match self {
+            RouterCall::AddLiquidity { .. } => "add_liquidity",
+            RouterCall::AddLiquidityCspr { .. } => "add_liquidity_cspr",
+            RouterCall::RemoveLiquidity { .. } => "remove_liquidity",
+            RouterCall::RemoveLiquidityCspr { .. } => "remove_liquidity_cspr",
+            RouterCall::SwapExactTokensForTokens { .. } => "swap_exact_tokens_for_tokens",
+            RouterCall::SwapTokensForExactTokens { .. } => "swap_tokens_for_exact_tokens",
+            RouterCall::SwapExactCsprForTokens { .. } => "swap_exact_cspr_for_tokens",
+            RouterCall::SwapCsprForExactTokens { .. } => "swap_cspr_for_exact_tokens",
+            RouterCall::SwapExactTokensForCspr { .. } => "swap_exact_tokens_for_cspr",
+            RouterCall::SwapTokensForExactCspr { .. } => "swap_tokens_for_exact_cspr",
+            RouterCall::Quote { .. } => "quote",
+            RouterCall::GetAmountOut { .. } => "get_amount_out",
+            RouterCall::GetAmountIn { .. } => "get_amount_in",
+            RouterCall::GetAmountsOut { .. } => "get_amounts_out",
+            RouterCall::GetAmountsIn { .. } => "get_amounts_in",
+            RouterCall::CheckDeadline { .. } => "check_deadline",
+            RouterCall::CreateLimitOrder { .. } => "create_limit_order",
+            RouterCall::CreateLimitOrderCspr { .. } => "create_limit_order_cspr",
+            RouterCall::FillOrder { .. } => "fill_order",
+            RouterCall::CancelOrder { .. } => "cancel_order",
+            RouterCall::CreateDcaOrder { .. } => "create_dca_order",
+            RouterCall::CreateDcaOrderCspr { .. } => "create_dca_order_cspr",
+            RouterCall::ExecuteDcaOrder { .. } => "execute_dca_order",
+            RouterCall::PauseDcaOrder { .. } => "pause_dca_order",
+            RouterCall::ResumeDcaOrder { .. } => "resume_dca_order",
+            RouterCall::WithdrawDcaOrder { .. } => "withdraw_dca_order",
+            RouterCall::GetFactory => "get_factory",
+            RouterCall::GetWcspr => "get_wcspr",
+            RouterCall::GetLibraryHash => "get_library_hash",
+            RouterCall::SetFactory { .. } => "set_factory",
+            RouterCall::SetWcspr { .. } => "set_wcspr",
+            RouterCall::SetLibraryHash { .. } => "set_library_hash",
+            RouterCall::QueueOperation { .. } => "queue_operation",
+            RouterCall::CancelOperation { .. } => "cancel_operation",
+            RouterCall::ExecuteOperation { .. } => "execute_operation",
+            RouterCall::GetOperation { .. } => "get_operation",
+            RouterCall::Pause => "pause",
+            RouterCall::RescueCspr { .. } => "rescue_cspr",
+            RouterCall::RescueTokens { .. } => "rescue_tokens",
+            RouterCall::GetPurseBalance => "get_purse_balance",
+            RouterCall::Migrate => "migrate",
+            RouterCall::GetEnabledVersions => "get_enabled_versions",
+            RouterCall::RecordVersions { .. } => "record_versions",
+            RouterCall::GetStorageVersion => "get_storage_version",
+            RouterCall::IsPaused => "is_paused",
+            RouterCall::GetTimelockDelay => "get_timelock_delay",
+        }
+    }
+
+    // Cspr the caller pays into the router, which only session code can do.
+    pub fn cspr_in(&self) -> Option<U512> {
+        let amount: U256 = match self {
+            RouterCall::AddLiquidityCspr {
+                amount_cspr_desired,
+                ..
+            } => *amount_cspr_desired,
+            RouterCall::SwapExactCsprForTokens { amount_in, .. } => *amount_in,
+            RouterCall::SwapCsprForExactTokens { amount_in_max, .. } => *amount_in_max,
+            RouterCall::CreateLimitOrderCspr {
+                amount_in, bounty, ..
+            } => *amount_in + *bounty,
+            RouterCall::CreateDcaOrderCspr { budget, .. } => *budget,
+            _ => return None,
+        };
+        Some(u256_to_u512(amount))
+    }
+
+    // Rejects the arguments the router would revert on.
+    pub fn validate(&self) -> Result<(), SdkError> {
+        match self {
+            RouterCall::AddLiquidity {
+                amount_a_desired,
+                amount_b_desired,
+                amount_a_min,
+                amount_b_min,
+                deadline,
+                ..
+            } => {
+                non_zero("amount_a_desired", *amount_a_desired)?;
+                non_zero("amount_b_desired", *amount_b_desired)?;
+                at_most("amount_a_min", *amount_a_min, *amount_a_desired)?;
+                at_most("amount_b_min", *amount_b_min, *amount_b_desired)?;
+                deadline.millis().map(drop)
+            }
+            RouterCall::AddLiquidityCspr {
+                amount_token_desired,
+                amount_cspr_desired,
+                amount_token_min,
+                amount_cspr_min,
+                deadline,
+                ..
+            } => {
+                non_zero("amount_token_desired", *amount_token_desired)?;
+                non_zero("amount_cspr_desired", *amount_cspr_desired)?;
+                at_most("amount_token_min", *amount_token_min, *amount_token_desired)?;
+                at_most("amount_cspr_min", *amount_cspr_min, *amount_cspr_desired)?;
+                deadline.millis().map(drop)
+            }
+            RouterCall::RemoveLiquidity {
+                liquidity,
+                deadline,
+                ..
+            }
+            | RouterCall::RemoveLiquidityCspr {
+                liquidity,
+                deadline,
+                ..
+            } => {
+                non_zero("liquidity", *liquidity)?;
+                deadline.millis().map(drop)
+            }
+            RouterCall::SwapExactTokensForTokens {
+                amount_in,
+                deadline,
+                ..
+            }
+            | RouterCall::SwapExactCsprForTokens {
+                amount_in,
+                deadline,
+                ..
+            }
+            | RouterCall::SwapExactTokensForCspr {
+                amount_in,
+                deadline,
+                ..
+            } => {
+                non_zero("amount_in", *amount_in)?;
+                deadline.millis().map(drop)
+            }
+            RouterCall::SwapTokensForExactTokens {
+                amount_out,
+                amount_in_max,
+                deadline,
+                ..
+            }
+            | RouterCall::SwapCsprForExactTokens {
+                amount_out,
+                amount_in_max,
+                deadline,
+                ..
+            }
+            | RouterCall::SwapTokensForExactCspr {
+                amount_out,
+                amount_in_max,
+                deadline,
+                ..
+            } => {
+                non_zero("amount_out", *amount_out)?;
+                non_zero("amount_in_max", *amount_in_max)?;
+                deadline.millis().map(drop)
+            }
+            RouterCall::GetAmountsOut { amount_in, .. } => non_zero("amount_in", *amount_in),
+            RouterCall::GetAmountsIn { amount_out, .. } => non_zero("amount_out", *amount_out),
+            RouterCall::CreateLimitOrder {
+                amount_in, expiry, ..
+            }
+            | RouterCall::CreateLimitOrderCspr {
+                amount_in, expiry, ..
+            } => {
+                non_zero("amount_in", *amount_in)?;
+                expiry.millis().map(drop)
+            }
+            RouterCall::CreateDcaOrder {
+                budget,
+                amount_per_interval,
+                interval,
+                ..
+            }
+            | RouterCall::CreateDcaOrderCspr {
+                budget,
+                amount_per_interval,
+                interval,
+                ..
+            } => {
+                non_zero("amount_per_interval", *amount_per_interval)?;
+                non_zero("interval", U256::from(*interval))?;
+                at_most("amount_per_interval", *amount_per_interval, *budget)
+            }
+            RouterCall::RescueTokens { amount, .. } => non_zero("amount", *amount),
+            _ => Ok(()),
+        }
+    }
+
+    // The entry point's args without the caller's purse.
+    pub(crate) fn args_without_purse(&self) -> Result<RuntimeArgs, SdkError> {
+        self.validate()?;
+        let mut args: RuntimeArgs = RuntimeArgs::new();
+        match self.clone() {
+            RouterCall::AddLiquidity {
+                token_a,
+                token_b,
+                amount_a_desired,
+                amount_b_desired,
+                amount_a_min,
+                amount_b_min,
+                to,
+                deadline,
+                pair,
+            } => {
+                insert(&mut args, "token_a", token_a);
+                insert(&mut args, "token_b", token_b);
+                insert(&mut args, "amount_a_desired", amount_a_desired);
+                insert(&mut args, "amount_b_desired", amount_b_desired);
+                insert(&mut args, "amount_a_min", amount_a_min);
+                insert(&mut args, "amount_b_min", amount_b_min);
+                insert(&mut args, "to", to);
+                insert(&mut args, "deadline", deadline.to_u256()?);
+                insert(&mut args, "pair", pair);
+            }
+            RouterCall::AddLiquidityCspr {
+                token,
+                amount_token_desired,
+                amount_cspr_desired,
+                amount_token_min,
+                amount_cspr_min,
+                to,
+                deadline,
+                pair,
+            } => {
+                insert(&mut args, "token", token);
+                insert(&mut args, "amount_token_desired", amount_token_desired);
+                insert(&mut args, "amount_cspr_desired", amount_cspr_desired);
+                insert(&mut args, "amount_token_min", amount_token_min);
+                insert(&mut args, "amount_cspr_min", amount_cspr_min);
+                insert(&mut args, "to", to);
+                insert(&mut args, "deadline", deadline.to_u256()?);
+                insert(&mut args, "pair", pair);
+            }
+            RouterCall::RemoveLiquidity {
+                token_a,
+                token_b,
+                liquidity,
+                amount_a_min,
+                amount_b_min,
+                to,
+                deadline,
+            } => {
+                insert(&mut args, "token_a", token_a);
+                insert(&mut args, "token_b", token_b);
+                insert(&mut args, "liquidity", liquidity);
+                insert(&mut args, "amount_a_min", amount_a_min);
+                insert(&mut args, "amount_b_min", amount_b_min);
+                insert(&mut args, "to", to);
+                insert(&mut args, "deadline", deadline.to_u256()?);
+            }
+            RouterCall::RemoveLiquidityCspr {
+                token,
+                liquidity,
+                amount_token_min,
+                amount_cspr_min,
+                to,
+                deadline,
+            } => {
+                insert(&mut args, "token", token);
+                insert(&mut args, "liquidity", liquidity);
+                insert(&mut args, "amount_token_min", amount_token_min);
+                insert(&mut args, "amount_cspr_min", amount_cspr_min);
+                insert(&mut args, "to", to);
+                insert(&mut args, "deadline", deadline.to_u256()?);
+            }
+            RouterCall::SwapExactTokensForTokens {
+                amount_in,
+                amount_out_min,
+                path,
+                to,
+                deadline,
+            }
+            | RouterCall::SwapExactCsprForTokens {
+                amount_in,
+                amount_out_min,
+                path,
+                to,
+                deadline,
+            }
+            | RouterCall::SwapExactTokensForCspr {
+                amount_in,
+                amount_out_min,
+                path,
+                to,
+                deadline,
+            } => {
+                insert(&mut args, "amount_in", amount_in);
+                insert(&mut args, "amount_out_min", amount_out_min);
+                insert(&mut args, "path", path.to_strings());
+                insert(&mut args, "to", to);
+                insert(&mut args, "deadline", deadline.to_u256()?);
+            }
+            RouterCall::SwapTokensForExactTokens {
+                amount_out,
+                amount_in_max,
+                path,
+                to,
+                deadline,
+            }
+            | RouterCall::SwapCsprForExactTokens {
+                amount_out,
+                amount_in_max,
+                path,
+                to,
+                deadline,
+            }
+            | RouterCall::SwapTokensForExactCspr {
+                amount_out,
+                amount_in_max,
+                path,
+                to,
+                deadline,
+            } => {
+                insert(&mut args, "amount_out", amount_out);
+                insert(&mut args, "amount_in_max", amount_in_max);
+                insert(&mut args, "path", path.to_strings());
+                insert(&mut args, "to", to);
+                insert(&mut args, "deadline", deadline.to_u256()?);
+            }
+            RouterCall::Quote {
+                amount_a,
+                reserve_a,
+                reserve_b,
+            } => {
+                insert(&mut args, "amount_a", amount_a);
+                insert(&mut args, "reserve_a", reserve_a);
+                insert(&mut args, "reserve_b", reserve_b);
+            }
+            RouterCall::GetAmountOut {
+                amount_in,
+                reserve_in,
+                reserve_out,
+            } => {
+                insert(&mut args, "amount_in", amount_in);
+                insert(&mut args, "reserve_in", reserve_in);
+                insert(&mut args, "reserve_out", reserve_out);
+            }
+            RouterCall::GetAmountIn {
+                amount_out,
+                reserve_in,
+                reserve_out,
+            } => {
+                insert(&mut args, "amount_out", amount_out);
+                insert(&mut args, "reserve_in", reserve_in);
+                insert(&mut args, "reserve_out", reserve_out);
+            }
+            RouterCall::GetAmountsOut { amount_in, path } => {
+                insert(&mut args, "amount_in", amount_in);
+                insert(&mut args, "path", path.tokens().to_vec());
+            }
+            RouterCall::GetAmountsIn { amount_out, path } => {
+                insert(&mut args, "amount_out", amount_out);
+                insert(&mut args, "path", path.tokens().to_vec());
+            }
+            RouterCall::CheckDeadline { deadline } => {
+                insert(&mut args, "deadline", deadline);
+            }
+            RouterCall::CreateLimitOrder {
+                amount_in,
+                amount_out_min,
+                bounty,
+                path,
+                to,
+                expiry,
+            }
+            | RouterCall::CreateLimitOrderCspr {
+                amount_in,
+                amount_out_min,
+                bounty,
+                path,
+                to,
+                expiry,
+            } => {
+                insert(&mut args, "amount_in", amount_in);
+                insert(&mut args, "amount_out_min", amount_out_min);
+                insert(&mut args, "bounty", bounty);
+                insert(&mut args, "path", path.to_strings());
+                insert(&mut args, "to", to);
+                insert(&mut args, "expiry", expiry.to_u256()?);
+            }
+            RouterCall::FillOrder { order_id }
+            | RouterCall::CancelOrder { order_id }
+            | RouterCall::ExecuteDcaOrder { order_id }
+            | RouterCall::PauseDcaOrder { order_id }
+            | RouterCall::ResumeDcaOrder { order_id }
+            | RouterCall::WithdrawDcaOrder { order_id } => {
+                insert(&mut args, "order_id", order_id);
+            }
+            RouterCall::CreateDcaOrder {
+                budget,
+                amount_per_interval,
+                interval,
+                amount_out_min,
+                fee,
+                path,
+                to,
+            }
+            | RouterCall::CreateDcaOrderCspr {
+                budget,
+                amount_per_interval,
+                interval,
+                amount_out_min,
+                fee,
+                path,
+                to,
+            } => {
+                insert(&mut args, "budget", budget);
+                insert(&mut args, "amount_per_interval", amount_per_interval);
+                insert(&mut args, "interval", interval);
+                insert(&mut args, "amount_out_min", amount_out_min);
+                insert(&mut args, "fee", fee);
+                insert(&mut args, "path", path.to_strings());
+                insert(&mut args, "to", to);
+            }
+            RouterCall::SetFactory { factory, eta } => {
+                insert(&mut args, "factory", factory);
+                insert(&mut args, "eta", eta);
+            }
+            RouterCall::SetWcspr { wcspr, eta } => {
+                insert(&mut args, "wcspr", wcspr);
+                insert(&mut args, "eta", eta);
+            }
+            RouterCall::SetLibraryHash { library_hash, eta } => {
+                insert(&mut args, "library_hash", library_hash);
+                insert(&mut args, "eta", eta);
+            }
+            RouterCall::QueueOperation {
+                action,
+                target,
+                value,
+                eta,
+            } => {
+                insert(&mut args, "action", action);
+                insert(&mut args, "target", target);
+                insert(&mut args, "value", value);
+                insert(&mut args, "eta", eta);
+            }
+            RouterCall::CancelOperation { operation_hash }
+            | RouterCall::ExecuteOperation { operation_hash }
+            | RouterCall::GetOperation { operation_hash } => {
+                insert(&mut args, "operation_hash", operation_hash);
+            }
+            RouterCall::RescueCspr { amount, .. } => {
+                insert(&mut args, "amount", amount);
+            }
+            RouterCall::RescueTokens { token, amount, to } => {
+                insert(&mut args, "token", token);
+                insert(&mut args, "amount", amount);
+                insert(&mut args, "to", to);
+            }
+            RouterCall::RecordVersions {
+                disable_previous_versions,
+            } => {
+                insert(
+                    &mut args,
+                    "disable_previous_versions",
+                    disable_previous_versions,
+                );
+            }
+            RouterCall::GetFactory
+            | RouterCall::GetWcspr
+            | RouterCall::GetLibraryHash
+            | RouterCall::Pause
+            | RouterCall::GetPurseBalance
+            | RouterCall::Migrate
+            | RouterCall::GetEnabledVersions
+            | RouterCall::GetStorageVersion
+            | RouterCall::IsPaused
+            | RouterCall::GetTimelockDelay => {}
+        }
+        Ok(args)
+    }
+
+    // Args of a direct call of the entry point. Entry points paid in cspr need the caller's
+    // purse and go through the session code router instead.
+    pub fn args(&self) -> Result<RuntimeArgs, SdkError> {
+        if self.cspr_in().is_some() {
+            return Err(SdkError::NeedsSession(self.entry_point()));
+        }
+        let mut args: RuntimeArgs = self.args_without_purse()?;
+        if let RouterCall::RescueCspr { to_purse, .. } = self {
+            match to_purse {
+                Some(to_purse) => insert(&mut args, "to_purse", *to_purse),
+                None => return Err(SdkError::NeedsSession(self.entry_point())),
+            }
+        }
+        Ok(args)
+    }
+
+    pub fn payload(&self, router: ContractPackageHash) -> Result<Payload, SdkError> {
+        Ok(Payload::StoredContract {
+            package_hash: router,
+            entry_point: self.entry_point().into(),
+            args: self.args()?,
+        })
+    }
+}
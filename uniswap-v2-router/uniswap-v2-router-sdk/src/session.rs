@@ -0,0 +1,109 @@
+use crate::{args::insert, Payload, RouterCall, SdkError};
+use common::{keys::*, ContractPackageHash, Key, RuntimeArgs};
+
+// Entry points the session code router has a destination for.
+pub const SESSION_DESTINATIONS: [&str; 25] = [
+    DESTINATION_ADD_LIQUIDITY,
+    DESTINATION_ADD_LIQUIDITY_CSPR,
+    DESTINATION_REMOVE_LIQUIDITY,
+    DESTINATION_REMOVE_LIQUIDITY_CSPR,
+    DESTINATION_SWAP_EXACT_TOKENS_FOR_TOKENS,
+    DESTINATION_SWAP_TOKENS_FOR_EXACT_TOKENS,
+    DESTINATION_SWAP_EXACT_CSPR_FOR_TOKENS,
+    DESTINATION_SWAP_CSPR_FOR_EXACT_TOKENS,
+    DESTINATION_SWAP_TOKENS_FOR_EXACT_CSPR,
+    DESTINATION_SWAP_EXACT_TOKENS_FOR_CSPR,
+    DESTINATION_CREATE_LIMIT_ORDER,
+    DESTINATION_CREATE_LIMIT_ORDER_CSPR,
+    DESTINATION_FILL_ORDER,
+    DESTINATION_CREATE_DCA_ORDER,
+    DESTINATION_CREATE_DCA_ORDER_CSPR,
+    DESTINATION_EXECUTE_DCA_ORDER,
+    DESTINATION_CONSULT,
+    DESTINATION_CHECK_DEADLINE,
+    DESTINATION_QUOTE,
+    DESTINATION_GET_AMOUNTS_OUT,
+    DESTINATION_GET_AMOUNTS_IN,
+    DESTINATION_QUEUE_OPERATION,
+    DESTINATION_GET_OPERATION,
+    DESTINATION_GET_STORAGE_VERSION,
+    DESTINATION_RESCUE_CSPR,
+];
+
+// A router call made through the session code router, which pays cspr from the account's main
+// purse and can approve the router for the call's input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionCall {
+    call: RouterCall,
+    approve: bool,
+    pair: Option<Key>,
+}
+
+impl SessionCall {
+    pub fn new(call: RouterCall) -> SessionCall {
+        SessionCall {
+            call,
+            approve: false,
+            pair: None,
+        }
+    }
+
+    // Approves the router for exactly the call's input and revokes the allowance afterwards.
+    pub fn approve(mut self) -> SessionCall {
+        self.approve = true;
+        self
+    }
+
+    // Pair whose liquidity tokens an approving removal spends.
+    pub fn pair(mut self, pair: Key) -> SessionCall {
+        self.pair = Some(pair);
+        self
+    }
+
+    pub fn destination(&self) -> Result<String, SdkError> {
+        let entry_point: &'static str = self.call.entry_point();
+        if !SESSION_DESTINATIONS.contains(&entry_point) {
+            return Err(SdkError::NotASessionDestination(entry_point));
+        }
+        if !self.approve {
+            return Ok(entry_point.into());
+        }
+        if !APPROVABLE_DESTINATIONS.contains(&entry_point) {
+            return Err(SdkError::NotApprovable(entry_point));
+        }
+        Ok(format!("{}{}", DESTINATION_APPROVE_PREFIX, entry_point))
+    }
+
+    // The destination's session args, without `entrypoint` and `package_hash` as script steps
+    // take them.
+    pub fn step_args(&self) -> Result<RuntimeArgs, SdkError> {
+        let mut args: RuntimeArgs = self.call.args_without_purse()?;
+        if let Some(amount) = self.call.cspr_in() {
+            insert(&mut args, AMOUNT_RUNTIME_ARG, amount);
+        }
+        let removes: bool = matches!(
+            self.call,
+            RouterCall::RemoveLiquidity { .. } | RouterCall::RemoveLiquidityCspr { .. }
+        );
+        if self.approve && removes {
+            let pair: Key = self
+                .pair
+                .ok_or(SdkError::NotApprovable(self.call.entry_point()))?;
+            insert(&mut args, "pair", pair);
+        }
+        Ok(args)
+    }
+
+    pub fn args(&self, router: ContractPackageHash) -> Result<RuntimeArgs, SdkError> {
+        let mut args: RuntimeArgs = self.step_args()?;
+        insert(&mut args, "entrypoint", self.destination()?);
+        insert(&mut args, "package_hash", Key::from(router));
+        Ok(args)
+    }
+
+    pub fn payload(&self, router: ContractPackageHash) -> Result<Payload, SdkError> {
+        Ok(Payload::Session {
+            args: self.args(router)?,
+        })
+    }
+}
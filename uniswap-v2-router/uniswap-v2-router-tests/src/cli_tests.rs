@@ -0,0 +1,190 @@
+use casperswap_cli::{run, CliError};
+use serde_json::{json, Value};
+use std::{env, fs};
+use tests_common::{ContractPackageHash, Key, RuntimeArgs, U256};
+use uniswap_v2_router_sdk::{Deadline, Path, RouterCall, SdkError, SessionCall};
+
+const TOKEN_A: Key = Key::Hash([1; 32]);
+const TOKEN_B: Key = Key::Hash([2; 32]);
+const TOKEN_C: Key = Key::Hash([3; 32]);
+const ROUTER: [u8; 32] = [4; 32];
+const TO: Key = Key::Hash([5; 32]);
+
+fn cli(args: &[&str]) -> Result<String, CliError> {
+    run(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+}
+
+fn cli_json(args: &[&str]) -> Value {
+    serde_json::from_str(&cli(args).unwrap()).unwrap()
+}
+
+fn temp_file(name: &str, contents: &[u8]) -> String {
+    let file = env::temp_dir().join(format!("casperswap-cli-{}-{}", std::process::id(), name));
+    fs::write(&file, contents).unwrap();
+    file.to_string_lossy().into_owned()
+}
+
+fn args_json(args: &RuntimeArgs) -> Value {
+    args.named_args()
+        .map(|arg| {
+            let bytes: String = arg
+                .cl_value()
+                .inner_bytes()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+            json!([arg.name(), { "cl_type": arg.cl_value().cl_type(), "bytes": bytes }])
+        })
+        .collect()
+}
+
+fn swap_args(router: &str, path: &str, to: &str) -> Vec<String> {
+    [
+        "swap",
+        "--router",
+        router,
+        "--path",
+        path,
+        "--to",
+        to,
+        "--amount-in",
+        "100",
+        "--amount-out-min",
+        "40",
+        "--deadline",
+        "1000",
+        "--chain-name",
+        "casper-test",
+        "--payment-amount",
+        "5000000000",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect()
+}
+
+fn swap_call() -> RouterCall {
+    RouterCall::SwapExactTokensForTokens {
+        amount_in: U256::from(100),
+        amount_out_min: U256::from(40),
+        path: Path::new(vec![TOKEN_A, TOKEN_B, TOKEN_C]).unwrap(),
+        to: TO,
+        deadline: Deadline::At(1000),
+    }
+}
+
+fn path_arg() -> String {
+    format!(
+        "{},{},{}",
+        TOKEN_A.to_formatted_string(),
+        TOKEN_B.to_formatted_string(),
+        TOKEN_C.to_formatted_string()
+    )
+}
+
+#[test]
+fn decode_error_accepts_user_and_api_error_codes() {
+    let decoded: String = cli(&["decode-error", "63"]).unwrap();
+    assert!(decoded.starts_with("NotInWhitelist (63): "));
+    assert_eq!(cli(&["decode-error", "65599"]).unwrap(), decoded);
+    assert!(matches!(
+        cli(&["decode-error", "abc"]),
+        Err(CliError::InvalidValue { .. })
+    ));
+}
+
+#[test]
+fn quote_uses_the_snapshot_reserves_along_the_path() {
+    let snapshot = json!({
+        "pairs": [
+            {
+                "token0": TOKEN_B.to_formatted_string(),
+                "token1": TOKEN_A.to_formatted_string(),
+                "reserve0": "2000",
+                "reserve1": 1000
+            },
+            {
+                "token0": TOKEN_B.to_formatted_string(),
+                "token1": TOKEN_C.to_formatted_string(),
+                "reserve0": "2000",
+                "reserve1": "500"
+            }
+        ]
+    });
+    let snapshot: String = temp_file("snapshot.json", snapshot.to_string().as_bytes());
+    let path: String = path_arg();
+    let quote: Value = cli_json(&[
+        "quote",
+        "--snapshot",
+        &snapshot,
+        "--path",
+        &path,
+        "--amount-in",
+        "100",
+    ]);
+    // 100 * 997 * 2000 / (1000 * 1000 + 100 * 997), then 181 * 997 * 500 / (2000 * 1000 + 181 * 997)
+    assert_eq!(quote["amounts"], json!(["100", "181", "41"]));
+    let quote: Value = cli_json(&[
+        "quote",
+        "--snapshot",
+        &snapshot,
+        "--path",
+        &path,
+        "--amount-out",
+        "41",
+    ]);
+    assert_eq!(quote["amounts"][2], json!("41"));
+    assert!(matches!(
+        cli(&[
+            "quote",
+            "--snapshot",
+            &snapshot,
+            "--path",
+            &format!(
+                "{},{}",
+                TOKEN_A.to_formatted_string(),
+                TOKEN_C.to_formatted_string()
+            ),
+            "--amount-in",
+            "100",
+        ]),
+        Err(CliError::PairNotInSnapshot(..))
+    ));
+}
+
+#[test]
+fn swap_deploys_carry_the_sdk_args() {
+    let router: String = Key::Hash(ROUTER).to_formatted_string();
+    let to: String = TO.to_formatted_string();
+    let args: Vec<String> = swap_args(&router, &path_arg(), &to);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let deploy: Value = cli_json(&args);
+    assert_eq!(deploy["chain_name"], json!("casper-test"));
+    let stored: &Value = &deploy["session"]["StoredVersionedContractByHash"];
+    assert_eq!(stored["entry_point"], json!("swap_exact_tokens_for_tokens"));
+    assert_eq!(stored["args"], args_json(&swap_call().args().unwrap()));
+
+    let wasm: String = temp_file("session-code-router.wasm", &[0, 97, 115, 109]);
+    let session_args: Vec<&str> = args
+        .iter()
+        .copied()
+        .chain(["--session-wasm", &wasm, "--approve"].iter().copied())
+        .collect();
+    let deploy: Value = cli_json(&session_args);
+    let module: &Value = &deploy["session"]["ModuleBytes"];
+    assert_eq!(module["module_bytes"], json!("0061736d"));
+    let session: SessionCall = SessionCall::new(swap_call()).approve();
+    assert_eq!(
+        module["args"],
+        args_json(&session.args(ContractPackageHash::from(ROUTER)).unwrap())
+    );
+
+    let cspr_args: Vec<&str> = args.iter().copied().chain(["--cspr-in"]).collect();
+    assert!(matches!(
+        cli(&cspr_args),
+        Err(CliError::Sdk(SdkError::NeedsSession(
+            "swap_exact_cspr_for_tokens"
+        )))
+    ));
+}
@@ -0,0 +1,357 @@
+use crate::simulator_tests::{deploy, Deployed};
+use proptest::{
+    prelude::*,
+    test_runner::{Config, TestRunner},
+};
+use serde_json::json;
+use std::panic::{self, AssertUnwindSafe};
+use tests_common::{account::AccountHash, bytesrepr::ToBytes, helpers::*, *};
+use uniswap_v2_simulator::{scenario::WCSPR, Scenario};
+
+// Router entry points driven with arbitrary paths, amounts, deadlines and recipients. A call may
+// succeed or revert, but a revert has to carry a user error code, anything else is a trap such as
+// an unwrap or an index out of bounds in the router.
+
+const CASES: u32 = 64;
+
+// A router with liquidity in A/B and A/WCSPR, provided by alice who makes every fuzzed call.
+struct Target {
+    deployed: Deployed,
+    alice: AccountHash,
+    tokens: Vec<Key>,
+}
+
+fn target() -> Target {
+    let scenario = Scenario::from_json(&json!({
+        "accounts": ["alice"],
+        "tokens": ["A", "B"],
+        "pairs": [["A", "B"], ["A", "WCSPR"]],
+        "balances": {"alice": {"A": "1000000000000", "B": "1000000000000"}},
+        "steps": [
+            {"caller": "alice", "call": "add_liquidity", "token_a": "A", "token_b": "B",
+             "amount_a_desired": "100000000000", "amount_b_desired": "200000000000"},
+            {"caller": "alice", "call": "add_liquidity_cspr", "token": "A",
+             "amount_token_desired": "100000000000", "amount_cspr_desired": "30000000000"}
+        ]
+    }))
+    .unwrap();
+    let (deployed, names) = deploy(&scenario);
+    let report = deployed.run(&scenario, &names);
+    assert!(report.steps.iter().all(|step| step.outcome.is_ok()));
+    Target {
+        deployed,
+        alice: names.key("alice").unwrap().into_account().unwrap(),
+        tokens: ["A", "B", WCSPR]
+            .iter()
+            .map(|token| names.key(token).unwrap())
+            .collect(),
+    }
+}
+
+// A call of a router entry point. Entry points paid in cspr are called through the session code
+// router, which funds the caller purse with `cspr`.
+#[derive(Clone, Debug)]
+struct FuzzCall {
+    entry_point: &'static str,
+    args: RuntimeArgs,
+    cspr: Option<U512>,
+}
+
+impl FuzzCall {
+    fn new(entry_point: &'static str, args: Vec<(&str, CLValue)>) -> FuzzCall {
+        let mut runtime_args = RuntimeArgs::new();
+        for (name, value) in args {
+            runtime_args.insert_cl_value(name, value);
+        }
+        FuzzCall {
+            entry_point,
+            args: runtime_args,
+            cspr: None,
+        }
+    }
+
+    fn paid(mut self, cspr: U512) -> FuzzCall {
+        self.cspr = Some(cspr);
+        self
+    }
+}
+
+fn value<T: CLTyped + ToBytes>(value: T) -> CLValue {
+    CLValue::from_t(value).unwrap()
+}
+
+// Known tokens and packages that aren't tokens.
+fn token(tokens: &[Key]) -> impl Strategy<Value = Key> {
+    prop_oneof![
+        prop::sample::select(tokens.to_vec()),
+        any::<[u8; 32]>().prop_map(Key::Hash),
+    ]
+}
+
+// Formatted keys of tokens and accounts, and strings that aren't keys at all.
+fn path(tokens: &[Key]) -> impl Strategy<Value = Vec<String>> {
+    let entry = prop_oneof![
+        4 => token(tokens).prop_map(|token| token.to_formatted_string()),
+        1 => any::<[u8; 32]>()
+            .prop_map(|hash| Key::Account(AccountHash::new(hash)).to_formatted_string()),
+        1 => "[a-z-]{0,16}[0-9a-f]{0,66}",
+    ];
+    prop::collection::vec(entry, 0..5)
+}
+
+fn amount() -> impl Strategy<Value = U256> {
+    prop_oneof![
+        Just(U256::zero()),
+        (1u64..10_000_000).prop_map(U256::from),
+        any::<u128>().prop_map(U256::from),
+        Just(U256::MAX),
+    ]
+}
+
+// Cspr the session code router takes from alice's main purse, which she holds.
+fn cspr() -> impl Strategy<Value = U512> {
+    (1u64..10_000_000_000).prop_map(U512::from)
+}
+
+fn deadline(now: u64) -> impl Strategy<Value = U256> {
+    prop_oneof![
+        (now..now + 3_600_000).prop_map(U256::from),
+        Just(U256::from(now / 1000)),
+        Just(U256::zero()),
+        Just(U256::from(u64::MAX)),
+        Just(U256::MAX),
+    ]
+}
+
+// Recipients of every kind. Cspr is paid into purses and to contracts through their `receive`,
+// a purse or package that doesn't exist fails in the engine rather than in the router, so cspr
+// is only sent to accounts and to keys the router turns away.
+fn recipient(alice: AccountHash, cspr_out: bool) -> BoxedStrategy<Key> {
+    let accounts = prop_oneof![
+        Just(Key::Account(alice)),
+        any::<[u8; 32]>().prop_map(|hash| Key::Account(AccountHash::new(hash))),
+    ];
+    let unsupported = prop_oneof![
+        any::<[u8; 32]>().prop_map(Key::Dictionary),
+        any::<[u8; 32]>().prop_map(Key::Balance),
+        any::<u64>().prop_map(Key::EraInfo),
+    ];
+    if cspr_out {
+        return prop_oneof![accounts, unsupported].boxed();
+    }
+    prop_oneof![
+        accounts,
+        unsupported,
+        any::<[u8; 32]>().prop_map(Key::Hash),
+        any::<[u8; 32]>().prop_map(|addr| Key::URef(URef::new(addr, AccessRights::READ_ADD_WRITE))),
+    ]
+    .boxed()
+}
+
+// (entry point, exact input, cspr in, cspr out)
+const SWAPS: [(&str, bool, bool, bool); 6] = [
+    ("swap_exact_tokens_for_tokens", true, false, false),
+    ("swap_tokens_for_exact_tokens", false, false, false),
+    ("swap_exact_cspr_for_tokens", true, true, false),
+    ("swap_cspr_for_exact_tokens", false, true, false),
+    ("swap_exact_tokens_for_cspr", true, false, true),
+    ("swap_tokens_for_exact_cspr", false, false, true),
+];
+
+fn swap(target: &Target, index: usize) -> BoxedStrategy<FuzzCall> {
+    let (entry_point, exact_in, cspr_in, cspr_out) = SWAPS[index];
+    let (amount_name, limit_name) = if exact_in {
+        ("amount_in", "amount_out_min")
+    } else {
+        ("amount_out", "amount_in_max")
+    };
+    (
+        amount(),
+        amount(),
+        path(&target.tokens),
+        recipient(target.alice, cspr_out),
+        deadline(target.deployed.now),
+        cspr(),
+    )
+        .prop_map(move |(amount, limit, path, to, deadline, cspr)| {
+            let call = FuzzCall::new(
+                entry_point,
+                vec![
+                    (amount_name, value(amount)),
+                    (limit_name, value(limit)),
+                    ("path", value(path)),
+                    ("to", value(to)),
+                    ("deadline", value(deadline)),
+                ],
+            );
+            if cspr_in {
+                call.paid(cspr)
+            } else {
+                call
+            }
+        })
+        .boxed()
+}
+
+fn liquidity(target: &Target) -> BoxedStrategy<FuzzCall> {
+    let add = (
+        token(&target.tokens),
+        token(&target.tokens),
+        [amount(), amount(), amount(), amount()],
+        recipient(target.alice, false),
+        deadline(target.deployed.now),
+    )
+        .prop_map(|(token_a, token_b, amounts, to, deadline)| {
+            FuzzCall::new(
+                "add_liquidity",
+                vec![
+                    ("token_a", value(token_a)),
+                    ("token_b", value(token_b)),
+                    ("amount_a_desired", value(amounts[0])),
+                    ("amount_b_desired", value(amounts[1])),
+                    ("amount_a_min", value(amounts[2])),
+                    ("amount_b_min", value(amounts[3])),
+                    ("to", value(to)),
+                    ("pair", value(Option::<Key>::None)),
+                    ("deadline", value(deadline)),
+                ],
+            )
+        });
+    let remove = (
+        token(&target.tokens),
+        token(&target.tokens),
+        [amount(), amount(), amount()],
+        recipient(target.alice, false),
+        deadline(target.deployed.now),
+    )
+        .prop_map(|(token_a, token_b, amounts, to, deadline)| {
+            FuzzCall::new(
+                "remove_liquidity",
+                vec![
+                    ("token_a", value(token_a)),
+                    ("token_b", value(token_b)),
+                    ("liquidity", value(amounts[0])),
+                    ("amount_a_min", value(amounts[1])),
+                    ("amount_b_min", value(amounts[2])),
+                    ("to", value(to)),
+                    ("deadline", value(deadline)),
+                ],
+            )
+        });
+    prop_oneof![add, remove].boxed()
+}
+
+fn orders(target: &Target) -> BoxedStrategy<FuzzCall> {
+    let limit_order = (
+        [amount(), amount(), amount()],
+        path(&target.tokens),
+        recipient(target.alice, false),
+        deadline(target.deployed.now),
+    )
+        .prop_map(|(amounts, path, to, expiry)| {
+            FuzzCall::new(
+                "create_limit_order",
+                vec![
+                    ("amount_in", value(amounts[0])),
+                    ("amount_out_min", value(amounts[1])),
+                    ("bounty", value(amounts[2])),
+                    ("path", value(path)),
+                    ("to", value(to)),
+                    ("expiry", value(expiry)),
+                ],
+            )
+        });
+    let dca_order = (
+        [amount(), amount(), amount(), amount()],
+        any::<u64>(),
+        path(&target.tokens),
+        recipient(target.alice, false),
+    )
+        .prop_map(|(amounts, interval, path, to)| {
+            FuzzCall::new(
+                "create_dca_order",
+                vec![
+                    ("budget", value(amounts[0])),
+                    ("amount_per_interval", value(amounts[1])),
+                    ("interval", value(interval)),
+                    ("amount_out_min", value(amounts[2])),
+                    ("fee", value(amounts[3])),
+                    ("path", value(path)),
+                    ("to", value(to)),
+                ],
+            )
+        });
+    prop_oneof![limit_order, dca_order].boxed()
+}
+
+impl Target {
+    // The error of a failed call, the test env panics with the execution result of the deploy.
+    fn call(&self, fuzz_call: &FuzzCall) -> Result<(), String> {
+        let Deployed {
+            env, router, now, ..
+        } = &self.deployed;
+        panic::catch_unwind(AssertUnwindSafe(|| match fuzz_call.cspr {
+            Some(cspr) => {
+                let mut args = fuzz_call.args.clone();
+                args.insert_cl_value("entrypoint", value(fuzz_call.entry_point.to_string()));
+                args.insert_cl_value("package_hash", value(Key::Hash(router.package_hash())));
+                args.insert_cl_value("amount", value(cspr));
+                call(env, self.alice, SESSION_CODE_ROUTER, args, *now);
+            }
+            None => router.call_contract(
+                self.alice,
+                fuzz_call.entry_point,
+                fuzz_call.args.clone(),
+                *now,
+            ),
+        }))
+        .map_err(|error| {
+            error
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| error.downcast_ref::<&str>().map(|error| error.to_string()))
+                .unwrap_or_default()
+        })
+    }
+
+    fn fuzz(&self, calls: BoxedStrategy<FuzzCall>) {
+        let mut runner = TestRunner::new(Config {
+            cases: CASES,
+            failure_persistence: None,
+            ..Config::default()
+        });
+        runner
+            .run(&calls, |fuzz_call| {
+                if let Err(error) = self.call(&fuzz_call) {
+                    prop_assert!(
+                        error.contains("ApiError::User("),
+                        "{} trapped: {}",
+                        fuzz_call.entry_point,
+                        error
+                    );
+                }
+                Ok(())
+            })
+            .unwrap();
+    }
+}
+
+#[test]
+fn fuzzed_swaps_only_revert_with_user_errors() {
+    let target = target();
+    let swaps: Vec<BoxedStrategy<FuzzCall>> =
+        (0..SWAPS.len()).map(|index| swap(&target, index)).collect();
+    target.fuzz(prop::strategy::Union::new(swaps).boxed());
+}
+
+#[test]
+fn fuzzed_liquidity_calls_only_revert_with_user_errors() {
+    let target = target();
+    target.fuzz(liquidity(&target));
+}
+
+#[test]
+fn fuzzed_orders_only_revert_with_user_errors() {
+    let target = target();
+    target.fuzz(orders(&target));
+}
@@ -1,2 +1,10 @@
 #[cfg(test)]
+pub mod cli_tests;
+#[cfg(test)]
+pub mod fuzz_tests;
+#[cfg(test)]
+pub mod schema_tests;
+#[cfg(test)]
+pub mod simulator_tests;
+#[cfg(test)]
 pub mod uniswap_tests;
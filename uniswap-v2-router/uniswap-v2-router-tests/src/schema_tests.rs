@@ -0,0 +1,67 @@
+use contract_schema::{library_schema, router_schema, to_file_contents};
+use std::collections::BTreeSet;
+
+const ROUTER_SCHEMA: &str = include_str!("../../../schema/uniswap-v2-router.json");
+const LIBRARY_SCHEMA: &str = include_str!("../../../schema/uniswap-v2-library.json");
+const ROUTER_BIN: &str = include_str!("../../uniswap-v2-router/bin/uniswap.rs");
+const LIBRARY_BIN: &str =
+    include_str!("../../../uniswap-v2-library/uniswap-v2-library/bin/uniswap.rs");
+
+// Functions a contract wasm exports, other than the install `call`.
+fn exported_functions(bin: &str) -> BTreeSet<String> {
+    let mut functions: BTreeSet<String> = BTreeSet::new();
+    let mut exported: bool = false;
+    for line in bin.lines() {
+        if line.trim() == "#[no_mangle]" {
+            exported = true;
+        } else if let Some(function) = line.strip_prefix("fn ").filter(|_| exported) {
+            functions.insert(function[..function.find('(').unwrap()].to_string());
+            exported = false;
+        }
+    }
+    functions.remove("call");
+    functions
+}
+
+fn schema_entry_points(schema: &serde_json::Value) -> BTreeSet<String> {
+    schema["entry_points"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry_point| entry_point["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn router_schema_file_is_current() {
+    assert_eq!(
+        ROUTER_SCHEMA,
+        to_file_contents(&router_schema()),
+        "schema/uniswap-v2-router.json is out of date, run `make schema`"
+    );
+}
+
+#[test]
+fn library_schema_file_is_current() {
+    assert_eq!(
+        LIBRARY_SCHEMA,
+        to_file_contents(&library_schema()),
+        "schema/uniswap-v2-library.json is out of date, run `make schema`"
+    );
+}
+
+#[test]
+fn router_schema_matches_exported_functions() {
+    assert_eq!(
+        schema_entry_points(&router_schema()),
+        exported_functions(ROUTER_BIN)
+    );
+}
+
+#[test]
+fn library_schema_matches_exported_functions() {
+    assert_eq!(
+        schema_entry_points(&library_schema()),
+        exported_functions(LIBRARY_BIN)
+    );
+}
@@ -0,0 +1,380 @@
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    panic::{self, AssertUnwindSafe},
+};
+use tests_common::{account::AccountHash, deploys::*, helpers::*, *};
+use uniswap_v2_router_sdk::{RouterCall, SessionCall};
+use uniswap_v2_simulator::{
+    scenario::{ROUTER, WCSPR},
+    Ledger, Names, PairError, Report, Scenario, SimError, StepReport, MINIMUM_LIQUIDITY,
+};
+
+// A router deployed with the tokens and pairs of a scenario, every account whitelisted and
+// every token approved to the router.
+pub(crate) struct Deployed {
+    pub(crate) env: TestEnv,
+    pub(crate) router: TestContract,
+    contracts: BTreeMap<Key, TestContract>,
+    pub(crate) now: u64,
+}
+
+fn address(key: Key) -> Address {
+    match key {
+        Key::Account(account) => Address::Account(account),
+        Key::Hash(hash) => Address::Contract(hash.into()),
+        _ => panic!("Unexpected key type"),
+    }
+}
+
+impl Ledger for Deployed {
+    fn balance_of(&self, token: Key, owner: Key) -> U256 {
+        balance_of(&self.contracts[&token], address(owner))
+    }
+
+    // A pair syncs its reserves to its balances whenever the router is done with it.
+    fn reserves(&self, pair: Key, token_a: Key, token_b: Key) -> (U256, U256) {
+        (
+            self.balance_of(token_a, pair),
+            self.balance_of(token_b, pair),
+        )
+    }
+}
+
+pub(crate) fn deploy(scenario: &Scenario) -> (Deployed, Names) {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let now = now();
+    let mut names = Names::new();
+    let accounts: Vec<AccountHash> = scenario
+        .accounts
+        .iter()
+        .map(|account| {
+            let user = env.next_user();
+            names.insert(account, Key::Account(user));
+            user
+        })
+        .collect();
+    let factory = deploy_factory(&env, owner, Key::Account(owner), now);
+    let wcspr = deploy_wcspr(
+        &env,
+        "WCSPR-1",
+        owner,
+        "Wrapped cspr".into(),
+        "WCSPR".into(),
+        9,
+        0.into(),
+        now,
+    );
+    let dai = deploy_wcspr(
+        &env,
+        "DAI-1",
+        owner,
+        "Dai token".into(),
+        "DAI".into(),
+        9,
+        0.into(),
+        now,
+    );
+    let flash_swapper = deploy_flashswapper(
+        &env,
+        owner,
+        Key::Hash(wcspr.package_hash()),
+        Key::Hash(dai.package_hash()),
+        Key::Hash(factory.package_hash()),
+        now,
+    );
+    let library = deploy_library(
+        &env,
+        owner,
+        Key::Hash(factory.package_hash()),
+        ORACLE_WINDOW_SIZE,
+        ORACLE_GRANULARITY,
+        now,
+    );
+    let router = deploy_router(
+        &env,
+        owner,
+        Key::Hash(factory.package_hash()),
+        Key::Hash(wcspr.package_hash()),
+        Key::Hash(library.package_hash()),
+        now,
+    );
+    factory.call_contract(
+        owner,
+        "set_white_list",
+        runtime_args! {
+            "white_list" => Key::Hash(router.package_hash())
+        },
+        now,
+    );
+    let eta: u64 = now + TIMELOCK_DELAY;
+    for account in &accounts {
+        router.call_contract(
+            owner,
+            "queue_operation",
+            runtime_args! {
+                "action" => "add_to_whitelist",
+                "target" => Key::Account(*account),
+                "value" => 0u64,
+                "eta" => eta
+            },
+            now,
+        );
+        router.call_contract(
+            owner,
+            "execute_operation",
+            runtime_args! {
+                "operation_hash" => operation_hash("add_to_whitelist", Key::Account(*account), 0, eta)
+            },
+            eta,
+        );
+    }
+    names.insert(ROUTER, Key::Hash(router.package_hash()));
+    names.insert(WCSPR, Key::Hash(wcspr.package_hash()));
+    let mut contracts: BTreeMap<Key, TestContract> = BTreeMap::new();
+    contracts.insert(Key::Hash(wcspr.package_hash()), wcspr);
+    for token in &scenario.tokens {
+        let contract = deploy_erc20(&env, token, owner, token, token, 9, 0.into(), now);
+        names.insert(token, Key::Hash(contract.package_hash()));
+        contracts.insert(Key::Hash(contract.package_hash()), contract);
+    }
+    for (index, pair) in scenario.pair_names().iter().enumerate() {
+        let contract = deploy_pair(
+            &env,
+            &format!("PAIR-{}", index),
+            owner,
+            "pair",
+            "PR",
+            9,
+            0.into(),
+            Key::Hash(flash_swapper.package_hash()),
+            Key::Hash(factory.package_hash()),
+            now,
+        );
+        names.insert(pair, Key::Hash(contract.package_hash()));
+        contracts.insert(Key::Hash(contract.package_hash()), contract);
+    }
+    for (account, balances) in &scenario.balances {
+        for (token, amount) in balances {
+            contracts[&names.key(token).unwrap()].call_contract(
+                owner,
+                "mint",
+                runtime_args! {
+                    "to" => address(names.key(account).unwrap()),
+                    "amount" => *amount,
+                    "swap_fee" => U256::from(0),
+                    "mintid" => String::from("0")
+                },
+                now,
+            );
+        }
+    }
+    for account in &accounts {
+        for contract in contracts.values() {
+            contract.call_contract(
+                *account,
+                "approve",
+                runtime_args! {
+                    "spender" => Address::Contract(router.package_hash().into()),
+                    "amount" => U256::MAX
+                },
+                now,
+            );
+        }
+    }
+    let deployed = Deployed {
+        env,
+        router,
+        contracts,
+        now,
+    };
+    (deployed, names)
+}
+
+impl Deployed {
+    pub(crate) fn run(&self, scenario: &Scenario, names: &Names) -> Report {
+        let mut report = Report::default();
+        let calls: Vec<(Key, RouterCall)> = scenario.calls(names).unwrap();
+        for (step, (caller, router_call)) in scenario.steps.iter().zip(calls) {
+            let outcome = self.call_router(caller.into_account().unwrap(), router_call);
+            report
+                .steps
+                .push(StepReport::read(scenario, names, step, outcome, self).unwrap());
+        }
+        report
+    }
+
+    // Amounts of the session result in the order the simulator returns them.
+    fn call_router(
+        &self,
+        sender: AccountHash,
+        router_call: RouterCall,
+    ) -> Result<Vec<U256>, String> {
+        let args = SessionCall::new(router_call.clone())
+            .args(self.router.package_hash().into())
+            .map_err(|error| error.to_string())?;
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            call(&self.env, sender, SESSION_CODE_ROUTER, args, self.now)
+        }))
+        .map_err(|_| "reverted".to_string())?;
+        let result = session_result(&self.env, sender);
+        let values = |names: &[&str]| names.iter().map(|name| result.value(name)).collect();
+        Ok(match router_call {
+            RouterCall::AddLiquidity { .. } => values(&["amount_a", "amount_b", "liquidity"]),
+            RouterCall::AddLiquidityCspr { .. } => {
+                values(&["amount_token", "amount_cspr", "liquidity"])
+            }
+            RouterCall::RemoveLiquidity { .. } => values(&["amount_a", "amount_b"]),
+            RouterCall::RemoveLiquidityCspr { .. } => values(&["amount_token", "amount_cspr"]),
+            _ => result.amounts.clone(),
+        })
+    }
+}
+
+// Runs the scenario in the simulator and through a deployed router, step by step the outcomes,
+// reserves and token balances must be the same. Cspr is left out, gas blurs it on chain.
+fn assert_simulated_as_deployed(json: Value) {
+    let scenario = Scenario::from_json(&json).unwrap();
+    let (deployed, names) = deploy(&scenario);
+    let simulated: Report = scenario.run_with(&names).unwrap();
+    let on_chain: Report = deployed.run(&scenario, &names);
+    for (index, (simulated, on_chain)) in simulated.steps.iter().zip(&on_chain.steps).enumerate() {
+        assert_eq!(
+            simulated.outcome.is_ok(),
+            on_chain.outcome.is_ok(),
+            "step {} `{}`: simulated {:?}, deployed {:?}",
+            index,
+            simulated.call,
+            simulated.outcome,
+            on_chain.outcome
+        );
+        if simulated.outcome.is_ok() {
+            assert_eq!(simulated.outcome, on_chain.outcome, "step {}", index);
+        }
+        assert_eq!(simulated.reserves, on_chain.reserves, "step {}", index);
+        assert_eq!(simulated.balances, on_chain.balances, "step {}", index);
+    }
+}
+
+fn token_scenario() -> Value {
+    json!({
+        "accounts": ["alice", "bob"],
+        "tokens": ["A", "B", "C"],
+        "pairs": [["A", "B"], ["B", "C"]],
+        "balances": {
+            "alice": {"A": "1000000000000", "B": "1000000000000", "C": "1000000000000"},
+            "bob": {"A": "10000000", "C": "10000000"}
+        },
+        "steps": [
+            {"caller": "alice", "call": "add_liquidity", "token_a": "A", "token_b": "B",
+             "amount_a_desired": "100000000000", "amount_b_desired": "200000000000"},
+            {"caller": "alice", "call": "add_liquidity", "token_a": "C", "token_b": "B",
+             "amount_a_desired": "50000000000", "amount_b_desired": "100000000000"},
+            {"caller": "bob", "call": "swap_exact_tokens_for_tokens", "amount_in": "1000000",
+             "path": ["A", "B", "C"]},
+            {"caller": "bob", "call": "swap_tokens_for_exact_tokens", "amount_out": "500000",
+             "amount_in_max": "10000000", "path": ["C", "B", "A"]},
+            // more of b than the ratio takes, b is cut to the optimal amount
+            {"caller": "alice", "call": "add_liquidity", "token_a": "A", "token_b": "B",
+             "amount_a_desired": "1000000", "amount_b_desired": "5000000"},
+            // less of b than the ratio takes, a is cut to the optimal amount
+            {"caller": "alice", "call": "add_liquidity", "token_a": "A", "token_b": "B",
+             "amount_a_desired": "1000000", "amount_b_desired": "1000000"},
+            {"caller": "alice", "call": "remove_liquidity", "token_a": "B", "token_b": "A",
+             "liquidity": "1000000000"},
+            {"caller": "bob", "call": "swap_exact_tokens_for_tokens", "amount_in": "1000000",
+             "amount_out_min": "3000000", "path": ["A", "B"]},
+            {"caller": "bob", "call": "swap_exact_tokens_for_tokens", "amount_in": "1000000",
+             "path": ["C", "B"], "to": "alice"}
+        ]
+    })
+}
+
+#[test]
+fn simulator_matches_router_for_tokens() {
+    assert_simulated_as_deployed(token_scenario());
+}
+
+#[test]
+fn simulator_matches_router_for_cspr() {
+    assert_simulated_as_deployed(json!({
+        "accounts": ["alice", "bob"],
+        "tokens": ["A"],
+        "pairs": [["A", "WCSPR"]],
+        "balances": {"alice": {"A": "1000000000000"}, "bob": {"A": "10000000"}},
+        "cspr": {"alice": "100000000000", "bob": "100000000000"},
+        "steps": [
+            {"caller": "alice", "call": "add_liquidity_cspr", "token": "A",
+             "amount_token_desired": "10000000000", "amount_cspr_desired": "30000000000"},
+            {"caller": "bob", "call": "swap_exact_cspr_for_tokens", "amount_in": "3000000",
+             "path": ["WCSPR", "A"]},
+            {"caller": "bob", "call": "swap_cspr_for_exact_tokens", "amount_out": "1000000",
+             "amount_in_max": "5000000", "path": ["WCSPR", "A"]},
+            {"caller": "bob", "call": "swap_exact_tokens_for_cspr", "amount_in": "1000000",
+             "path": ["A", "WCSPR"]},
+            {"caller": "bob", "call": "swap_tokens_for_exact_cspr", "amount_out": "1000000",
+             "amount_in_max": "1000000", "path": ["A", "WCSPR"]},
+            {"caller": "alice", "call": "remove_liquidity_cspr", "token": "A",
+             "liquidity": "1000000000"}
+        ]
+    }));
+}
+
+#[test]
+fn simulated_steps_report_the_ratio_and_leave_reverted_steps_out() {
+    let report: Report = Scenario::from_json(&token_scenario())
+        .unwrap()
+        .run()
+        .unwrap();
+    let first_liquidity: U256 = U256::from(100_000_000_000u64) * 200_000_000_000u64;
+    assert_eq!(
+        report.steps[0].outcome,
+        Ok(vec![
+            100_000_000_000u64.into(),
+            200_000_000_000u64.into(),
+            first_liquidity.integer_sqrt() - MINIMUM_LIQUIDITY
+        ])
+    );
+    let ab = |step: usize, token: &str| report.steps[step].reserves["A/B"][token];
+    let (amounts, reserve_a, reserve_b) = match &report.steps[4].outcome {
+        Ok(amounts) => (amounts, ab(3, "A"), ab(3, "B")),
+        Err(error) => panic!("{}", error),
+    };
+    assert_eq!(amounts[0], 1_000_000.into());
+    assert_eq!(amounts[1], U256::from(1_000_000) * reserve_b / reserve_a);
+    // the slippage check reverts the swap and nothing moves
+    assert!(report.steps[7].outcome.is_err());
+    assert_eq!(report.steps[7].reserves, report.steps[6].reserves);
+    assert_eq!(report.steps[7].balances, report.steps[6].balances);
+    // bob swaps for alice
+    let amount_out: U256 = report.steps[8].outcome.as_ref().unwrap()[1];
+    assert_eq!(
+        report.steps[8].balances["alice"]["B"],
+        report.steps[7].balances["alice"]["B"] + amount_out
+    );
+}
+
+#[test]
+fn scenario_with_unknown_names_is_rejected() {
+    let mut scenario: Value = token_scenario();
+    scenario["steps"][2]["path"] = json!(["A", "D"]);
+    assert_eq!(
+        Scenario::from_json(&scenario).unwrap_err(),
+        SimError::InvalidScenario("unknown name `D`".into())
+    );
+    let mut scenario: Value = token_scenario();
+    scenario["tokens"] = json!(["A", "B", "WCSPR"]);
+    assert!(matches!(
+        Scenario::from_json(&scenario),
+        Err(SimError::InvalidScenario(_))
+    ));
+    let mut scenario: Value = token_scenario();
+    scenario["steps"] = json!([{"caller": "alice", "call": "add_liquidity", "token_a": "A",
+        "token_b": "B", "amount_a_desired": "1000", "amount_b_desired": "1000"}]);
+    let report: Report = Scenario::from_json(&scenario).unwrap().run().unwrap();
+    assert_eq!(
+        report.steps[0].outcome,
+        Err(SimError::Pair(PairError::InsufficientLiquidityMinted).to_string())
+    );
+}
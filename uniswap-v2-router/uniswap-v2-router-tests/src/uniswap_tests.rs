@@ -1,7 +1,17 @@
-use std::time::{SystemTime, UNIX_EPOCH};
-use tests_common::{account::AccountHash, deploys::*, functions::u256_to_u512, helpers::*, *, bytesrepr::ToBytes};
+use std::panic::{self, AssertUnwindSafe};
+use tests_common::{
+    account::AccountHash,
+    bytesrepr::ToBytes,
+    deploys::*,
+    functions::{u256_to_u512, u512_to_u256},
+    helpers::*,
+    script::Script,
+    session::{SessionResult, INPUT_U512, INPUT_U64},
+    *,
+};
+use uniswap_v2_router_sdk::{Deadline, Path, RouterCall, SdkError, SessionCall};
 
-fn deploy() -> (
+type Deployment = (
     TestEnv,      // env
     AccountHash,  // owner
     TestContract, // router_contract
@@ -13,7 +23,26 @@ fn deploy() -> (
     TestContract, // wcspr
     TestContract, // factory
     u64,          // time
-) {
+);
+
+fn deploy() -> Deployment {
+    let deployment = deploy_contracts(deploy_router);
+    let (_, owner, router, _, _, token1, token2, _, _, factory, now) = &deployment;
+    init(*owner, token1, token2, factory, router, *now);
+    deployment
+}
+
+// Deployment with the router installed from the v1 fixture, whitelisted the way v1 did it.
+fn deploy_v1() -> Deployment {
+    let deployment = deploy_contracts(deploy_router_v1);
+    let (_, owner, router, _, _, token1, token2, _, _, factory, now) = &deployment;
+    init_v1(*owner, token1, token2, factory, router, *now);
+    deployment
+}
+
+fn deploy_contracts(
+    deploy_router: fn(&TestEnv, AccountHash, Key, Key, Key, u64) -> TestContract,
+) -> Deployment {
     let env = TestEnv::new();
     let owner = env.next_user();
     let now = now();
@@ -67,21 +96,20 @@ fn deploy() -> (
         },
         now,
     );
-    let library_contract = deploy_library(&env, owner, now);
-    let router_contract = deploy_router(
+    let library_contract = deploy_library(
         &env,
         owner,
         Key::Hash(factory_contract.package_hash()),
-        Key::Hash(wcspr.package_hash()),
-        Key::Hash(library_contract.package_hash()),
+        ORACLE_WINDOW_SIZE,
+        ORACLE_GRANULARITY,
         now,
     );
-    init(
+    let router_contract = deploy_router(
+        &env,
         owner,
-        &token1,
-        &token2,
-        &factory_contract,
-        &router_contract,
+        Key::Hash(factory_contract.package_hash()),
+        Key::Hash(wcspr.package_hash()),
+        Key::Hash(library_contract.package_hash()),
         now,
     );
     (
@@ -111,102 +139,89 @@ fn add_and_remove_liquidity_with_tokens() {
     let amount_b_min: U256 = 1000000.into();
     let to = Key::Account(owner);
     let pair_ = Some(Key::Hash(pair.package_hash()));
-    let deadline: U256 = match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(n) => (n.as_millis() + (1000 * (30 * 60))).into(), // current epoch time in milisecond + 30 minutes
-        Err(_) => 0.into(),
-    };
-    call(
-      &env,
-      owner,
-      SESSION_CODE_ROUTER,
-      runtime_args! {
-          "entrypoint" => "add_liquidity",
-          "package_hash" => Key::from(router_package_hash),
-          "token_a" => token_a,
-          "token_b" => token_b,
-          "amount_a_desired" => amount_a_desired,
-          "amount_b_desired" => amount_b_desired,
-          "amount_a_min" => amount_a_min,
-          "amount_b_min" => amount_b_min,
-          "to" => to,
-          "deadline" => deadline,
-          "pair" => pair_,
-      },
-      now,
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::AddLiquidity {
+            token_a,
+            token_b,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            to,
+            deadline,
+            pair: pair_,
+        },
+        now,
     );
     let amount_in: U256 = 100000.into();
     let amount_out_min: U256 = 1000.into();
-    let path1: Vec<String> = vec![token_a.to_formatted_string(), token_b.to_formatted_string()];
-    call(
+    let path1 = Path::new(vec![token_a, token_b]).unwrap();
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "swap_exact_tokens_for_tokens",
-            "package_hash" => Key::from(router_package_hash),
-            "amount_in" => amount_in,
-            "amount_out_min" => amount_out_min,
-            "path" => path1,
-            "to" => to,
-            "deadline" => deadline,
+        &router,
+        RouterCall::SwapExactTokensForTokens {
+            amount_in,
+            amount_out_min,
+            path: path1,
+            to,
+            deadline,
         },
         now,
     );
-    call(
-      &env,
-      owner,
-      SESSION_CODE_ROUTER,
-      runtime_args! {
-          "entrypoint" => "add_liquidity",
-          "package_hash" => Key::from(router_package_hash),
-          "token_a" => token_a,
-          "token_b" => token_b,
-          "amount_a_desired" => amount_a_desired,
-          "amount_b_desired" => amount_b_desired,
-          "amount_a_min" => amount_a_min,
-          "amount_b_min" => amount_b_min,
-          "to" => to,
-          "deadline" => deadline,
-          "pair" => pair_,
-      },
-      now,
-    );
-  let path2: Vec<String> = vec![token_a.to_formatted_string(), token_b.to_formatted_string()];
-  call(
-      &env,
-      owner,
-      SESSION_CODE_ROUTER,
-      runtime_args! {
-          "entrypoint" => "swap_exact_tokens_for_tokens",
-          "package_hash" => Key::from(router_package_hash),
-          "amount_in" => amount_in,
-          "amount_out_min" => amount_out_min,
-          "path" => path2,
-          "to" => to,
-          "deadline" => deadline,
-      },
-      now,
-  );
-  let ret: Vec<U256> = result_key(&env, owner, "swap_exact_tokens_for_tokens");
-  assert_eq!(ret, [100000.into(), 99699.into()]);
-    call(
-      &env,
-      owner,
-      SESSION_CODE_ROUTER,
-      runtime_args! {
-          "entrypoint" => "add_liquidity",
-          "package_hash" => Key::from(router_package_hash),
-          "token_a" => token_a,
-          "token_b" => token_b,
-          "amount_a_desired" => amount_a_desired,
-          "amount_b_desired" => amount_b_desired,
-          "amount_a_min" => amount_a_min,
-          "amount_b_min" => amount_b_min,
-          "to" => to,
-          "deadline" => deadline,
-          "pair" => pair_,
-      },
-      now,
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::AddLiquidity {
+            token_a,
+            token_b,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            to,
+            deadline,
+            pair: pair_,
+        },
+        now,
+    );
+    let path2 = Path::new(vec![token_a, token_b]).unwrap();
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::SwapExactTokensForTokens {
+            amount_in,
+            amount_out_min,
+            path: path2,
+            to,
+            deadline,
+        },
+        now,
+    );
+    let ret: Vec<U256> = result_key(&env, owner, "swap_exact_tokens_for_tokens");
+    assert_eq!(ret, [100000.into(), 99699.into()]);
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::AddLiquidity {
+            token_a,
+            token_b,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            to,
+            deadline,
+            pair: pair_,
+        },
+        now,
     );
     let (amount_a, amount_b, liquidity): (U256, U256, U256) =
         result_key(&env, owner, "add_liquidity");
@@ -221,23 +236,21 @@ fn add_and_remove_liquidity_with_tokens() {
         },
         now,
     );
-    call(
-      &env,
-      owner,
-      SESSION_CODE_ROUTER,
-      runtime_args! {
-          "entrypoint" => "remove_liquidity",
-          "package_hash" => Key::from(router_package_hash),
-          "token_a" => token_a,
-          "token_b" => token_b,
-          "liquidity" => liquidity,
-          "amount_a_min" => amount_a_min,
-          "amount_b_min" => amount_b_min,
-          "to" => to,
-          "deadline" => deadline,
-      },
-      now,
-  );
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::RemoveLiquidity {
+            token_a,
+            token_b,
+            liquidity,
+            amount_a_min,
+            amount_b_min,
+            to,
+            deadline,
+        },
+        now,
+    );
     let (amount_a, amount_b): (U256, U256) = result_key(&env, owner, "remove_liquidity");
     assert_ge!(amount_a, amount_a_min);
     assert_ge!(amount_b, amount_b_min);
@@ -254,26 +267,20 @@ fn add_and_remove_liquidity_with_cspr() {
     let amount_cspr_min: U256 = 1000000.into();
     let to = Key::Account(owner);
     let pair_ = Some(Key::Hash(pair.package_hash()));
-    let deadline: U256 = match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(n) => (n.as_millis() + (1000 * (30 * 60))).into(), // current epoch time in milisecond + 30 minutes
-        Err(_) => 0.into(),
-    };
-    call(
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "add_liquidity_cspr",
-            "package_hash" => Key::from(router_package_hash),
-            "amount" => u256_to_u512(amount_cspr_desired),
-            "token" => token,
-            "amount_token_desired" => amount_token_desired,
-            "amount_cspr_desired" => amount_cspr_desired,
-            "amount_token_min" => amount_token_min,
-            "amount_cspr_min" => amount_cspr_min,
-            "to" => to,
-            "deadline" => deadline,
-            "pair" => pair_
+        &router,
+        RouterCall::AddLiquidityCspr {
+            token,
+            amount_token_desired,
+            amount_cspr_desired,
+            amount_token_min,
+            amount_cspr_min,
+            to,
+            deadline,
+            pair: pair_,
         },
         now,
     );
@@ -290,34 +297,29 @@ fn add_and_remove_liquidity_with_cspr() {
         },
         now,
     );
-    // find a way to generate purses
-    /*call(
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "remove_liquidity_cspr",
-            "package_hash" => Key::from(router_package_hash),
-            "token" => token,
-            "liquidity" => liquidity,
-            "amount_token_min" => amount_token_min,
-            "amount_cspr_min" => amount_cspr_min,
-            "to" => to,
-            "deadline" => deadline,
-            "to_purse" => owner.
+        &router,
+        RouterCall::RemoveLiquidityCspr {
+            token,
+            liquidity,
+            amount_token_min,
+            amount_cspr_min,
+            to,
+            deadline,
         },
         now,
     );
     let (amount_token, amount_cspr): (U256, U256) =
         result_key(&env, owner, "remove_liquidity_cspr");
     assert_ge!(amount_token, amount_token_min);
-    assert_ge!(amount_cspr, amount_cspr_min);*/
+    assert_ge!(amount_cspr, amount_cspr_min);
 }
 
 #[test]
 fn swap_exact_tokens_for_tokens() {
     let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
-    let router_package_hash: ContractPackageHash = router.package_hash().into();
     let token_a = Key::Hash(token1.package_hash());
     let token_b = Key::Hash(token2.package_hash());
     let amount_a_desired: U256 = AMOUNT;
@@ -326,26 +328,21 @@ fn swap_exact_tokens_for_tokens() {
     let amount_b_min: U256 = 1000000.into();
     let to = Key::Account(owner);
     let pair_ = Some(Key::Hash(pair.package_hash()));
-    let deadline: U256 = match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(n) => (n.as_millis() + (1000 * (30 * 60))).into(), // current epoch time in milisecond + 30 minutes
-        Err(_) => 0.into(),
-    };
-    call(
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "add_liquidity",
-            "package_hash" => Key::from(router_package_hash),
-            "token_a" => token_a,
-            "token_b" => token_b,
-            "amount_a_desired" => amount_a_desired,
-            "amount_b_desired" => amount_b_desired,
-            "amount_a_min" => amount_a_min,
-            "amount_b_min" => amount_b_min,
-            "to" => to,
-            "deadline" => deadline,
-            "pair" => pair_,
+        &router,
+        RouterCall::AddLiquidity {
+            token_a,
+            token_b,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            to,
+            deadline,
+            pair: pair_,
         },
         now,
     );
@@ -355,19 +352,17 @@ fn swap_exact_tokens_for_tokens() {
     // SWAP
     let amount_in: U256 = 100000.into();
     let amount_out_min: U256 = 1000.into();
-    let path: Vec<String> = vec![token_a.to_formatted_string(), token_b.to_formatted_string()];
-    call(
+    let path = Path::new(vec![token_a, token_b]).unwrap();
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "swap_exact_tokens_for_tokens",
-            "package_hash" => Key::from(router_package_hash),
-            "amount_in" => amount_in,
-            "amount_out_min" => amount_out_min,
-            "path" => path,
-            "to" => to,
-            "deadline" => deadline,
+        &router,
+        RouterCall::SwapExactTokensForTokens {
+            amount_in,
+            amount_out_min,
+            path,
+            to,
+            deadline,
         },
         now,
     );
@@ -378,7 +373,6 @@ fn swap_exact_tokens_for_tokens() {
 #[test]
 fn swap_tokens_for_exact_tokens() {
     let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
-    let router_package_hash: ContractPackageHash = router.package_hash().into();
     let token_a = Key::Hash(token1.package_hash());
     let token_b = Key::Hash(token2.package_hash());
     let amount_a_desired: U256 = AMOUNT;
@@ -387,26 +381,21 @@ fn swap_tokens_for_exact_tokens() {
     let amount_b_min: U256 = 1000000.into();
     let to = Key::Account(owner);
     let pair_ = Some(Key::Hash(pair.package_hash()));
-    let deadline: U256 = match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(n) => (n.as_millis() + (1000 * (30 * 60))).into(), // current epoch time in milisecond + 30 minutes
-        Err(_) => 0.into(),
-    };
-    call(
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "add_liquidity",
-            "package_hash" => Key::from(router_package_hash),
-            "token_a" => token_a,
-            "token_b" => token_b,
-            "amount_a_desired" => amount_a_desired,
-            "amount_b_desired" => amount_b_desired,
-            "amount_a_min" => amount_a_min,
-            "amount_b_min" => amount_b_min,
-            "to" => to,
-            "deadline" => deadline,
-            "pair" => pair_,
+        &router,
+        RouterCall::AddLiquidity {
+            token_a,
+            token_b,
+            amount_a_desired,
+            amount_b_desired,
+            amount_a_min,
+            amount_b_min,
+            to,
+            deadline,
+            pair: pair_,
         },
         now,
     );
@@ -416,19 +405,17 @@ fn swap_tokens_for_exact_tokens() {
     // SWAP
     let amount_in_max: U256 = 1000000.into();
     let amount_out: U256 = 10000.into();
-    let path: Vec<String> = vec![token_a.to_formatted_string(), token_b.to_formatted_string()];
-    call(
+    let path = Path::new(vec![token_a, token_b]).unwrap();
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "swap_tokens_for_exact_tokens",
-            "package_hash" => Key::from(router_package_hash),
-            "amount_out" => amount_out,
-            "amount_in_max" => amount_in_max,
-            "path" => path,
-            "to" => to,
-            "deadline" => deadline,
+        &router,
+        RouterCall::SwapTokensForExactTokens {
+            amount_out,
+            amount_in_max,
+            path,
+            to,
+            deadline,
         },
         now,
     );
@@ -439,7 +426,6 @@ fn swap_tokens_for_exact_tokens() {
 #[test]
 fn swap_exact_cspr_for_tokens() {
     let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
-    let router_package_hash: ContractPackageHash = router.package_hash().into();
     let token = Key::Hash(token1.package_hash());
     let amount_token_desired: U256 = AMOUNT;
     let amount_cspr_desired: U256 = AMOUNT;
@@ -447,26 +433,20 @@ fn swap_exact_cspr_for_tokens() {
     let amount_cspr_min: U256 = 1000000.into();
     let to = Key::Account(owner);
     let pair_ = Some(Key::Hash(pair.package_hash()));
-    let deadline: U256 = match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(n) => (n.as_millis() + (1000 * (30 * 60))).into(), // current epoch time in milisecond + 30 minutes
-        Err(_) => 0.into(),
-    };
-    call(
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "add_liquidity_cspr",
-            "package_hash" => Key::from(router_package_hash),
-            "amount" => u256_to_u512(amount_cspr_desired),
-            "token" => token,
-            "amount_token_desired" => amount_token_desired,
-            "amount_cspr_desired" => amount_cspr_desired,
-            "amount_token_min" => amount_token_min,
-            "amount_cspr_min" => amount_cspr_min,
-            "to" => to,
-            "deadline" => deadline,
-            "pair" => pair_
+        &router,
+        RouterCall::AddLiquidityCspr {
+            token,
+            amount_token_desired,
+            amount_cspr_desired,
+            amount_token_min,
+            amount_cspr_min,
+            to,
+            deadline,
+            pair: pair_,
         },
         now,
     );
@@ -477,23 +457,17 @@ fn swap_exact_cspr_for_tokens() {
     // SWAP
     let amount_out_min: U256 = 10000.into();
     let amount_in: U256 = 1000000.into();
-    let path: Vec<String> = vec![
-        Key::Hash(wcspr.package_hash()).to_formatted_string(),
-        token.to_formatted_string(),
-    ];
-    call(
+    let path = Path::new(vec![Key::Hash(wcspr.package_hash()), token]).unwrap();
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "swap_exact_cspr_for_tokens",
-            "package_hash" => Key::from(router_package_hash),
-            "amount" => u256_to_u512(amount_in),
-            "amount_out_min" => amount_out_min,
-            "amount_in" => amount_in,
-            "path" => path,
-            "to" => to,
-            "deadline" => deadline,
+        &router,
+        RouterCall::SwapExactCsprForTokens {
+            amount_out_min,
+            amount_in,
+            path,
+            to,
+            deadline,
         },
         now,
     );
@@ -504,7 +478,6 @@ fn swap_exact_cspr_for_tokens() {
 #[test]
 fn swap_cspr_for_exact_tokens() {
     let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
-    let router_package_hash: ContractPackageHash = router.package_hash().into();
     let token = Key::Hash(token1.package_hash());
     let amount_token_desired: U256 = AMOUNT;
     let amount_cspr_desired: U256 = AMOUNT;
@@ -512,26 +485,20 @@ fn swap_cspr_for_exact_tokens() {
     let amount_cspr_min: U256 = 1000000.into();
     let to = Key::Account(owner);
     let pair_ = Some(Key::Hash(pair.package_hash()));
-    let deadline: U256 = match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(n) => (n.as_millis() + (1000 * (30 * 60))).into(), // current epoch time in milisecond + 30 minutes
-        Err(_) => 0.into(),
-    };
-    call(
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "add_liquidity_cspr",
-            "package_hash" => Key::from(router_package_hash),
-            "amount" => u256_to_u512(amount_cspr_desired),
-            "token" => token,
-            "amount_token_desired" => amount_token_desired,
-            "amount_cspr_desired" => amount_cspr_desired,
-            "amount_token_min" => amount_token_min,
-            "amount_cspr_min" => amount_cspr_min,
-            "to" => to,
-            "deadline" => deadline,
-            "pair" => pair_
+        &router,
+        RouterCall::AddLiquidityCspr {
+            token,
+            amount_token_desired,
+            amount_cspr_desired,
+            amount_token_min,
+            amount_cspr_min,
+            to,
+            deadline,
+            pair: pair_,
         },
         now,
     );
@@ -542,23 +509,17 @@ fn swap_cspr_for_exact_tokens() {
     // SWAP
     let amount_out: U256 = 10000.into();
     let amount_in_max: U256 = 1000000.into();
-    let path: Vec<String> = vec![
-        Key::Hash(wcspr.package_hash()).to_formatted_string(),
-        token.to_formatted_string(),
-    ];
-    call(
+    let path = Path::new(vec![Key::Hash(wcspr.package_hash()), token]).unwrap();
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "swap_cspr_for_exact_tokens",
-            "package_hash" => Key::from(router_package_hash),
-            "amount" => u256_to_u512(amount_in_max),
-            "amount_out" => amount_out,
-            "amount_in_max" => amount_in_max,
-            "path" => path,
-            "to" => to,
-            "deadline" => deadline,
+        &router,
+        RouterCall::SwapCsprForExactTokens {
+            amount_out,
+            amount_in_max,
+            path,
+            to,
+            deadline,
         },
         now,
     );
@@ -566,11 +527,9 @@ fn swap_cspr_for_exact_tokens() {
     assert_eq!(ret, [10031.into(), 10000.into()]);
 }
 
-//#[test]
-// Re-enable when I can generate purses
+#[test]
 fn swap_exact_tokens_for_cspr() {
     let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
-    let router_package_hash: ContractPackageHash = router.package_hash().into();
     let token = Key::Hash(token1.package_hash());
     let amount_token_desired: U256 = AMOUNT;
     let amount_cspr_desired: U256 = AMOUNT;
@@ -578,26 +537,20 @@ fn swap_exact_tokens_for_cspr() {
     let amount_cspr_min: U256 = 1000000.into();
     let to = Key::Account(owner);
     let pair_ = Some(Key::Hash(pair.package_hash()));
-    let deadline: U256 = match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(n) => (n.as_millis() + (1000 * (30 * 60))).into(), // current epoch time in milisecond + 30 minutes
-        Err(_) => 0.into(),
-    };
-    call(
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "add_liquidity_cspr",
-            "package_hash" => Key::from(router_package_hash),
-            "amount" => u256_to_u512(amount_cspr_desired),
-            "token" => token,
-            "amount_token_desired" => amount_token_desired,
-            "amount_cspr_desired" => amount_cspr_desired,
-            "amount_token_min" => amount_token_min,
-            "amount_cspr_min" => amount_cspr_min,
-            "to" => to,
-            "deadline" => deadline,
-            "pair" => pair_
+        &router,
+        RouterCall::AddLiquidityCspr {
+            token,
+            amount_token_desired,
+            amount_cspr_desired,
+            amount_token_min,
+            amount_cspr_min,
+            to,
+            deadline,
+            pair: pair_,
         },
         now,
     );
@@ -608,21 +561,17 @@ fn swap_exact_tokens_for_cspr() {
     // SWAP
     let amount_out_min: U256 = 10000.into();
     let amount_in: U256 = 1000000.into();
-    let path: Vec<String> = vec![
-        token.to_formatted_string(),
-        Key::Hash(wcspr.package_hash()).to_formatted_string(),
-    ];
-    call(
+    let path = Path::new(vec![token, Key::Hash(wcspr.package_hash())]).unwrap();
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "swap_exact_tokens_for_cspr",
-            "package_hash" => Key::from(router_package_hash),
-            "amount_out_min" => amount_out_min,
-            "amount_in" => amount_in,
-            "path" => path,
-            "deadline" => deadline,
+        &router,
+        RouterCall::SwapExactTokensForCspr {
+            amount_out_min,
+            amount_in,
+            path,
+            to,
+            deadline,
         },
         now,
     );
@@ -630,11 +579,9 @@ fn swap_exact_tokens_for_cspr() {
     assert_eq!(ret, [1000000.into(), 996990.into()]);
 }
 
-//#[test]
-// Re-enable when I can generate purses
+#[test]
 fn swap_tokens_for_exact_cspr() {
     let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
-    let router_package_hash: ContractPackageHash = router.package_hash().into();
     let token = Key::Hash(token1.package_hash());
     let amount_token_desired: U256 = AMOUNT;
     let amount_cspr_desired: U256 = AMOUNT;
@@ -642,26 +589,20 @@ fn swap_tokens_for_exact_cspr() {
     let amount_cspr_min: U256 = 1000000.into();
     let to = Key::Account(owner);
     let pair_ = Some(Key::Hash(pair.package_hash()));
-    let deadline: U256 = match SystemTime::now().duration_since(UNIX_EPOCH) {
-        Ok(n) => (n.as_millis() + (1000 * (30 * 60))).into(), // current epoch time in milisecond + 30 minutes
-        Err(_) => 0.into(),
-    };
-    call(
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "add_liquidity_cspr",
-            "package_hash" => Key::from(router_package_hash),
-            "amount" => u256_to_u512(amount_cspr_desired),
-            "token" => token,
-            "amount_token_desired" => amount_token_desired,
-            "amount_cspr_desired" => amount_cspr_desired,
-            "amount_token_min" => amount_token_min,
-            "amount_cspr_min" => amount_cspr_min,
-            "to" => to,
-            "deadline" => deadline,
-            "pair" => pair_
+        &router,
+        RouterCall::AddLiquidityCspr {
+            token,
+            amount_token_desired,
+            amount_cspr_desired,
+            amount_token_min,
+            amount_cspr_min,
+            to,
+            deadline,
+            pair: pair_,
         },
         now,
     );
@@ -672,25 +613,2843 @@ fn swap_tokens_for_exact_cspr() {
     // SWAP
     let amount_out: U256 = 10000.into();
     let amount_in_max: U256 = 1000000.into();
-    let path: Vec<String> = vec![
-        token.to_formatted_string(),
-        Key::Hash(wcspr.package_hash()).to_formatted_string(),
-    ];
-    call(
+    let path = Path::new(vec![token, Key::Hash(wcspr.package_hash())]).unwrap();
+    call_router(
         &env,
         owner,
-        SESSION_CODE_ROUTER,
-        runtime_args! {
-            "entrypoint" => "swap_tokens_for_exact_cspr",
-            "package_hash" => Key::from(router_package_hash),
-            "amount_out" => amount_out,
-            "amount_in_max" => amount_in_max,
-            "path" => path,
-            "to" => to,
-            "deadline" => deadline,
+        &router,
+        RouterCall::SwapTokensForExactCspr {
+            amount_out,
+            amount_in_max,
+            path,
+            to,
+            deadline,
         },
         now,
     );
     let ret: Vec<U256> = result_key(&env, owner, "swap_tokens_for_exact_cspr");
     assert_eq!(ret, [10031.into(), 10000.into()]);
 }
+
+fn call_router(
+    env: &TestEnv,
+    sender: AccountHash,
+    router: &TestContract,
+    router_call: RouterCall,
+    now: u64,
+) {
+    let args = SessionCall::new(router_call)
+        .args(router.package_hash().into())
+        .unwrap();
+    call(env, sender, SESSION_CODE_ROUTER, args, now);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_token_liquidity(
+    env: &TestEnv,
+    owner: AccountHash,
+    router: &TestContract,
+    token_a: Key,
+    token_b: Key,
+    pair: Key,
+    deadline: Deadline,
+    now: u64,
+) {
+    call_router(
+        env,
+        owner,
+        router,
+        RouterCall::AddLiquidity {
+            token_a,
+            token_b,
+            amount_a_desired: AMOUNT,
+            amount_b_desired: AMOUNT,
+            amount_a_min: U256::from(1000000),
+            amount_b_min: U256::from(1000000),
+            to: Key::Account(owner),
+            deadline,
+            pair: Some(pair),
+        },
+        now,
+    );
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_limit_order(
+    env: &TestEnv,
+    owner: AccountHash,
+    router: &TestContract,
+    path: Path,
+    amount_in: U256,
+    amount_out_min: U256,
+    bounty: U256,
+    to: Key,
+    expiry: Deadline,
+    now: u64,
+) -> U256 {
+    call_router(
+        env,
+        owner,
+        router,
+        RouterCall::CreateLimitOrder {
+            amount_in,
+            amount_out_min,
+            bounty,
+            path,
+            to,
+            expiry,
+        },
+        now,
+    );
+    result_key(env, owner, "create_limit_order")
+}
+
+#[test]
+fn limit_order_filled_after_reserves_move() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let router_address = Address::Contract(router.package_hash().into());
+    let recipient = env.next_user();
+    let filler = env.next_user();
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let amount_in: U256 = 100000.into();
+    let amount_out_min: U256 = 110000.into();
+    let bounty: U256 = 1000.into();
+    let order_id = create_limit_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        amount_in,
+        amount_out_min,
+        bounty,
+        Key::Account(recipient),
+        deadline,
+        now,
+    );
+    assert_eq!(order_id, 0.into());
+    assert_eq!(balance_of(&token1, router_address), amount_in + bounty);
+    // Sell token_b into the pool so that token_a becomes expensive enough for the limit
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::SwapExactTokensForTokens {
+            amount_in: AMOUNT / 10,
+            amount_out_min: U256::from(1),
+            path: Path::new(vec![token_b, token_a]).unwrap(),
+            to: Key::Account(owner),
+            deadline,
+        },
+        now,
+    );
+    router.call_contract(
+        filler,
+        "fill_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    assert_ge!(
+        balance_of(&token2, Address::Account(recipient)),
+        amount_out_min
+    );
+    assert_eq!(balance_of(&token1, Address::Account(filler)), bounty);
+    assert_eq!(balance_of(&token1, router_address), 0.into());
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(109)")]
+fn fill_limit_order_before_limit_reached() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let filler = env.next_user();
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let order_id = create_limit_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        100000.into(),
+        110000.into(),
+        1000.into(),
+        Key::Account(owner),
+        deadline,
+        now,
+    );
+    router.call_contract(
+        filler,
+        "fill_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+}
+
+#[test]
+fn cancel_limit_order_refunds_escrow() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let router_address = Address::Contract(router.package_hash().into());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let balance_before = balance_of(&token1, Address::Account(owner));
+    let order_id = create_limit_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        100000.into(),
+        110000.into(),
+        1000.into(),
+        Key::Account(owner),
+        deadline,
+        now,
+    );
+    assert_eq!(
+        balance_of(&token1, Address::Account(owner)),
+        balance_before - 101000
+    );
+    router.call_contract(
+        owner,
+        "cancel_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    assert_eq!(balance_of(&token1, Address::Account(owner)), balance_before);
+    assert_eq!(balance_of(&token1, router_address), 0.into());
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(110)")]
+fn cancel_limit_order_by_non_owner() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let order_id = create_limit_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        100000.into(),
+        110000.into(),
+        1000.into(),
+        Key::Account(owner),
+        deadline,
+        now,
+    );
+    router.call_contract(
+        env.next_user(),
+        "cancel_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(107)")]
+fn fill_cancelled_limit_order() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    // the limit is already met, only the cancellation can make the fill fail
+    let order_id = create_limit_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        100000.into(),
+        1.into(),
+        1000.into(),
+        Key::Account(owner),
+        deadline,
+        now,
+    );
+    router.call_contract(
+        owner,
+        "cancel_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    router.call_contract(
+        env.next_user(),
+        "fill_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(108)")]
+fn fill_expired_limit_order() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    // the limit is already met, only the expiry can make the fill fail
+    let order_id = create_limit_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        100000.into(),
+        1.into(),
+        1000.into(),
+        Key::Account(owner),
+        Deadline::At(now),
+        now,
+    );
+    router.call_contract(
+        env.next_user(),
+        "fill_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now + 1,
+    );
+}
+
+#[test]
+fn limit_order_cspr_filled() {
+    let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
+    let router_package_hash: ContractPackageHash = router.package_hash().into();
+    let token = Key::Hash(token1.package_hash());
+    let recipient = env.next_user();
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::AddLiquidityCspr {
+            token,
+            amount_token_desired: AMOUNT,
+            amount_cspr_desired: AMOUNT,
+            amount_token_min: U256::from(1000000),
+            amount_cspr_min: U256::from(1000000),
+            to: Key::Account(owner),
+            deadline,
+            pair: Some(Key::Hash(pair.package_hash())),
+        },
+        now,
+    );
+    let amount_in: U256 = 100000.into();
+    let bounty: U256 = 1000.into();
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::CreateLimitOrderCspr {
+            amount_in,
+            amount_out_min: U256::from(1),
+            bounty,
+            path: Path::new(vec![Key::Hash(wcspr.package_hash()), token]).unwrap(),
+            to: Key::Account(recipient),
+            expiry: deadline,
+        },
+        now,
+    );
+    let order_id: U256 = result_key(&env, owner, "create_limit_order_cspr");
+    assert_eq!(
+        balance_of(&wcspr, Address::Contract(router_package_hash)),
+        amount_in + bounty
+    );
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::FillOrder { order_id },
+        now,
+    );
+    let ret: Vec<U256> = result_key(&env, owner, "fill_order");
+    assert_eq!(ret[0], amount_in);
+    assert_eq!(balance_of(&token1, Address::Account(recipient)), ret[1]);
+    assert_eq!(
+        balance_of(&wcspr, Address::Contract(router_package_hash)),
+        0.into()
+    );
+}
+#[allow(clippy::too_many_arguments)]
+fn create_dca_order(
+    env: &TestEnv,
+    owner: AccountHash,
+    router: &TestContract,
+    path: Path,
+    budget: U256,
+    amount_per_interval: U256,
+    interval: u64,
+    fee: U256,
+    now: u64,
+) -> U256 {
+    call_router(
+        env,
+        owner,
+        router,
+        RouterCall::CreateDcaOrder {
+            budget,
+            amount_per_interval,
+            interval,
+            amount_out_min: U256::from(1),
+            fee,
+            path,
+            to: Key::Account(owner),
+        },
+        now,
+    );
+    result_key(env, owner, "create_dca_order")
+}
+
+// one hour in milliseconds
+const DCA_INTERVAL: u64 = 60 * 60 * 1000;
+
+#[test]
+fn dca_order_executed_once_per_interval() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let router_address = Address::Contract(router.package_hash().into());
+    let keeper = env.next_user();
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let amount_per_interval: U256 = 10000.into();
+    let fee: U256 = 100.into();
+    let order_id = create_dca_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        (amount_per_interval + fee) * 3,
+        amount_per_interval,
+        DCA_INTERVAL,
+        fee,
+        now,
+    );
+    assert_eq!(order_id, 0.into());
+    let balance_before = balance_of(&token2, Address::Account(owner));
+    router.call_contract(
+        keeper,
+        "execute_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    assert_gt!(balance_of(&token2, Address::Account(owner)), balance_before);
+    assert_eq!(balance_of(&token1, Address::Account(keeper)), fee);
+    assert_eq!(
+        balance_of(&token1, router_address),
+        (amount_per_interval + fee) * 2
+    );
+    router.call_contract(
+        keeper,
+        "execute_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now + DCA_INTERVAL,
+    );
+    assert_eq!(balance_of(&token1, Address::Account(keeper)), fee * 2);
+    assert_eq!(
+        balance_of(&token1, router_address),
+        amount_per_interval + fee
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(121)")]
+fn execute_dca_order_before_interval() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let order_id = create_dca_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        30300.into(),
+        10000.into(),
+        DCA_INTERVAL,
+        100.into(),
+        now,
+    );
+    router.call_contract(
+        owner,
+        "execute_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    router.call_contract(
+        owner,
+        "execute_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now + DCA_INTERVAL - 1,
+    );
+}
+
+// longest interval the router accepts, one year in milliseconds
+const DCA_MAX_INTERVAL: u64 = 365 * 24 * 60 * 60 * 1000;
+
+#[test]
+#[should_panic(expected = "ApiError::User(125)")]
+fn create_dca_order_with_interval_past_max() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    create_dca_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        30300.into(),
+        10000.into(),
+        DCA_MAX_INTERVAL + 1,
+        100.into(),
+        now,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(119)")]
+fn execute_paused_dca_order() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let order_id = create_dca_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        30300.into(),
+        10000.into(),
+        DCA_INTERVAL,
+        100.into(),
+        now,
+    );
+    router.call_contract(
+        owner,
+        "pause_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    router.call_contract(
+        env.next_user(),
+        "execute_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+}
+
+#[test]
+fn withdraw_dca_order_refunds_remaining_budget() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let router_address = Address::Contract(router.package_hash().into());
+    let keeper = env.next_user();
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let balance_before = balance_of(&token1, Address::Account(owner));
+    let order_id = create_dca_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        30300.into(),
+        10000.into(),
+        DCA_INTERVAL,
+        100.into(),
+        now,
+    );
+    router.call_contract(
+        owner,
+        "pause_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    router.call_contract(
+        owner,
+        "resume_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    router.call_contract(
+        keeper,
+        "execute_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    router.call_contract(
+        owner,
+        "withdraw_dca_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    assert_eq!(
+        balance_of(&token1, Address::Account(owner)),
+        balance_before - 10100
+    );
+    assert_eq!(balance_of(&token1, router_address), 0.into());
+}
+// Liquidity in the token/WCSPR pair, for swaps that pay out cspr.
+fn add_cspr_liquidity(
+    env: &TestEnv,
+    owner: AccountHash,
+    router: &TestContract,
+    token: Key,
+    pair: Key,
+    deadline: Deadline,
+    now: u64,
+) {
+    call_router(
+        env,
+        owner,
+        router,
+        RouterCall::AddLiquidityCspr {
+            token,
+            amount_token_desired: AMOUNT,
+            amount_cspr_desired: AMOUNT,
+            amount_token_min: 1000000.into(),
+            amount_cspr_min: 1000000.into(),
+            to: Key::Account(owner),
+            deadline,
+            pair: Some(pair),
+        },
+        now,
+    );
+}
+
+#[test]
+fn swap_exact_tokens_for_cspr_to_purse() {
+    let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
+    let token = Key::Hash(token1.package_hash());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_cspr_liquidity(
+        &env,
+        owner,
+        &router,
+        token,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let receiver = deploy_cspr_receiver(&env, owner, now);
+    let deposit_purse: Key = receiver.query_named_key("deposit_purse".into());
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::SwapExactTokensForCspr {
+            amount_in: 1000000.into(),
+            amount_out_min: 10000.into(),
+            path: Path::new(vec![token, Key::Hash(wcspr.package_hash())]).unwrap(),
+            to: deposit_purse,
+            deadline,
+        },
+        now,
+    );
+    let ret: Vec<U256> = result_key(&env, owner, "swap_exact_tokens_for_cspr");
+    receiver.call_contract(owner, "record_balance", runtime_args! {}, now);
+    let balance: U512 = receiver.query_named_key("balance".into());
+    assert_eq!(u512_to_u256(balance), ret[1]);
+    // paid into the purse directly, the contract's receive is not called
+    let received: U512 = receiver.query_named_key("received".into());
+    assert_eq!(received, U512::zero());
+    assert_eq!(
+        balance_of(&wcspr, Address::Contract(router.package_hash().into())),
+        0.into()
+    );
+}
+
+#[test]
+fn swap_tokens_for_exact_cspr_to_contract() {
+    let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
+    let token = Key::Hash(token1.package_hash());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_cspr_liquidity(
+        &env,
+        owner,
+        &router,
+        token,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let receiver = deploy_cspr_receiver(&env, owner, now);
+    let amount_out: U256 = 10000.into();
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::SwapTokensForExactCspr {
+            amount_out,
+            amount_in_max: 1000000.into(),
+            path: Path::new(vec![token, Key::Hash(wcspr.package_hash())]).unwrap(),
+            to: Key::Hash(receiver.package_hash()),
+            deadline,
+        },
+        now,
+    );
+    // the cspr arrives through the contract's receive entry point
+    let received: U512 = receiver.query_named_key("received".into());
+    assert_eq!(u512_to_u256(received), amount_out);
+    receiver.call_contract(owner, "record_balance", runtime_args! {}, now);
+    let balance: U512 = receiver.query_named_key("balance".into());
+    assert_eq!(balance, received);
+    assert_eq!(
+        balance_of(&wcspr, Address::Contract(router.package_hash().into())),
+        0.into()
+    );
+}
+
+// Main purse of `account` with add access, which the cspr receiver fixture publishes to the
+// account installing it.
+fn main_purse(env: &TestEnv, account: AccountHash, now: u64) -> Key {
+    deploy_cspr_receiver(env, account, now);
+    env.query_account_named_key(account, &["cspr_receiver_main_purse".into()])
+}
+
+#[test]
+fn swap_exact_tokens_for_cspr_to_account_purse() {
+    let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
+    let token = Key::Hash(token1.package_hash());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_cspr_liquidity(
+        &env,
+        owner,
+        &router,
+        token,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let recipient = env.next_user();
+    let purse = main_purse(&env, recipient, now);
+    let balance_before: U512 = env.get_account_cspr_balance(recipient);
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::SwapExactTokensForCspr {
+            amount_in: 1000000.into(),
+            amount_out_min: 10000.into(),
+            path: Path::new(vec![token, Key::Hash(wcspr.package_hash())]).unwrap(),
+            to: purse,
+            deadline,
+        },
+        now,
+    );
+    let ret: Vec<U256> = result_key(&env, owner, "swap_exact_tokens_for_cspr");
+    assert_eq!(
+        env.get_account_cspr_balance(recipient),
+        balance_before + u256_to_u512(ret[1])
+    );
+}
+
+#[test]
+fn swap_exact_tokens_for_tokens_to_purse() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::from_now(30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let recipient = env.next_user();
+    let purse = main_purse(&env, recipient, now);
+    let balance_before: U512 = env.get_account_cspr_balance(recipient);
+    // purses can only receive cspr
+    let error: String = panic::catch_unwind(AssertUnwindSafe(|| {
+        call_router(
+            &env,
+            owner,
+            &router,
+            RouterCall::SwapExactTokensForTokens {
+                amount_in: U256::from(10000),
+                amount_out_min: U256::from(1),
+                path: Path::new(vec![token_a, token_b]).unwrap(),
+                to: purse,
+                deadline,
+            },
+            now,
+        )
+    }))
+    .map_err(|error| error.downcast_ref::<String>().cloned().unwrap_or_default())
+    .expect_err("paying tokens to a purse reverts");
+    // InvalidRecipient
+    assert!(error.contains("ApiError::User(126)"), "{}", error);
+    assert_eq!(env.get_account_cspr_balance(recipient), balance_before);
+}
+
+// `deadline` is given the block time the pair was set up at
+fn swap_before_deadline(deadline: fn(u64) -> U256, block_time_offset: u64) {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        runtime_args! {
+            "entrypoint" => "swap_exact_tokens_for_tokens",
+            "package_hash" => Key::Hash(router.package_hash()),
+            "amount_in" => U256::from(10000),
+            "amount_out_min" => U256::from(1),
+            "path" => vec![token_a.to_formatted_string(), token_b.to_formatted_string()],
+            "to" => Key::Account(owner),
+            "deadline" => deadline(now),
+        },
+        now + block_time_offset,
+    );
+    let ret: Vec<U256> = result_key(&env, owner, "swap_exact_tokens_for_tokens");
+    assert_eq!(ret[0], 10000.into());
+}
+
+#[test]
+fn swap_at_deadline() {
+    swap_before_deadline(U256::from, 0);
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(83)")]
+fn swap_one_millisecond_after_deadline() {
+    swap_before_deadline(U256::from, 1);
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(83)")]
+fn swap_with_deadline_over_u64() {
+    swap_before_deadline(|_| U256::from(u64::MAX) + 1, 0);
+}
+
+// Creates a limit order at `created_at` that expires `valid_for` after the block time it runs at.
+fn create_limit_order_valid_for(
+    env: &TestEnv,
+    owner: AccountHash,
+    router: &TestContract,
+    path: Vec<String>,
+    valid_for: u64,
+    created_at: u64,
+) -> U256 {
+    call(
+        env,
+        owner,
+        SESSION_CODE_ROUTER,
+        runtime_args! {
+            "entrypoint" => "create_limit_order",
+            "package_hash" => Key::Hash(router.package_hash()),
+            "amount_in" => U256::from(100000),
+            "amount_out_min" => U256::from(1),
+            "bounty" => U256::from(1000),
+            "path" => path,
+            "to" => Key::Account(owner),
+            "valid_for" => valid_for,
+        },
+        created_at,
+    );
+    result_key(env, owner, "create_limit_order")
+}
+
+#[test]
+fn limit_order_valid_for_from_block_time() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    let path: Vec<String> = vec![token_a.to_formatted_string(), token_b.to_formatted_string()];
+    // runs a minute after it could have been signed, the validity starts when it runs
+    let created_at = now + 60000;
+    let order_id = create_limit_order_valid_for(&env, owner, &router, path, 60000, created_at);
+    // filled exactly when the validity window ends
+    router.call_contract(
+        env.next_user(),
+        "fill_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        created_at + 60000,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(108)")]
+fn fill_limit_order_after_valid_for() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    let path: Vec<String> = vec![token_a.to_formatted_string(), token_b.to_formatted_string()];
+    let order_id = create_limit_order_valid_for(&env, owner, &router, path, 60000, now);
+    router.call_contract(
+        env.next_user(),
+        "fill_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now + 60001,
+    );
+}
+
+#[test]
+fn check_deadline_reasons() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    let reason = |deadline: U256| -> u8 {
+        call_router(
+            &env,
+            owner,
+            &router,
+            RouterCall::CheckDeadline { deadline },
+            now,
+        );
+        result_key(&env, owner, "check_deadline")
+    };
+    assert_eq!(reason(now.into()), 0);
+    assert_eq!(reason(U256::from(now) + 1), 0);
+    assert_eq!(reason(U256::from(now) - 1), 1);
+    assert_eq!(reason(U256::from(u64::MAX) + 1), 2);
+    // a deadline given in seconds has simply expired
+    assert_eq!(reason(U256::from(now / 1000 + 30 * 60)), 1);
+}
+
+#[test]
+fn decode_error_codes() {
+    use tests_common::errors::{Errors, Side};
+    assert_eq!(
+        Errors::from_code(Errors::PairNotFound.code()),
+        Some(Errors::PairNotFound)
+    );
+    // numbered duplicates of older releases
+    assert_eq!(Errors::from_code(66), Some(Errors::NotOwner));
+    assert_eq!(
+        Errors::from_code(78),
+        Some(Errors::SlippageExceeded { side: Side::Output })
+    );
+    assert_eq!(
+        Errors::from_code(80),
+        Some(Errors::SlippageExceeded { side: Side::Input })
+    );
+    assert_eq!(Errors::from_code(92), Some(Errors::TimedOut));
+    assert_eq!(Errors::from_code(105), Some(Errors::InvalidPath));
+    assert_eq!(Errors::from_code(1000), None);
+    assert_eq!(
+        Errors::from_api_error(73),
+        "path must start with wcspr (73)"
+    );
+    assert_eq!(Errors::from_api_error(1000), "unknown error code 1000");
+}
+
+#[test]
+fn swap_through_cached_pair_costs_less() {
+    let (env, owner, router, _, pair, token1, token2, _, wcspr, factory, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::At(now + 30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    // routers installed after the pair was created, neither has resolved it yet
+    let library: ContractPackageHash = router.query_named_key("library_hash".into());
+    let install = |deploy_router: fn(&TestEnv, AccountHash, Key, Key, Key, u64) -> TestContract| {
+        let router = deploy_router(
+            &env,
+            env.next_user(),
+            Key::Hash(factory.package_hash()),
+            Key::Hash(wcspr.package_hash()),
+            Key::Hash(library.value()),
+            now,
+        );
+        token1.call_contract(
+            owner,
+            "approve",
+            runtime_args! {
+                "spender" => Address::Contract(router.package_hash().into()),
+                "amount" => AMOUNT
+            },
+            now,
+        );
+        router
+    };
+    let fresh_router = install(deploy_router);
+    let v1_router = install(deploy_router_v1);
+    let swap = |router: &TestContract| {
+        gas_used(&env, owner, || {
+            call_router(
+                &env,
+                owner,
+                router,
+                RouterCall::SwapExactTokensForTokens {
+                    amount_in: U256::from(10000),
+                    amount_out_min: U256::from(1),
+                    path: Path::new(vec![token_a, token_b]).unwrap(),
+                    to: Key::Account(owner),
+                    deadline,
+                },
+                now,
+            )
+        })
+    };
+    // the first swap resolves the pair through the factory and caches it
+    let uncached: U512 = swap(&fresh_router);
+    let cached: U512 = swap(&fresh_router);
+    assert_lt!(cached, uncached);
+    // v1 asked the library contract for the pair on every swap
+    let cross_contract: U512 = swap(&v1_router);
+    assert_lt!(cached, cross_contract);
+}
+
+#[test]
+fn linked_math_matches_library_contract() {
+    let (env, owner, router, _, pair, token1, token2, _, wcspr, factory, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::At(now + 30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let library: ContractPackageHash = router.query_named_key("library_hash".into());
+    let legacy_router = deploy_router_using_library_contract(
+        &env,
+        owner,
+        Key::Hash(factory.package_hash()),
+        Key::Hash(wcspr.package_hash()),
+        Key::Hash(library.value()),
+        now,
+    );
+    let path = Path::new(vec![token_a, token_b]).unwrap();
+    let outputs = |router: &TestContract| -> (U256, Vec<U256>, Vec<U256>) {
+        call_router(
+            &env,
+            owner,
+            router,
+            RouterCall::Quote {
+                amount_a: U256::from(10000),
+                reserve_a: AMOUNT,
+                reserve_b: AMOUNT * 2,
+            },
+            now,
+        );
+        call_router(
+            &env,
+            owner,
+            router,
+            RouterCall::GetAmountsOut {
+                amount_in: U256::from(10000),
+                path: path.clone(),
+            },
+            now,
+        );
+        call_router(
+            &env,
+            owner,
+            router,
+            RouterCall::GetAmountsIn {
+                amount_out: U256::from(10000),
+                path: path.clone(),
+            },
+            now,
+        );
+        (
+            result_key(&env, owner, "quote"),
+            result_key(&env, owner, "get_amounts_out"),
+            result_key(&env, owner, "get_amounts_in"),
+        )
+    };
+    assert_eq!(outputs(&router), outputs(&legacy_router));
+}
+
+fn queue_operation(
+    env: &TestEnv,
+    owner: AccountHash,
+    router: &TestContract,
+    action: &str,
+    target: Key,
+    eta: u64,
+    now: u64,
+) -> String {
+    call_router(
+        env,
+        owner,
+        router,
+        RouterCall::QueueOperation {
+            action: action.to_string(),
+            target,
+            value: 0u64,
+            eta,
+        },
+        now,
+    );
+    result_key(env, owner, "queue_operation")
+}
+
+#[test]
+fn set_library_hash_after_timelock() {
+    let (env, owner, router, _, _, _, _, token3, _, _, now) = deploy();
+    let library: ContractPackageHash = router.query_named_key("library_hash".into());
+    let new_library = Key::Hash(token3.package_hash());
+    let eta: u64 = now + TIMELOCK_DELAY;
+    let operation_hash = queue_operation(
+        &env,
+        owner,
+        &router,
+        "set_library_hash",
+        new_library,
+        eta,
+        now,
+    );
+    let queued: ContractPackageHash = router.query_named_key("library_hash".into());
+    assert_eq!(queued, library);
+    router.call_contract(
+        owner,
+        "execute_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash,
+        },
+        eta,
+    );
+    let applied: ContractPackageHash = router.query_named_key("library_hash".into());
+    assert_eq!(Key::Hash(applied.value()), new_library);
+}
+
+fn set_factory(router: &TestContract, sender: AccountHash, factory: Key, eta: u64, now: u64) {
+    router.call_contract(
+        sender,
+        "set_factory",
+        runtime_args! {
+            "factory" => factory,
+            "eta" => eta,
+        },
+        now,
+    );
+}
+
+#[test]
+fn set_factory_queued_and_applied_by_the_setter() {
+    let (_, owner, router, _, _, _, _, token3, _, factory, now) = deploy();
+    let new_factory = Key::Hash(token3.package_hash());
+    let eta: u64 = now + TIMELOCK_DELAY;
+    set_factory(&router, owner, new_factory, eta, now);
+    let queued: ContractPackageHash = router.query_named_key("factory".into());
+    assert_eq!(queued.value(), factory.package_hash());
+    set_factory(&router, owner, new_factory, eta, eta);
+    let applied: ContractPackageHash = router.query_named_key("factory".into());
+    assert_eq!(Key::Hash(applied.value()), new_factory);
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(127)")]
+fn set_factory_before_eta() {
+    let (_, owner, router, _, _, _, _, token3, _, _, now) = deploy();
+    let eta: u64 = now + TIMELOCK_DELAY;
+    set_factory(&router, owner, Key::Hash(token3.package_hash()), eta, now);
+    set_factory(
+        &router,
+        owner,
+        Key::Hash(token3.package_hash()),
+        eta,
+        eta - 1,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(64)")]
+fn set_factory_by_non_owner() {
+    let (env, _, router, _, _, _, _, token3, _, _, now) = deploy();
+    let eta: u64 = now + TIMELOCK_DELAY;
+    set_factory(
+        &router,
+        env.next_user(),
+        Key::Hash(token3.package_hash()),
+        eta,
+        now,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(129)")]
+fn set_factory_after_cancel() {
+    let (_, owner, router, _, _, _, _, token3, _, _, now) = deploy();
+    let new_factory = Key::Hash(token3.package_hash());
+    let eta: u64 = now + TIMELOCK_DELAY;
+    set_factory(&router, owner, new_factory, eta, now);
+    router.call_contract(
+        owner,
+        "cancel_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash("set_factory", new_factory, 0, eta),
+        },
+        now,
+    );
+    set_factory(&router, owner, new_factory, eta, eta);
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(127)")]
+fn execute_operation_before_eta() {
+    let (env, owner, router, _, _, _, _, token3, _, _, now) = deploy();
+    let eta: u64 = now + TIMELOCK_DELAY;
+    let operation_hash = queue_operation(
+        &env,
+        owner,
+        &router,
+        "set_factory",
+        Key::Hash(token3.package_hash()),
+        eta,
+        now,
+    );
+    router.call_contract(
+        owner,
+        "execute_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash,
+        },
+        eta - 1,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(130)")]
+fn queue_operation_before_delay() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    queue_operation(
+        &env,
+        owner,
+        &router,
+        "add_to_whitelist",
+        Key::Account(owner),
+        now + TIMELOCK_DELAY - 1,
+        now,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(129)")]
+fn execute_cancelled_operation() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    let eta: u64 = now + TIMELOCK_DELAY;
+    let operation_hash = queue_operation(
+        &env,
+        owner,
+        &router,
+        "add_to_whitelist",
+        Key::Account(owner),
+        eta,
+        now,
+    );
+    router.call_contract(
+        owner,
+        "cancel_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash.clone(),
+        },
+        now,
+    );
+    router.call_contract(
+        owner,
+        "execute_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash,
+        },
+        eta,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(64)")]
+fn queue_operation_by_non_owner() {
+    let (env, _, router, _, _, _, _, token3, _, _, now) = deploy();
+    let user = env.next_user();
+    queue_operation(
+        &env,
+        user,
+        &router,
+        "set_wcspr",
+        Key::Hash(token3.package_hash()),
+        now + TIMELOCK_DELAY,
+        now,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(141)")]
+fn queue_operation_with_eta_past_block_time_range() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    queue_operation(
+        &env,
+        owner,
+        &router,
+        "add_to_whitelist",
+        Key::Account(owner),
+        u64::MAX - 1,
+        now,
+    );
+}
+
+#[test]
+fn get_operation_by_anyone() {
+    let (env, owner, router, _, _, _, _, _, _, factory, now) = deploy();
+    let user = env.next_user();
+    let eta: u64 = now + TIMELOCK_DELAY;
+    let operation_hash = queue_operation(
+        &env,
+        owner,
+        &router,
+        "set_factory",
+        Key::Hash(factory.package_hash()),
+        eta,
+        now,
+    );
+    let get_operation = |now: u64| -> SessionResult {
+        call_router(
+            &env,
+            user,
+            &router,
+            RouterCall::GetOperation {
+                operation_hash: operation_hash.clone(),
+            },
+            now,
+        );
+        session_result(&env, user)
+    };
+    let queued: SessionResult = get_operation(now);
+    assert_eq!(queued.destination, "get_operation");
+    assert_eq!(queued.operation_hash, Some(operation_hash.clone()));
+    assert_eq!(queued.value("eta"), eta.into());
+    assert_eq!(queued.value("status"), 0.into());
+    router.call_contract(
+        owner,
+        "execute_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash.clone(),
+        },
+        eta,
+    );
+    assert_eq!(get_operation(eta).value("status"), 1.into());
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(128)")]
+fn get_unknown_operation() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::GetOperation {
+            operation_hash: operation_hash("unpause", Key::Account(owner), 0, now),
+        },
+        now,
+    );
+}
+
+// Opens a cspr limit order, whose escrow is held as wcspr, and queues a change of wcspr.
+fn queue_set_wcspr_with_cspr_order(
+    env: &TestEnv,
+    owner: AccountHash,
+    router: &TestContract,
+    pair: &TestContract,
+    token: &TestContract,
+    wcspr: &TestContract,
+    now: u64,
+) -> (U256, String, u64) {
+    let token = Key::Hash(token.package_hash());
+    let deadline = Deadline::At(now + 30 * 60 * 1000);
+    add_cspr_liquidity(
+        env,
+        owner,
+        router,
+        token,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    call_router(
+        env,
+        owner,
+        router,
+        RouterCall::CreateLimitOrderCspr {
+            amount_in: 100000.into(),
+            amount_out_min: U256::from(1),
+            bounty: 1000.into(),
+            path: Path::new(vec![Key::Hash(wcspr.package_hash()), token]).unwrap(),
+            to: Key::Account(owner),
+            expiry: deadline,
+        },
+        now,
+    );
+    let order_id: U256 = result_key(env, owner, "create_limit_order_cspr");
+    let eta: u64 = now + TIMELOCK_DELAY;
+    let operation_hash = queue_operation(env, owner, router, "set_wcspr", token, eta, now);
+    (order_id, operation_hash, eta)
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(140)")]
+fn set_wcspr_while_cspr_order_open() {
+    let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
+    let (_, operation_hash, eta) =
+        queue_set_wcspr_with_cspr_order(&env, owner, &router, &pair, &token1, &wcspr, now);
+    router.call_contract(
+        owner,
+        "execute_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash,
+        },
+        eta,
+    );
+}
+
+#[test]
+fn set_wcspr_after_cspr_order_cancelled() {
+    let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
+    let (order_id, operation_hash, eta) =
+        queue_set_wcspr_with_cspr_order(&env, owner, &router, &pair, &token1, &wcspr, now);
+    // the refund is unwrapped through the wcspr the order was escrowed in
+    router.call_contract(
+        owner,
+        "cancel_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    router.call_contract(
+        owner,
+        "execute_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash,
+        },
+        eta,
+    );
+    let applied: ContractPackageHash = router.query_named_key("wcspr".into());
+    assert_eq!(applied.value(), token1.package_hash());
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(133)")]
+fn swap_while_paused() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::At(now + 30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    // pausing bypasses the timelock
+    router.call_contract(owner, "pause", runtime_args! {}, now);
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::SwapExactTokensForTokens {
+            amount_in: U256::from(10000),
+            amount_out_min: U256::from(1),
+            path: Path::new(vec![token_a, token_b]).unwrap(),
+            to: Key::Account(owner),
+            deadline,
+        },
+        now,
+    );
+}
+
+#[test]
+fn limit_orders_while_paused() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let router_address = Address::Contract(router.package_hash().into());
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::At(now + 30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let balance_before = balance_of(&token1, Address::Account(owner));
+    let order_id = create_limit_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        100000.into(),
+        110000.into(),
+        1000.into(),
+        Key::Account(owner),
+        deadline,
+        now,
+    );
+    router.call_contract(owner, "pause", runtime_args! {}, now);
+    let error: String = panic::catch_unwind(AssertUnwindSafe(|| {
+        create_limit_order(
+            &env,
+            owner,
+            &router,
+            Path::new(vec![token_a, token_b]).unwrap(),
+            100000.into(),
+            110000.into(),
+            1000.into(),
+            Key::Account(owner),
+            deadline,
+            now,
+        )
+    }))
+    .map_err(|error| error.downcast_ref::<String>().cloned().unwrap_or_default())
+    .expect_err("creating an order while paused should revert");
+    // Paused
+    assert!(error.contains("ApiError::User(133)"), "{}", error);
+    // cancelling keeps working so the escrow can leave the paused router
+    router.call_contract(
+        owner,
+        "cancel_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    assert_eq!(balance_of(&token1, Address::Account(owner)), balance_before);
+    assert_eq!(balance_of(&token1, router_address), 0.into());
+}
+
+#[test]
+fn rescue_tokens_sent_by_mistake() {
+    let (env, owner, router, _, _, _, _, token3, _, _, now) = deploy();
+    let router_address = Address::Contract(router.package_hash().into());
+    let recipient = env.next_user();
+    let amount: U256 = 5000.into();
+    token3.call_contract(
+        owner,
+        "transfer",
+        runtime_args! {
+            "recipient" => router_address,
+            "amount" => amount,
+        },
+        now,
+    );
+    router.call_contract(
+        owner,
+        "rescue_tokens",
+        runtime_args! {
+            "token" => Key::Hash(token3.package_hash()),
+            "amount" => amount,
+            "to" => Key::Account(recipient),
+        },
+        now,
+    );
+    assert_eq!(balance_of(&token3, router_address), 0.into());
+    assert_eq!(balance_of(&token3, Address::Account(recipient)), amount);
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(134)")]
+fn rescue_limit_order_escrow() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let deadline = Deadline::At(now + 30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    create_limit_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        100000.into(),
+        110000.into(),
+        1000.into(),
+        Key::Account(owner),
+        deadline,
+        now,
+    );
+    router.call_contract(
+        owner,
+        "rescue_tokens",
+        runtime_args! {
+            "token" => token_a,
+            "amount" => U256::from(1),
+            "to" => Key::Account(owner),
+        },
+        now,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(64)")]
+fn rescue_tokens_by_non_owner() {
+    let (env, owner, router, _, _, _, _, token3, _, _, now) = deploy();
+    let user = env.next_user();
+    token3.call_contract(
+        owner,
+        "transfer",
+        runtime_args! {
+            "recipient" => Address::Contract(router.package_hash().into()),
+            "amount" => U256::from(5000),
+        },
+        now,
+    );
+    router.call_contract(
+        user,
+        "rescue_tokens",
+        runtime_args! {
+            "token" => Key::Hash(token3.package_hash()),
+            "amount" => U256::from(5000),
+            "to" => Key::Account(user),
+        },
+        now,
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(135)")]
+fn receive_from_non_wcspr_caller() {
+    let (env, _, router, _, _, _, _, _, _, _, now) = deploy();
+    let caller = env.next_user();
+    let purse: URef = main_purse(&env, caller, now).into_uref().unwrap();
+    router.call_contract(
+        caller,
+        "receive",
+        runtime_args! {
+            "amount" => U512::from(1000),
+            "purse" => purse,
+        },
+        now,
+    );
+}
+
+// Storage version the router reports, read through the session code.
+fn storage_version(env: &TestEnv, sender: AccountHash, router: &TestContract, now: u64) -> U256 {
+    call_router(env, sender, router, RouterCall::GetStorageVersion, now);
+    session_result(env, sender).value("storage_version")
+}
+
+#[test]
+fn upgrade_and_migrate_preserves_state() {
+    let (env, owner, router, _, pair, token1, token2, _, wcspr, factory, now) = deploy_v1();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let router_address = Address::Contract(router.package_hash().into());
+    let deadline = Deadline::At(now + 30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let liquidity: U256 = balance_of(&pair, Address::Account(owner));
+    let first_version: [u8; 32] = router.contract_hash();
+    let upgraded = upgrade_router(&env, owner, false, now);
+    assert_eq!(upgraded.package_hash(), router.package_hash());
+    assert_ne!(upgraded.contract_hash(), first_version);
+    // v1 did not record its versions, the one it was installed with is kept enabled
+    let versions: Vec<ContractHash> = upgraded.query_named_key("enabled_versions".into());
+    assert_eq!(
+        versions,
+        vec![first_version.into(), upgraded.contract_hash().into()]
+    );
+    assert_eq!(storage_version(&env, owner, &upgraded, now), 1.into());
+    upgraded.call_contract(owner, "migrate", runtime_args! {}, now);
+    assert_eq!(storage_version(&env, owner, &upgraded, now), 2.into());
+    // state written by v1 is kept
+    let router_owner: Key = upgraded.query_named_key("owner".into());
+    assert_eq!(router_owner, Key::Account(owner));
+    let router_factory: ContractPackageHash = upgraded.query_named_key("factory".into());
+    assert_eq!(router_factory.value(), factory.package_hash());
+    let router_wcspr: ContractPackageHash = upgraded.query_named_key("wcspr".into());
+    assert_eq!(router_wcspr.value(), wcspr.package_hash());
+    assert_eq!(balance_of(&pair, Address::Account(owner)), liquidity);
+    // the owner whitelisted by v1 can still add liquidity
+    call_router(
+        &env,
+        owner,
+        &upgraded,
+        RouterCall::AddLiquidity {
+            token_a,
+            token_b,
+            amount_a_desired: 1000000.into(),
+            amount_b_desired: 1000000.into(),
+            amount_a_min: 0.into(),
+            amount_b_min: 0.into(),
+            to: Key::Account(owner),
+            deadline,
+            pair: Some(Key::Hash(pair.package_hash())),
+        },
+        now,
+    );
+    assert_gt!(balance_of(&pair, Address::Account(owner)), liquidity);
+    // swaps resolve their pairs through the new pair cache
+    call_router(
+        &env,
+        owner,
+        &upgraded,
+        RouterCall::SwapExactTokensForTokens {
+            amount_in: 10000.into(),
+            amount_out_min: 1.into(),
+            path: Path::new(vec![token_a, token_b]).unwrap(),
+            to: Key::Account(owner),
+            deadline,
+        },
+        now,
+    );
+    let ret: Vec<U256> = result_key(&env, owner, "swap_exact_tokens_for_tokens");
+    assert_gt!(ret[1], 0.into());
+    // limit orders and their escrow
+    let order_id = create_limit_order(
+        &env,
+        owner,
+        &upgraded,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        100000.into(),
+        110000.into(),
+        1000.into(),
+        Key::Account(owner),
+        deadline,
+        now,
+    );
+    assert_eq!(balance_of(&token1, router_address), 101000.into());
+    let balance: U256 = balance_of(&token1, Address::Account(owner));
+    upgraded.call_contract(
+        owner,
+        "cancel_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    assert_eq!(
+        balance_of(&token1, Address::Account(owner)),
+        balance + 101000
+    );
+    assert_eq!(balance_of(&token1, router_address), 0.into());
+    // dca orders
+    let dca_order_id = create_dca_order(
+        &env,
+        owner,
+        &upgraded,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        20000.into(),
+        10000.into(),
+        DCA_INTERVAL,
+        0.into(),
+        now,
+    );
+    let balance: U256 = balance_of(&token2, Address::Account(owner));
+    upgraded.call_contract(
+        env.next_user(),
+        "execute_dca_order",
+        runtime_args! {
+            "order_id" => dca_order_id
+        },
+        now,
+    );
+    assert_gt!(balance_of(&token2, Address::Account(owner)), balance);
+    // timelock operations
+    let eta: u64 = now + TIMELOCK_DELAY;
+    let operation_hash = queue_operation(
+        &env,
+        owner,
+        &upgraded,
+        "remove_from_whitelist",
+        Key::Account(owner),
+        eta,
+        now,
+    );
+    call_router(
+        &env,
+        owner,
+        &upgraded,
+        RouterCall::GetOperation { operation_hash },
+        now,
+    );
+    assert_eq!(session_result(&env, owner).value("eta"), eta.into());
+}
+
+#[test]
+fn upgrade_keeps_escrowed_orders() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let router_address = Address::Contract(router.package_hash().into());
+    let deadline = Deadline::At(now + 30 * 60 * 1000);
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        deadline,
+        now,
+    );
+    let order_id = create_limit_order(
+        &env,
+        owner,
+        &router,
+        Path::new(vec![token_a, token_b]).unwrap(),
+        100000.into(),
+        110000.into(),
+        1000.into(),
+        Key::Account(owner),
+        deadline,
+        now,
+    );
+    let escrow: U256 = balance_of(&token1, router_address);
+    let first_version: [u8; 32] = router.contract_hash();
+    let upgraded = upgrade_router(&env, owner, false, now);
+    assert_eq!(upgraded.package_hash(), router.package_hash());
+    assert_ne!(upgraded.contract_hash(), first_version);
+    upgraded.call_contract(owner, "migrate", runtime_args! {}, now);
+    let version: u32 = upgraded.query_named_key("storage_version".into());
+    assert_eq!(version, 2);
+    let router_owner: Key = upgraded.query_named_key("owner".into());
+    assert_eq!(router_owner, Key::Account(owner));
+    // the order escrowed by the first version is still refunded by the new one
+    let balance: U256 = balance_of(&token1, Address::Account(owner));
+    upgraded.call_contract(
+        owner,
+        "cancel_order",
+        runtime_args! {
+            "order_id" => order_id
+        },
+        now,
+    );
+    assert_eq!(
+        balance_of(&token1, Address::Account(owner)),
+        balance + escrow
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(64)")]
+fn migrate_by_non_owner() {
+    let (env, owner, _, _, _, _, _, _, _, _, now) = deploy();
+    let upgraded = upgrade_router(&env, owner, false, now);
+    upgraded.call_contract(env.next_user(), "migrate", runtime_args! {}, now);
+}
+
+fn call_version(env: &TestEnv, sender: AccountHash, contract_hash: [u8; 32], now: u64) {
+    env.run(
+        sender,
+        DeploySource::ByContractHash {
+            hash: contract_hash.into(),
+            method: "get_storage_version".into(),
+        },
+        runtime_args! {},
+        now,
+    );
+}
+
+#[test]
+fn upgrade_keeps_previous_versions_enabled() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    let first_version: [u8; 32] = router.contract_hash();
+    let upgraded = upgrade_router(&env, owner, false, now);
+    let versions: Vec<ContractHash> = upgraded.query_named_key("enabled_versions".into());
+    assert_eq!(
+        versions,
+        vec![first_version.into(), upgraded.contract_hash().into()]
+    );
+    call_version(&env, owner, first_version, now);
+}
+
+#[test]
+fn call_disabled_version() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    let first_version: [u8; 32] = router.contract_hash();
+    let upgraded = upgrade_router(&env, owner, true, now);
+    let versions: Vec<ContractHash> = upgraded.query_named_key("enabled_versions".into());
+    assert_eq!(versions, vec![upgraded.contract_hash().into()]);
+    call_version(&env, owner, upgraded.contract_hash(), now);
+    let error: String = panic::catch_unwind(AssertUnwindSafe(|| {
+        call_version(&env, owner, first_version, now)
+    }))
+    .map_err(|error| error.downcast_ref::<String>().cloned().unwrap_or_default())
+    .expect_err("calling a disabled version should fail");
+    assert!(error.contains("DisabledContract"), "{}", error);
+}
+
+// Hands the router to new_owner through the timelock, returns the block time it happened at.
+fn transfer_ownership(
+    env: &TestEnv,
+    owner: AccountHash,
+    router: &TestContract,
+    new_owner: AccountHash,
+    now: u64,
+) -> u64 {
+    let eta: u64 = now + TIMELOCK_DELAY;
+    let operation_hash = queue_operation(
+        env,
+        owner,
+        router,
+        "change_owner",
+        Key::Account(new_owner),
+        eta,
+        now,
+    );
+    router.call_contract(
+        owner,
+        "execute_operation",
+        runtime_args! {
+            "operation_hash" => operation_hash,
+        },
+        eta,
+    );
+    eta
+}
+
+#[test]
+fn upgrade_by_non_owner_keeps_previous_versions_enabled() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    let eta = transfer_ownership(&env, owner, &router, env.next_user(), now);
+    // the installing account still holds the package access token
+    let first_version: [u8; 32] = router.contract_hash();
+    let upgraded = upgrade_router(&env, owner, false, eta);
+    let versions: Vec<ContractHash> = upgraded.query_named_key("enabled_versions".into());
+    assert_eq!(
+        versions,
+        vec![first_version.into(), upgraded.contract_hash().into()]
+    );
+    let user = env.next_user();
+    upgraded.call_contract(user, "get_enabled_versions", runtime_args! {}, eta);
+}
+
+#[test]
+fn upgrade_by_non_owner_disables_previous_versions() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    let eta = transfer_ownership(&env, owner, &router, env.next_user(), now);
+    let upgraded = upgrade_router(&env, owner, true, eta);
+    let versions: Vec<ContractHash> = upgraded.query_named_key("enabled_versions".into());
+    assert_eq!(versions, vec![upgraded.contract_hash().into()]);
+}
+
+#[test]
+#[should_panic(expected = "InvalidContext")]
+fn record_versions_by_other_account() {
+    let (env, _, router, _, _, _, _, _, _, _, now) = deploy();
+    router.call_contract(
+        env.next_user(),
+        "record_versions",
+        runtime_args! {
+            "disable_previous_versions" => false,
+        },
+        now,
+    );
+}
+
+#[test]
+#[should_panic(expected = "InvalidContext")]
+fn record_versions_by_new_owner() {
+    let (env, owner, router, _, _, _, _, _, _, _, now) = deploy();
+    let new_owner = env.next_user();
+    let eta = transfer_ownership(&env, owner, &router, new_owner, now);
+    router.call_contract(
+        new_owner,
+        "record_versions",
+        runtime_args! {
+            "disable_previous_versions" => true,
+        },
+        eta,
+    );
+}
+
+#[test]
+fn deploy_and_register_deployment() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let now = now();
+    let factory = deploy_factory(&env, owner, Key::Account(owner), now);
+    let wcspr = deploy_wcspr(
+        &env,
+        "WCSPR-1",
+        owner,
+        "Wrapped cspr".into(),
+        "WCSPR".into(),
+        9,
+        0.into(),
+        now,
+    );
+    let (library, router) = deploy_and_register(
+        &env,
+        owner,
+        Key::Hash(factory.package_hash()),
+        Key::Hash(wcspr.package_hash()),
+        now,
+    );
+    let named_hash = |name: &str| -> Key { env.query_account_named_key(owner, &[name.into()]) };
+    assert_eq!(
+        named_hash("uniswap_v2_factory_package_hash"),
+        Key::Hash(factory.package_hash())
+    );
+    assert_eq!(
+        named_hash("uniswap_v2_wcspr_package_hash"),
+        Key::Hash(wcspr.package_hash())
+    );
+    assert_eq!(
+        named_hash("uniswap_v2_library_package_hash"),
+        Key::Hash(library.package_hash())
+    );
+    assert_eq!(
+        named_hash("uniswap_v2_library_contract_hash"),
+        Key::Hash(library.contract_hash())
+    );
+    assert_eq!(
+        named_hash("uniswap_v2_router_package_hash"),
+        Key::Hash(router.package_hash())
+    );
+    assert_eq!(
+        named_hash("uniswap_v2_router_contract_hash"),
+        Key::Hash(router.contract_hash())
+    );
+}
+
+#[test]
+#[should_panic(expected = "ApiError::User(138)")]
+fn register_deployment_with_other_wcspr() {
+    let env = TestEnv::new();
+    let owner = env.next_user();
+    let now = now();
+    let factory = deploy_factory(&env, owner, Key::Account(owner), now);
+    let wcspr = deploy_wcspr(
+        &env,
+        "WCSPR-1",
+        owner,
+        "Wrapped cspr".into(),
+        "WCSPR".into(),
+        9,
+        0.into(),
+        now,
+    );
+    let library = deploy_library(
+        &env,
+        owner,
+        Key::Hash(factory.package_hash()),
+        ORACLE_WINDOW_SIZE,
+        ORACLE_GRANULARITY,
+        now,
+    );
+    deploy_router(
+        &env,
+        owner,
+        Key::Hash(factory.package_hash()),
+        Key::Hash(wcspr.package_hash()),
+        Key::Hash(library.package_hash()),
+        now,
+    );
+    call(
+        &env,
+        owner,
+        REGISTER_DEPLOYMENT,
+        runtime_args! {
+            "deployment_name" => "uniswap_v2",
+            "library_name" => "library",
+            "router_name" => "Uniswap Router",
+            "factory" => Key::Hash(factory.package_hash()),
+            "wcspr" => Key::Hash(library.package_hash()),
+        },
+        now,
+    );
+}
+#[test]
+fn approve_and_remove_liquidity() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    let (_, _, liquidity): (U256, U256, U256) = result_key(&env, owner, "add_liquidity");
+    // the pair token was never approved, the session approves exactly `liquidity`
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        runtime_args! {
+            "entrypoint" => "approve_and_remove_liquidity",
+            "package_hash" => Key::Hash(router.package_hash()),
+            "token_a" => token_a,
+            "token_b" => token_b,
+            "liquidity" => liquidity,
+            "amount_a_min" => U256::from(1),
+            "amount_b_min" => U256::from(1),
+            "to" => Key::Account(owner),
+            "deadline" => U256::from(now),
+            "pair" => Key::Hash(pair.package_hash()),
+        },
+        now,
+    );
+    let (amount_a, amount_b): (U256, U256) = result_key(&env, owner, "remove_liquidity");
+    assert_gt!(amount_a, 0.into());
+    assert_gt!(amount_b, 0.into());
+    assert_eq!(balance_of(&pair, Address::Account(owner)), 0.into());
+}
+
+#[test]
+fn approve_and_swap_tokens_for_exact_tokens() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    let balance_before: U256 = balance_of(&token1, Address::Account(owner));
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        runtime_args! {
+            "entrypoint" => "approve_and_swap_tokens_for_exact_tokens",
+            "package_hash" => Key::Hash(router.package_hash()),
+            "amount_out" => U256::from(10000),
+            "amount_in_max" => AMOUNT,
+            "path" => vec![token_a.to_formatted_string(), token_b.to_formatted_string()],
+            "to" => Key::Account(owner),
+            "deadline" => U256::from(now),
+        },
+        now,
+    );
+    let ret: Vec<U256> = result_key(&env, owner, "swap_tokens_for_exact_tokens");
+    assert_eq!(ret[1], 10000.into());
+    assert_eq!(
+        balance_before - balance_of(&token1, Address::Account(owner)),
+        ret[0]
+    );
+}
+
+// The `ApiError` a failed deploy reverted with, out of the panic message of the test env.
+fn reverted_with(error: &str) -> &str {
+    let start: usize = error.find("ApiError::").expect(error);
+    let end: usize = start + error[start..].find(')').expect(error) + 1;
+    &error[start..end]
+}
+
+#[test]
+fn swap_after_approve_and_swap() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    let path = Path::new(vec![token_a, token_b]).unwrap();
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        runtime_args! {
+            "entrypoint" => "approve_and_swap_exact_tokens_for_tokens",
+            "package_hash" => Key::Hash(router.package_hash()),
+            "amount_in" => U256::from(10000),
+            "amount_out_min" => U256::from(1),
+            "path" => path.to_strings(),
+            "to" => Key::Account(owner),
+            "deadline" => U256::from(now),
+        },
+        now,
+    );
+    let swap = |sender: AccountHash| -> String {
+        panic::catch_unwind(AssertUnwindSafe(|| {
+            call_router(
+                &env,
+                sender,
+                &router,
+                RouterCall::SwapExactTokensForTokens {
+                    amount_in: U256::from(10000),
+                    amount_out_min: U256::from(1),
+                    path: path.clone(),
+                    to: Key::Account(sender),
+                    deadline: Deadline::At(now),
+                },
+                now,
+            )
+        }))
+        .map_err(|error| error.downcast_ref::<String>().cloned().unwrap_or_default())
+        .expect_err("a swap without allowance should revert")
+    };
+    // the allowance left over from `init` was replaced and then cleared by the session
+    let balance = balance_of(&token1, Address::Account(owner));
+    let after_session = swap(owner);
+    assert_eq!(balance_of(&token1, Address::Account(owner)), balance);
+    // the token reverts as it does for a holder that never approved the router
+    let holder = env.next_user();
+    token1.call_contract(
+        owner,
+        "transfer",
+        runtime_args! {
+            "recipient" => Address::Account(holder),
+            "amount" => U256::from(10000),
+        },
+        now,
+    );
+    let never_approved = swap(holder);
+    assert!(
+        reverted_with(&after_session).starts_with("ApiError::User("),
+        "{}",
+        after_session
+    );
+    assert_eq!(
+        reverted_with(&after_session),
+        reverted_with(&never_approved)
+    );
+}
+
+#[test]
+fn session_results_are_numbered() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    let (amount_a, amount_b, liquidity): (U256, U256, U256) =
+        result_key(&env, owner, "add_liquidity");
+    let result: SessionResult = session_result(&env, owner);
+    assert_eq!(result.run_id, 1);
+    assert_eq!(result.destination, "add_liquidity");
+    assert_eq!(result.block_time, now);
+    assert_eq!(result.value("amount_a"), amount_a);
+    assert_eq!(result.value("amount_b"), amount_b);
+    assert_eq!(result.value("liquidity"), liquidity);
+    assert!(result.amounts.is_empty());
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        runtime_args! {
+            "entrypoint" => "swap_exact_tokens_for_tokens",
+            "package_hash" => Key::Hash(router.package_hash()),
+            "amount_in" => U256::from(10000),
+            "amount_out_min" => U256::from(1),
+            "path" => vec![token_a.to_formatted_string(), token_b.to_formatted_string()],
+            "to" => Key::Account(owner),
+            "deadline" => U256::from(now + 1),
+        },
+        now + 1,
+    );
+    let ret: Vec<U256> = result_key(&env, owner, "swap_exact_tokens_for_tokens");
+    let result: SessionResult = session_result(&env, owner);
+    assert_eq!(result.run_id, 2);
+    assert_eq!(result.destination, "swap_exact_tokens_for_tokens");
+    assert_eq!(result.block_time, now + 1);
+    assert_eq!(result.value("amount_in"), 10000.into());
+    assert_eq!(result.value("amount_out"), ret[1]);
+    assert_eq!(result.amounts, ret);
+}
+
+#[test]
+fn session_result_of_queue_operation() {
+    let (env, owner, router, _, _, _, _, _, _, factory, now) = deploy();
+    let target = Key::Hash(factory.package_hash());
+    let eta: u64 = now + TIMELOCK_DELAY;
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::QueueOperation {
+            action: "set_factory".to_string(),
+            target,
+            value: 0u64,
+            eta,
+        },
+        now,
+    );
+    let result: SessionResult = session_result(&env, owner);
+    assert_eq!(result.run_id, 1);
+    assert_eq!(
+        result.operation_hash,
+        Some(operation_hash("set_factory", target, 0, eta))
+    );
+    assert!(result.values.is_empty());
+}
+#[test]
+fn script_remove_swap_and_add_liquidity() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    let pair = Key::Hash(pair.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        pair,
+        Deadline::At(now),
+        now,
+    );
+    let (_, _, liquidity): (U256, U256, U256) = result_key(&env, owner, "add_liquidity");
+    let script = Script::new()
+        .step(
+            "approve_and_remove_liquidity",
+            runtime_args! {
+                "token_a" => token_a,
+                "token_b" => token_b,
+                "liquidity" => liquidity / 2,
+                "amount_a_min" => U256::from(1),
+                "amount_b_min" => U256::from(1),
+                "to" => Key::Account(owner),
+                "deadline" => U256::from(now),
+                "pair" => pair,
+            },
+        )
+        // swap all of token a that was removed
+        .step(
+            "approve_and_swap_exact_tokens_for_tokens",
+            runtime_args! {
+                "amount_out_min" => U256::from(1),
+                "path" => vec![token_a.to_formatted_string(), token_b.to_formatted_string()],
+                "to" => Key::Account(owner),
+                "deadline" => U256::from(now),
+            },
+        )
+        .input("amount_in", 0, "amount_a")
+        // and add the swapped token b back
+        .step(
+            "approve_and_add_liquidity",
+            runtime_args! {
+                "token_a" => token_a,
+                "token_b" => token_b,
+                "amount_a_desired" => AMOUNT,
+                "amount_a_min" => U256::from(1),
+                "amount_b_min" => U256::from(1),
+                "to" => Key::Account(owner),
+                "deadline" => U256::from(now),
+                "pair" => Some(pair),
+            },
+        )
+        .input("amount_b_desired", 1, "amount_out");
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        script.args(Key::Hash(router.package_hash())),
+        now,
+    );
+    let (amount_a, _): (U256, U256) = result_key(&env, owner, "remove_liquidity");
+    let amounts: Vec<U256> = result_key(&env, owner, "swap_exact_tokens_for_tokens");
+    assert_eq!(amounts[0], amount_a);
+    let result: SessionResult = session_result(&env, owner);
+    assert_eq!(result.run_id, 4);
+    assert_eq!(result.destination, "add_liquidity");
+    assert_eq!(result.value("amount_b"), amounts[1]);
+}
+
+#[test]
+#[should_panic(expected = "ApiError::InvalidArgument")]
+fn script_input_from_later_step() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    let path: Vec<String> = vec![token_a.to_formatted_string(), token_b.to_formatted_string()];
+    let script = Script::new()
+        .step(
+            "swap_exact_tokens_for_tokens",
+            runtime_args! {
+                "amount_out_min" => U256::from(1),
+                "path" => path.clone(),
+                "to" => Key::Account(owner),
+                "deadline" => U256::from(now),
+            },
+        )
+        .input("amount_in", 1, "amount_out")
+        .step(
+            "swap_exact_tokens_for_tokens",
+            runtime_args! {
+                "amount_in" => U256::from(10000),
+                "amount_out_min" => U256::from(1),
+                "path" => path,
+                "to" => Key::Account(owner),
+                "deadline" => U256::from(now),
+            },
+        );
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        script.args(Key::Hash(router.package_hash())),
+        now,
+    );
+}
+
+#[test]
+fn script_with_failing_step() {
+    let (env, owner, router, _, pair, token1, token2, _, _, _, now) = deploy();
+    let token_a = Key::Hash(token1.package_hash());
+    let token_b = Key::Hash(token2.package_hash());
+    add_token_liquidity(
+        &env,
+        owner,
+        &router,
+        token_a,
+        token_b,
+        Key::Hash(pair.package_hash()),
+        Deadline::At(now),
+        now,
+    );
+    let path: Vec<String> = vec![token_a.to_formatted_string(), token_b.to_formatted_string()];
+    // the second swap is past its deadline, so the first one is reverted with it
+    let script = Script::new()
+        .step(
+            "swap_exact_tokens_for_tokens",
+            runtime_args! {
+                "amount_in" => U256::from(10000),
+                "amount_out_min" => U256::from(1),
+                "path" => path.clone(),
+                "to" => Key::Account(owner),
+                "deadline" => U256::from(now + 1),
+            },
+        )
+        .step(
+            "swap_exact_tokens_for_tokens",
+            runtime_args! {
+                "amount_out_min" => U256::from(1),
+                "path" => path,
+                "to" => Key::Account(owner),
+                "deadline" => U256::from(now),
+            },
+        )
+        .input("amount_in", 0, "amount_out");
+    let balance_a: U256 = balance_of(&token1, Address::Account(owner));
+    let balance_b: U256 = balance_of(&token2, Address::Account(owner));
+    let error: String = panic::catch_unwind(AssertUnwindSafe(|| {
+        call(
+            &env,
+            owner,
+            SESSION_CODE_ROUTER,
+            script.args(Key::Hash(router.package_hash())),
+            now + 1,
+        )
+    }))
+    .map_err(|error| error.downcast_ref::<String>().cloned().unwrap_or_default())
+    .err()
+    .expect("the failing step reverts the script");
+    // TimedOut
+    assert!(error.contains("ApiError::User(83)"), "{}", error);
+    assert_eq!(balance_of(&token1, Address::Account(owner)), balance_a);
+    assert_eq!(balance_of(&token2, Address::Account(owner)), balance_b);
+    // neither swap left a result, the last run is still the add_liquidity
+    assert_eq!(session_result(&env, owner).run_id, 1);
+}
+
+#[test]
+fn script_with_typed_inputs() {
+    let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
+    let token = Key::Hash(token1.package_hash());
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::AddLiquidityCspr {
+            token,
+            amount_token_desired: AMOUNT,
+            amount_cspr_desired: AMOUNT,
+            amount_token_min: U256::from(1000000),
+            amount_cspr_min: U256::from(1000000),
+            to: Key::Account(owner),
+            deadline: Deadline::At(now),
+            pair: Some(Key::Hash(pair.package_hash())),
+        },
+        now,
+    );
+    let path: Vec<String> = vec![
+        Key::Hash(wcspr.package_hash()).to_formatted_string(),
+        token.to_formatted_string(),
+    ];
+    // the second swap spends as much cspr as the first one, passed as its U512 `amount`
+    let script = Script::new()
+        .step(
+            "swap_exact_cspr_for_tokens",
+            runtime_args! {
+                "amount" => U512::from(10000),
+                "amount_in" => U256::from(10000),
+                "amount_out_min" => U256::from(1),
+                "path" => path.clone(),
+                "to" => Key::Account(owner),
+                "deadline" => U256::from(now),
+            },
+        )
+        .step(
+            "swap_exact_cspr_for_tokens",
+            runtime_args! {
+                "amount_out_min" => U256::from(1),
+                "path" => path,
+                "to" => Key::Account(owner),
+                "deadline" => U256::from(now),
+            },
+        )
+        .typed_input("amount", 0, "cspr_spent", INPUT_U512)
+        .input("amount_in", 0, "cspr_spent");
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        script.args(Key::Hash(router.package_hash())),
+        now,
+    );
+    let result: SessionResult = session_result(&env, owner);
+    assert_eq!(result.run_id, 3);
+    assert_eq!(result.value("cspr_spent"), 10000.into());
+}
+
+#[test]
+#[should_panic(expected = "ApiError::InvalidArgument")]
+fn script_with_u64_input_out_of_range() {
+    let (env, owner, router, _, _, _, _, _, _, factory, now) = deploy();
+    // the quote does not fit in the u64 `value` of a timelock operation
+    let script = Script::new()
+        .step(
+            "quote",
+            runtime_args! {
+                "amount_a" => U256::from(u64::MAX) + 1,
+                "reserve_a" => U256::from(1),
+                "reserve_b" => U256::from(1),
+            },
+        )
+        .step(
+            "queue_operation",
+            runtime_args! {
+                "action" => "set_fee_to",
+                "target" => Key::Hash(factory.package_hash()),
+                "eta" => now + TIMELOCK_DELAY,
+            },
+        )
+        .typed_input("value", 0, "amount_b", INPUT_U64);
+    call(
+        &env,
+        owner,
+        SESSION_CODE_ROUTER,
+        script.args(Key::Hash(router.package_hash())),
+        now,
+    );
+}
+
+#[test]
+fn swap_cspr_for_exact_tokens_refunds_unspent_cspr() {
+    let (env, owner, router, _, pair, token1, _, _, wcspr, _, now) = deploy();
+    let token = Key::Hash(token1.package_hash());
+    call_router(
+        &env,
+        owner,
+        &router,
+        RouterCall::AddLiquidityCspr {
+            token,
+            amount_token_desired: AMOUNT,
+            amount_cspr_desired: AMOUNT,
+            amount_token_min: U256::from(1000000),
+            amount_cspr_min: U256::from(1000000),
+            to: Key::Account(owner),
+            deadline: Deadline::At(now),
+            pair: Some(Key::Hash(pair.package_hash())),
+        },
+        now,
+    );
+    let result: SessionResult = session_result(&env, owner);
+    assert_eq!(result.value("cspr_spent"), result.value("amount_cspr"));
+    assert_eq!(
+        result.value("cspr_spent") + result.value("cspr_refunded"),
+        AMOUNT
+    );
+    // far more cspr than the swap needs, the rest goes back to the main purse
+    let amount: U512 = AMOUNT_U512 * 10;
+    let paid: U512 = gas_used(&env, owner, || {
+        call_router(
+            &env,
+            owner,
+            &router,
+            RouterCall::SwapCsprForExactTokens {
+                amount_out: U256::from(10000),
+                amount_in_max: u512_to_u256(amount),
+                path: Path::new(vec![Key::Hash(wcspr.package_hash()), token]).unwrap(),
+                to: Key::Account(owner),
+                deadline: Deadline::At(now),
+            },
+            now,
+        );
+    });
+    let ret: Vec<U256> = result_key(&env, owner, "swap_cspr_for_exact_tokens");
+    let result: SessionResult = session_result(&env, owner);
+    assert_eq!(result.value("cspr_spent"), ret[0]);
+    assert_eq!(result.value("cspr_refunded"), u512_to_u256(amount) - ret[0]);
+    // only the spent cspr and the gas left the account
+    assert_lt!(paid, amount);
+}
+#[test]
+fn sdk_rejects_calls_the_router_reverts_on() {
+    let token_a = Key::Hash([1; 32]);
+    let token_b = Key::Hash([2; 32]);
+    assert_eq!(Path::new(vec![token_a]), Err(SdkError::PathTooShort));
+    assert_eq!(
+        Path::new(vec![token_a, token_a]),
+        Err(SdkError::IdenticalTokens(token_a.to_formatted_string()))
+    );
+    let swap = RouterCall::SwapExactTokensForTokens {
+        amount_in: U256::zero(),
+        amount_out_min: U256::from(1),
+        path: Path::new(vec![token_a, token_b]).unwrap(),
+        to: Key::Hash([3; 32]),
+        deadline: Deadline::After {
+            submitted_at: u64::MAX,
+            valid_for: 1,
+        },
+    };
+    assert_eq!(swap.args(), Err(SdkError::ZeroAmount("amount_in")));
+    let add = RouterCall::AddLiquidity {
+        token_a,
+        token_b,
+        amount_a_desired: AMOUNT,
+        amount_b_desired: AMOUNT,
+        amount_a_min: AMOUNT + 1,
+        amount_b_min: U256::zero(),
+        to: Key::Hash([3; 32]),
+        deadline: Deadline::At(0),
+        pair: None,
+    };
+    assert_eq!(
+        add.args(),
+        Err(SdkError::MinimumAboveDesired("amount_a_min"))
+    );
+}
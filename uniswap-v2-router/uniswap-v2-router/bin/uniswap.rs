@@ -4,7 +4,8 @@ use std::collections::BTreeSet;
 
 use uniswap_v2_router_crate::{
     contract_api::{runtime, storage, system},
-    functions::get_purse,
+    entry_points::get_entry_points,
+    functions::{get_optional_named_arg, get_purse},
     unwrap_or_revert::UnwrapOrRevert,
     *,
 };
@@ -20,12 +21,18 @@ impl ContractContext<OnChainContractStorage> for Uniswap {
 
 impl UniswapV2Router<OnChainContractStorage> for Uniswap {}
 
+impl LimitOrderBook<OnChainContractStorage> for Uniswap {}
+impl DcaScheduler<OnChainContractStorage> for Uniswap {}
+impl RouterAdmin<OnChainContractStorage> for Uniswap {}
+impl Timelock<OnChainContractStorage> for Uniswap {}
+
 impl Uniswap {
     fn constructor(
         &self,
         factory: Key,
         wcspr: Key,
         library_hash: Key,
+        use_library_contract: bool,
         contract_hash: ContractHash,
         package_hash: ContractPackageHash,
     ) {
@@ -34,6 +41,7 @@ impl Uniswap {
             factory.into_hash().unwrap_or_default().into(),
             wcspr.into_hash().unwrap_or_default().into(),
             library_hash.into_hash().unwrap_or_default().into(),
+            use_library_contract,
             contract_hash,
             package_hash,
         );
@@ -46,9 +54,17 @@ fn constructor() {
     let factory: Key = runtime::get_named_arg("factory");
     let wcspr: Key = runtime::get_named_arg("wcspr");
     let library_hash: Key = runtime::get_named_arg("library_hash");
+    let use_library_contract: bool = runtime::get_named_arg("use_library_contract");
     let contract_hash: ContractHash = runtime::get_named_arg("contract_hash");
     let package_hash: ContractPackageHash = runtime::get_named_arg("package_hash");
-    Uniswap::default().constructor(factory, wcspr, library_hash, contract_hash, package_hash);
+    Uniswap::default().constructor(
+        factory,
+        wcspr,
+        library_hash,
+        use_library_contract,
+        contract_hash,
+        package_hash,
+    );
 }
 
 #[inline(always)]
@@ -146,7 +162,6 @@ fn remove_liquidity_cspr() {
     let amount_token_min: U256 = runtime::get_named_arg("amount_token_min");
     let amount_cspr_min: U256 = runtime::get_named_arg("amount_cspr_min");
     let to: Key = runtime::get_named_arg("to");
-    let to_purse: URef = runtime::get_named_arg("to_purse");
     let deadline: U256 = runtime::get_named_arg("deadline");
     let (amount_token, amount_cspr): (U256, U256) = Uniswap::default().remove_liquidity_cspr(
         token.into_hash().unwrap_or_revert().into(),
@@ -154,7 +169,6 @@ fn remove_liquidity_cspr() {
         amount_token_min,
         amount_cspr_min,
         to,
-        to_purse,
         deadline,
     );
     runtime::ret(CLValue::from_t((amount_token, amount_cspr)).unwrap_or_revert());
@@ -253,7 +267,7 @@ fn swap_tokens_for_exact_cspr() {
     let amount_out: U256 = runtime::get_named_arg("amount_out");
     let amount_in_max: U256 = runtime::get_named_arg("amount_in_max");
     let path: Vec<String> = runtime::get_named_arg("path");
-    let to: URef = runtime::get_named_arg("to");
+    let to: Key = runtime::get_named_arg("to");
     let deadline: U256 = runtime::get_named_arg("deadline");
     let amounts: Vec<U256> = Uniswap::default().swap_tokens_for_exact_cspr(
         amount_out,
@@ -273,7 +287,7 @@ fn swap_exact_tokens_for_cspr() {
     let amount_in: U256 = runtime::get_named_arg("amount_in");
     let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
     let path: Vec<String> = runtime::get_named_arg("path");
-    let to: URef = runtime::get_named_arg("to");
+    let to: Key = runtime::get_named_arg("to");
     let deadline: U256 = runtime::get_named_arg("deadline");
     let amounts: Vec<U256> = Uniswap::default().swap_exact_tokens_for_cspr(
         amount_in,
@@ -335,286 +349,335 @@ fn get_amounts_in() {
 }
 
 #[no_mangle]
-// only accept CSPR via from the WCSPR contract
+/// Reason a deadline would be rejected at the current block time, 0 if it is valid.
+///
+/// Parameters-> deadline:U256
+fn check_deadline() {
+    let deadline: U256 = runtime::get_named_arg("deadline");
+    let reason: u8 = deadline::deadline_reason(deadline);
+    runtime::ret(CLValue::from_t(reason).unwrap_or_revert())
+}
+
+#[no_mangle]
+/// Escrow tokens in the router until the order can be filled at the limit.
+///
+/// Parameters-> amount_in:U256, amount_out_min:U256, bounty:U256, path:Vec<String>, to:Key, expiry:U256
+fn create_limit_order() {
+    let amount_in: U256 = runtime::get_named_arg("amount_in");
+    let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
+    let bounty: U256 = runtime::get_named_arg("bounty");
+    let path: Vec<String> = runtime::get_named_arg("path");
+    let to: Key = runtime::get_named_arg("to");
+    let expiry: U256 = runtime::get_named_arg("expiry");
+    let order_id: U256 =
+        Uniswap::default().create_limit_order(amount_in, amount_out_min, bounty, path, to, expiry);
+    runtime::ret(CLValue::from_t(order_id).unwrap_or_revert());
+}
+
+#[no_mangle]
+/// Escrow cspr in the router until the order can be filled at the limit.
+///
+/// Parameters-> amount_in:U256, amount_out_min:U256, bounty:U256, path:Vec<String>, to:Key, expiry:U256, purse:URef
+fn create_limit_order_cspr() {
+    let amount_in: U256 = runtime::get_named_arg("amount_in");
+    let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
+    let bounty: U256 = runtime::get_named_arg("bounty");
+    let path: Vec<String> = runtime::get_named_arg("path");
+    let to: Key = runtime::get_named_arg("to");
+    let purse: URef = runtime::get_named_arg("purse");
+    let expiry: U256 = runtime::get_named_arg("expiry");
+    let order_id: U256 = Uniswap::default().create_limit_order_cspr(
+        amount_in,
+        amount_out_min,
+        bounty,
+        path,
+        to,
+        purse,
+        expiry,
+    );
+    runtime::ret(CLValue::from_t(order_id).unwrap_or_revert());
+}
+
+#[no_mangle]
+/// Fill an open limit order, the caller receives the order bounty.
+///
+/// Parameters-> order_id:U256
+fn fill_order() {
+    let order_id: U256 = runtime::get_named_arg("order_id");
+    let amounts: Vec<U256> = Uniswap::default().fill_order(order_id);
+    runtime::ret(CLValue::from_t(amounts).unwrap_or_revert());
+}
+
+#[no_mangle]
+/// Cancel an open limit order and refund the escrow to its owner.
+///
+/// Parameters-> order_id:U256
+fn cancel_order() {
+    let order_id: U256 = runtime::get_named_arg("order_id");
+    Uniswap::default().cancel_order(order_id);
+}
+
+#[no_mangle]
+/// Escrow a budget of tokens in the router to be swapped in equal amounts once per interval.
+///
+/// Parameters-> budget:U256, amount_per_interval:U256, interval:u64, amount_out_min:U256, fee:U256, path:Vec<String>, to:Key
+fn create_dca_order() {
+    let budget: U256 = runtime::get_named_arg("budget");
+    let amount_per_interval: U256 = runtime::get_named_arg("amount_per_interval");
+    let interval: u64 = runtime::get_named_arg("interval");
+    let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
+    let fee: U256 = runtime::get_named_arg("fee");
+    let path: Vec<String> = runtime::get_named_arg("path");
+    let to: Key = runtime::get_named_arg("to");
+    let order_id: U256 = Uniswap::default().create_dca_order(
+        budget,
+        amount_per_interval,
+        interval,
+        amount_out_min,
+        fee,
+        path,
+        to,
+    );
+    runtime::ret(CLValue::from_t(order_id).unwrap_or_revert());
+}
+
+#[no_mangle]
+/// Escrow a budget of cspr in the router to be swapped in equal amounts once per interval.
+///
+/// Parameters-> budget:U256, amount_per_interval:U256, interval:u64, amount_out_min:U256, fee:U256, path:Vec<String>, to:Key, purse:URef
+fn create_dca_order_cspr() {
+    let budget: U256 = runtime::get_named_arg("budget");
+    let amount_per_interval: U256 = runtime::get_named_arg("amount_per_interval");
+    let interval: u64 = runtime::get_named_arg("interval");
+    let amount_out_min: U256 = runtime::get_named_arg("amount_out_min");
+    let fee: U256 = runtime::get_named_arg("fee");
+    let path: Vec<String> = runtime::get_named_arg("path");
+    let to: Key = runtime::get_named_arg("to");
+    let purse: URef = runtime::get_named_arg("purse");
+    let order_id: U256 = Uniswap::default().create_dca_order_cspr(
+        budget,
+        amount_per_interval,
+        interval,
+        amount_out_min,
+        fee,
+        path,
+        to,
+        purse,
+    );
+    runtime::ret(CLValue::from_t(order_id).unwrap_or_revert());
+}
+
+#[no_mangle]
+/// Execute a due dca order, the caller receives the order fee.
+///
+/// Parameters-> order_id:U256
+fn execute_dca_order() {
+    let order_id: U256 = runtime::get_named_arg("order_id");
+    let amounts: Vec<U256> = Uniswap::default().execute_dca_order(order_id);
+    runtime::ret(CLValue::from_t(amounts).unwrap_or_revert());
+}
+
+#[no_mangle]
+/// Pause a dca order, only its owner can pause it.
+///
+/// Parameters-> order_id:U256
+fn pause_dca_order() {
+    let order_id: U256 = runtime::get_named_arg("order_id");
+    Uniswap::default().pause_dca_order(order_id);
+}
+
+#[no_mangle]
+/// Resume a paused dca order, only its owner can resume it.
+///
+/// Parameters-> order_id:U256
+fn resume_dca_order() {
+    let order_id: U256 = runtime::get_named_arg("order_id");
+    Uniswap::default().resume_dca_order(order_id);
+}
+
+#[no_mangle]
+/// Close a dca order and refund the remaining budget to its owner.
+///
+/// Parameters-> order_id:U256
+fn withdraw_dca_order() {
+    let order_id: U256 = runtime::get_named_arg("order_id");
+    let amount: U256 = Uniswap::default().withdraw_dca_order(order_id);
+    runtime::ret(CLValue::from_t(amount).unwrap_or_revert());
+}
+
+#[no_mangle]
+// only accept CSPR from the WCSPR contract, while the router is withdrawing it
 fn receive() {
     let amount: U512 = runtime::get_named_arg("amount");
     let purse: URef = runtime::get_named_arg("purse");
-    system::transfer_from_purse_to_purse(purse, get_purse(), amount, None).unwrap_or_revert();
-}
-
-#[no_mangle]
-// change the owner for whitelisting
-fn change_owner() {
-    let owner: Key = runtime::get_named_arg("owner");
-    Uniswap::default().change_owner(owner);
-}
-
-#[no_mangle]
-// add a user to whitelist
-fn add_to_whitelist() {
-    let user: Key = runtime::get_named_arg("user");
-    Uniswap::default().add_to_whitelist(user);
-}
-
-#[no_mangle]
-// remove a user from whitelist
-fn remove_from_whitelist() {
-    let user: Key = runtime::get_named_arg("user");
-    Uniswap::default().remove_from_whitelist(user);
-}
-
-fn get_entry_points() -> EntryPoints {
-    let mut entry_points = EntryPoints::new();
-    entry_points.add_entry_point(EntryPoint::new(
-        "constructor",
-        vec![
-            Parameter::new("factory", CLType::Key),
-            Parameter::new("wcspr", CLType::Key),
-            Parameter::new("library_hash", CLType::Key),
-            Parameter::new("contract_hash", ContractHash::cl_type()),
-            Parameter::new("package_hash", ContractPackageHash::cl_type()),
-        ],
-        <()>::cl_type(),
-        EntryPointAccess::Groups(vec![Group::new("constructor")]),
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "add_liquidity",
-        vec![
-            Parameter::new("token_a", CLType::Key),
-            Parameter::new("token_b", CLType::Key),
-            Parameter::new("amount_a_desired", CLType::U256),
-            Parameter::new("amount_b_desired", CLType::U256),
-            Parameter::new("amount_a_min", CLType::U256),
-            Parameter::new("amount_b_min", CLType::U256),
-            Parameter::new("to", CLType::Key),
-            Parameter::new("deadline", CLType::U256),
-            Parameter::new("pair", CLType::Option(Box::new(CLType::Key))),
-        ],
-        CLType::Tuple3([
-            Box::new(CLType::U256),
-            Box::new(CLType::U256),
-            Box::new(CLType::U256),
-        ]),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "add_liquidity_cspr",
-        vec![
-            Parameter::new("token", Key::cl_type()),
-            Parameter::new("amount_token_desired", CLType::U256),
-            Parameter::new("amount_cspr_desired", CLType::U256),
-            Parameter::new("amount_token_min", CLType::U256),
-            Parameter::new("amount_cspr_min", CLType::U256),
-            Parameter::new("to", Key::cl_type()),
-            Parameter::new("deadline", CLType::U256),
-            Parameter::new("pair", CLType::Option(Box::new(CLType::Key))),
-            Parameter::new("purse", CLType::URef),
-        ],
-        CLType::Tuple3([
-            Box::new(CLType::U256),
-            Box::new(CLType::U256),
-            Box::new(CLType::U256),
-        ]),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "remove_liquidity",
-        vec![
-            Parameter::new("token_a", Key::cl_type()),
-            Parameter::new("token_b", Key::cl_type()),
-            Parameter::new("liquidity", CLType::U256),
-            Parameter::new("amount_a_min", CLType::U256),
-            Parameter::new("amount_b_min", CLType::U256),
-            Parameter::new("to", Key::cl_type()),
-            Parameter::new("deadline", CLType::U256),
-        ],
-        CLType::Tuple2([Box::new(CLType::U256), Box::new(CLType::U256)]),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "remove_liquidity_cspr",
-        vec![
-            Parameter::new("token", Key::cl_type()),
-            Parameter::new("liquidity", CLType::U256),
-            Parameter::new("amount_token_min", CLType::U256),
-            Parameter::new("amount_cspr_min", CLType::U256),
-            Parameter::new("to", Key::cl_type()),
-            Parameter::new("deadline", CLType::U256),
-            Parameter::new("to_purse", CLType::URef),
-        ],
-        CLType::Tuple2([Box::new(CLType::U256), Box::new(CLType::U256)]),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "swap_exact_tokens_for_tokens",
-        vec![
-            Parameter::new("amount_in", CLType::U256),
-            Parameter::new("amount_out_min", CLType::U256),
-            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
-            Parameter::new("to", CLType::Key),
-            Parameter::new("deadline", CLType::U256),
-        ],
-        CLType::List(Box::new(CLType::U256)),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "swap_tokens_for_exact_tokens",
-        vec![
-            Parameter::new("amount_out", CLType::U256),
-            Parameter::new("amount_in_max", CLType::U256),
-            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
-            Parameter::new("to", CLType::Key),
-            Parameter::new("deadline", CLType::U256),
-        ],
-        CLType::List(Box::new(CLType::U256)),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "swap_exact_cspr_for_tokens",
-        vec![
-            Parameter::new("amount_out_min", CLType::U256),
-            Parameter::new("amount_in", CLType::U256),
-            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
-            Parameter::new("to", CLType::Key),
-            Parameter::new("deadline", CLType::U256),
-            Parameter::new("purse", CLType::URef),
-        ],
-        CLType::List(Box::new(CLType::U256)),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "swap_tokens_for_exact_cspr",
-        vec![
-            Parameter::new("amount_out", CLType::U256),
-            Parameter::new("amount_in_max", CLType::U256),
-            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
-            Parameter::new("to", CLType::URef),
-            Parameter::new("deadline", CLType::U256),
-        ],
-        CLType::List(Box::new(CLType::U256)),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "swap_exact_tokens_for_cspr",
-        vec![
-            Parameter::new("amount_in", CLType::U256),
-            Parameter::new("amount_out_min", CLType::U256),
-            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
-            Parameter::new("to", CLType::URef), // purse to transfer cspr to
-            Parameter::new("deadline", CLType::U256),
-        ],
-        CLType::List(Box::new(CLType::U256)),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "swap_cspr_for_exact_tokens",
-        vec![
-            Parameter::new("amount_out", CLType::U256),
-            Parameter::new("amount_in_max", CLType::U256),
-            Parameter::new("path", CLType::List(Box::new(String::cl_type()))),
-            Parameter::new("to", CLType::Key),
-            Parameter::new("deadline", CLType::U256),
-            Parameter::new("purse", CLType::URef),
-        ],
-        CLType::List(Box::new(CLType::U256)),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "quote",
-        vec![
-            Parameter::new("amount_a", U256::cl_type()),
-            Parameter::new("reserve_a", U256::cl_type()),
-            Parameter::new("reserve_b", U256::cl_type()),
-        ],
-        U256::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "get_amount_out",
-        vec![
-            Parameter::new("amount_in", U256::cl_type()),
-            Parameter::new("reserve_in", U256::cl_type()),
-            Parameter::new("reserve_out", U256::cl_type()),
-        ],
-        U256::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "get_amount_in",
-        vec![
-            Parameter::new("amount_out", U256::cl_type()),
-            Parameter::new("reserve_in", U256::cl_type()),
-            Parameter::new("reserve_out", U256::cl_type()),
-        ],
-        U256::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "get_amounts_out",
-        vec![
-            Parameter::new("amount_in", U256::cl_type()),
-            Parameter::new("path", CLType::List(Box::new(Key::cl_type()))),
-        ],
-        CLType::List(Box::new(U256::cl_type())),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "get_amounts_in",
-        vec![
-            Parameter::new("amount_out", U256::cl_type()),
-            Parameter::new("path", CLType::List(Box::new(Key::cl_type()))),
-        ],
-        CLType::List(Box::new(U256::cl_type())),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "receive",
-        vec![
-            Parameter::new("amount", U512::cl_type()),
-            Parameter::new("purse", URef::cl_type()),
-        ],
-        <()>::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "change_owner",
-        vec![Parameter::new("owner", Key::cl_type())],
-        <()>::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "add_to_whitelist",
-        vec![Parameter::new("user", Key::cl_type())],
-        <()>::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "remove_from_whitelist",
-        vec![Parameter::new("user", Key::cl_type())],
-        <()>::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points
+    UniswapV2Router::receive(&Uniswap::default(), amount, purse);
+}
+
+#[no_mangle]
+/// Queue a privileged action of the owner to be executed once eta has passed.
+///
+/// Parameters-> action:String, target:Key, value:u64, eta:u64
+fn queue_operation() {
+    let action: String = runtime::get_named_arg("action");
+    let target: Key = runtime::get_named_arg("target");
+    let value: u64 = runtime::get_named_arg("value");
+    let eta: u64 = runtime::get_named_arg("eta");
+    let operation_hash: String = Uniswap::default().queue_operation(action, target, value, eta);
+    runtime::ret(CLValue::from_t(operation_hash).unwrap_or_revert());
+}
+
+#[no_mangle]
+// cancel a queued operation
+fn cancel_operation() {
+    let operation_hash: String = runtime::get_named_arg("operation_hash");
+    Uniswap::default().cancel_operation(operation_hash);
+}
+
+#[no_mangle]
+// execute a queued operation whose eta has passed
+fn execute_operation() {
+    let operation_hash: String = runtime::get_named_arg("operation_hash");
+    Uniswap::default().execute_operation(operation_hash);
+}
+
+#[no_mangle]
+/// Queue a new factory for eta, or apply it when called again once eta has passed.
+///
+/// Parameters-> factory:Key, eta:u64
+fn set_factory() {
+    let factory: Key = runtime::get_named_arg("factory");
+    let eta: u64 = runtime::get_named_arg("eta");
+    Uniswap::default().set_reference("set_factory", factory, eta);
+}
+
+#[no_mangle]
+/// Queue a new wcspr for eta, or apply it when called again once eta has passed.
+///
+/// Parameters-> wcspr:Key, eta:u64
+fn set_wcspr() {
+    let wcspr: Key = runtime::get_named_arg("wcspr");
+    let eta: u64 = runtime::get_named_arg("eta");
+    Uniswap::default().set_reference("set_wcspr", wcspr, eta);
+}
+
+#[no_mangle]
+/// Queue a new library for eta, or apply it when called again once eta has passed.
+///
+/// Parameters-> library_hash:Key, eta:u64
+fn set_library_hash() {
+    let library_hash: Key = runtime::get_named_arg("library_hash");
+    let eta: u64 = runtime::get_named_arg("eta");
+    Uniswap::default().set_reference("set_library_hash", library_hash, eta);
+}
+
+#[no_mangle]
+/// Look up a timelock operation, queued, executed or cancelled, by its hash.
+///
+/// Parameters-> operation_hash:String
+fn get_operation() {
+    let operation_hash: String = runtime::get_named_arg("operation_hash");
+    let operation: data::TimelockOperation = Uniswap::default().operation(operation_hash);
+    runtime::ret(CLValue::from_t(operation).unwrap_or_revert());
+}
+
+#[no_mangle]
+// stop swaps and deposits without waiting for the timelock
+fn pause() {
+    Uniswap::default().pause();
+}
+
+#[no_mangle]
+/// Send cspr left in the router's purse to a purse of the owner.
+///
+/// Parameters-> amount:U512, to_purse:URef
+fn rescue_cspr() {
+    let amount: U512 = runtime::get_named_arg("amount");
+    let to_purse: URef = runtime::get_named_arg("to_purse");
+    Uniswap::default().rescue_cspr(amount, to_purse);
+}
+
+#[no_mangle]
+/// Send tokens that are not escrowed for an order out of the router.
+///
+/// Parameters-> token:Key, amount:U256, to:Key
+fn rescue_tokens() {
+    let token: Key = runtime::get_named_arg("token");
+    let amount: U256 = runtime::get_named_arg("amount");
+    let to: Key = runtime::get_named_arg("to");
+    Uniswap::default().rescue_tokens(token, amount, to);
+}
+
+#[no_mangle]
+fn get_purse_balance() {
+    let balance: U512 = system::get_purse_balance(get_purse()).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(balance).unwrap_or_revert());
+}
+
+#[no_mangle]
+/// Bring storage up to the layout of this contract version, returns the storage version.
+fn migrate() {
+    let version: u32 = Uniswap::default().migrate();
+    runtime::ret(CLValue::from_t(version).unwrap_or_revert());
+}
+
+#[no_mangle]
+/// Contract versions of the router package that can still be called.
+fn get_enabled_versions() {
+    let versions: Vec<ContractHash> = Uniswap::default().enabled_versions();
+    runtime::ret(CLValue::from_t(versions).unwrap_or_revert());
+}
+
+#[no_mangle]
+/// Record the version running as enabled after an upgrade, only the install session can call it.
+///
+/// Parameters-> disable_previous_versions:bool
+fn record_versions() {
+    let disable_previous_versions: bool = runtime::get_named_arg("disable_previous_versions");
+    Uniswap::default().record_versions(disable_previous_versions);
+}
+
+#[no_mangle]
+fn get_storage_version() {
+    runtime::ret(CLValue::from_t(data::storage_version()).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn is_paused() {
+    runtime::ret(CLValue::from_t(data::paused()).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn get_timelock_delay() {
+    runtime::ret(CLValue::from_t(data::timelock_delay()).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn get_factory() {
+    runtime::ret(CLValue::from_t(data::factory()).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn get_wcspr() {
+    runtime::ret(CLValue::from_t(data::wcspr()).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn get_library_hash() {
+    runtime::ret(CLValue::from_t(data::library_hash()).unwrap_or_revert());
+}
+
+// Add the upgrader group to the package with a single URef, kept next to the package access
+// token. Only sessions of the account holding it can call `record_versions`.
+fn put_upgrader_access(package_hash: ContractPackageHash, contract_name: &str) {
+    let upgrader_access: URef =
+        storage::create_contract_user_group(package_hash, "upgrader", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+    runtime::put_key(
+        &format!("{}_upgrader_access", contract_name),
+        upgrader_access.into(),
+    );
 }
 
 // All session code must have a `call` entrypoint.
@@ -633,12 +696,16 @@ fn call() {
         let factory: Key = runtime::get_named_arg("factory");
         let wcspr: Key = runtime::get_named_arg("wcspr");
         let library_hash: Key = runtime::get_named_arg("library");
+        // pricing math is linked into the router unless asked to go through the library contract
+        let use_library_contract: bool =
+            get_optional_named_arg("use_library_contract").unwrap_or_default();
 
         // Prepare constructor args
         let constructor_args = runtime_args! {
             "factory" => factory,
             "wcspr" => wcspr,
             "library_hash" =>  library_hash,
+            "use_library_contract" => use_library_contract,
             "contract_hash" => contract_hash,
             "package_hash" => package_hash,
         };
@@ -680,6 +747,7 @@ fn call() {
             &format!("{}_package_access_token", contract_name),
             access_token.into(),
         );
+        put_upgrader_access(package_hash, &contract_name);
     } else {
         // this is a contract upgrade
         let package_hash: ContractPackageHash =
@@ -689,9 +757,47 @@ fn call() {
                 .unwrap()
                 .into();
 
+        // routers installed before record_versions was restricted have no upgrader group yet
+        if !runtime::has_key(&format!("{}_upgrader_access", contract_name)) {
+            put_upgrader_access(package_hash, &contract_name);
+        }
         let (contract_hash, _): (ContractHash, _) =
             storage::add_contract_version(package_hash, get_entry_points(), Default::default());
 
+        // optionally stop previous versions from being called, needs the package access token
+        let disable_previous_versions: bool =
+            get_optional_named_arg("disable_previous_versions").unwrap_or_default();
+        if disable_previous_versions {
+            let mut previous_versions: Vec<ContractHash> = runtime::call_versioned_contract(
+                package_hash,
+                None,
+                "get_enabled_versions",
+                runtime_args! {},
+            );
+            // versions added before they were recorded are only known to this account
+            let latest_version: ContractHash =
+                runtime::get_key(&format!("{}_contract_hash", contract_name))
+                    .unwrap_or_revert()
+                    .into_hash()
+                    .unwrap_or_revert()
+                    .into();
+            if !previous_versions.contains(&latest_version) {
+                previous_versions.push(latest_version);
+            }
+            for previous_version in previous_versions {
+                storage::disable_contract_version(package_hash, previous_version)
+                    .unwrap_or_revert();
+            }
+        }
+        let _: () = runtime::call_versioned_contract(
+            package_hash,
+            None,
+            "record_versions",
+            runtime_args! {
+                "disable_previous_versions" => disable_previous_versions,
+            },
+        );
+
         // update contract hash
         runtime::put_key(
             &format!("{}_contract_hash", contract_name),
@@ -0,0 +1,69 @@
+use common::{errors::Errors, Key};
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SimError {
+    /// the router or the library math reverts with this error
+    Router(Errors),
+    /// a pair reverts, for the reasons of UniswapV2Pair
+    Pair(PairError),
+    /// the factory already has a pair for the two tokens
+    PairExists,
+    /// a transfer or burn exceeds the balance of the owner
+    InsufficientBalance { token: Key, owner: Key },
+    /// a cspr payment exceeds the balance of the payer
+    InsufficientCspr(Key),
+    /// the simulator has no model of the entry point
+    Unsupported(&'static str),
+    /// the scenario names an unknown account, token or pair, or is malformed
+    InvalidScenario(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PairError {
+    InsufficientLiquidityMinted,
+    InsufficientLiquidityBurned,
+    InsufficientOutputAmount,
+    InsufficientInputAmount,
+    InsufficientLiquidity,
+    InvalidTo,
+    K,
+    Overflow,
+}
+
+impl fmt::Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SimError::Router(error) => write!(f, "{}", error),
+            SimError::Pair(error) => write!(f, "pair reverted: {:?}", error),
+            SimError::PairExists => write!(f, "factory already has the pair"),
+            SimError::InsufficientBalance { token, owner } => write!(
+                f,
+                "{} has not enough of {}",
+                owner.to_formatted_string(),
+                token.to_formatted_string()
+            ),
+            SimError::InsufficientCspr(owner) => {
+                write!(f, "{} has not enough cspr", owner.to_formatted_string())
+            }
+            SimError::Unsupported(entry_point) => {
+                write!(f, "`{}` is not simulated", entry_point)
+            }
+            SimError::InvalidScenario(reason) => write!(f, "invalid scenario: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for SimError {}
+
+impl From<Errors> for SimError {
+    fn from(error: Errors) -> SimError {
+        SimError::Router(error)
+    }
+}
+
+impl From<PairError> for SimError {
+    fn from(error: PairError) -> SimError {
+        SimError::Pair(error)
+    }
+}
@@ -0,0 +1,15 @@
+// Host side model of the factory, the pairs, wcspr and the router for trying out strategies
+// without deploying. Prices come from the library's math, as linked into the router, and
+// scenarios of router calls are reported step by step with every reserve and balance.
+pub mod error;
+mod pair;
+pub mod report;
+mod router;
+pub mod scenario;
+mod simulator;
+
+pub use error::{PairError, SimError};
+pub use pair::MINIMUM_LIQUIDITY;
+pub use report::{Ledger, Report, StepReport};
+pub use scenario::{Names, Scenario, Step};
+pub use simulator::Simulator;
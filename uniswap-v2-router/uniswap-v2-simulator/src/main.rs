@@ -0,0 +1,31 @@
+use std::{env, fs, process};
+use uniswap_v2_simulator::{Report, Scenario, SimError};
+
+// Runs the scenario of a json file and prints its report.
+fn main() {
+    let file: String = match env::args().nth(1) {
+        Some(file) => file,
+        None => {
+            eprintln!("usage: uniswap-v2-simulator <scenario.json>");
+            process::exit(2);
+        }
+    };
+    match run(&file) {
+        Ok(report) => println!(
+            "{}",
+            serde_json::to_string_pretty(&report.to_json()).expect("json values serialize")
+        ),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+fn run(file: &str) -> Result<Report, SimError> {
+    let contents: String = fs::read_to_string(file)
+        .map_err(|error| SimError::InvalidScenario(format!("{}: {}", file, error)))?;
+    let json: serde_json::Value = serde_json::from_str(&contents)
+        .map_err(|error| SimError::InvalidScenario(error.to_string()))?;
+    Scenario::from_json(&json)?.run()
+}
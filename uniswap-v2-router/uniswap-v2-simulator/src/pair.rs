@@ -0,0 +1,187 @@
+use crate::{
+    error::{PairError, SimError},
+    simulator::Pair,
+    Simulator,
+};
+use common::{
+    errors::Errors,
+    functions::{u256_to_u512, u512_to_u256, zero_address},
+    Key, U128, U256, U512,
+};
+
+// Liquidity locked to the zero address by a pair's first mint.
+pub const MINIMUM_LIQUIDITY: u64 = 1000;
+
+// The factory and the pairs, following UniswapV2Factory and UniswapV2Pair with the protocol fee
+// off, as when the factory's `fee_to` is not set.
+impl Simulator {
+    pub fn get_pair(&self, token_a: Key, token_b: Key) -> Option<Key> {
+        let (token0, token1): (Key, Key) = sort_tokens(token_a, token_b).ok()?;
+        self.factory_pairs.get(&(token0, token1)).copied()
+    }
+
+    // Reserves of the pair ordered as (token0, token1).
+    pub fn get_reserves(&self, pair: Key) -> Option<(U256, U256)> {
+        self.pairs
+            .get(&pair)
+            .map(|pair| (pair.reserve0, pair.reserve1))
+    }
+
+    // The factory's create_pair, which initializes the pair contract passed in for the tokens.
+    pub(crate) fn create_pair(
+        &mut self,
+        token_a: Key,
+        token_b: Key,
+        pair: Key,
+    ) -> Result<(), SimError> {
+        let (token0, token1): (Key, Key) = sort_tokens(token_a, token_b)?;
+        if self.factory_pairs.contains_key(&(token0, token1)) || self.pairs.contains_key(&pair) {
+            return Err(SimError::PairExists);
+        }
+        self.factory_pairs.insert((token0, token1), pair);
+        self.pairs.insert(
+            pair,
+            Pair {
+                token0,
+                token1,
+                reserve0: U256::zero(),
+                reserve1: U256::zero(),
+            },
+        );
+        Ok(())
+    }
+
+    fn pair(&self, pair: Key) -> Result<Pair, SimError> {
+        self.pairs
+            .get(&pair)
+            .cloned()
+            .ok_or(SimError::Router(Errors::PairNotFound))
+    }
+
+    // Mints liquidity for the tokens sent to the pair since its last sync.
+    pub(crate) fn pair_mint(&mut self, pair: Key, to: Key) -> Result<U256, SimError> {
+        let state: Pair = self.pair(pair)?;
+        let balance0: U256 = self.balance_of(state.token0, pair);
+        let balance1: U256 = self.balance_of(state.token1, pair);
+        let amount0: U512 = u256_to_u512(balance0 - state.reserve0);
+        let amount1: U512 = u256_to_u512(balance1 - state.reserve1);
+        let total_supply: U512 = u256_to_u512(self.total_supply(pair));
+        let liquidity: U512 = if total_supply.is_zero() {
+            let liquidity: U512 = (amount0 * amount1)
+                .integer_sqrt()
+                .checked_sub(MINIMUM_LIQUIDITY.into())
+                .ok_or(PairError::InsufficientLiquidityMinted)?;
+            self.mint(pair, zero_address(), MINIMUM_LIQUIDITY.into());
+            liquidity
+        } else {
+            (amount0 * total_supply / u256_to_u512(state.reserve0))
+                .min(amount1 * total_supply / u256_to_u512(state.reserve1))
+        };
+        if liquidity.is_zero() {
+            return Err(PairError::InsufficientLiquidityMinted.into());
+        }
+        let liquidity: U256 = to_u256(liquidity)?;
+        self.mint(pair, to, liquidity);
+        self.update(pair, balance0, balance1)?;
+        Ok(liquidity)
+    }
+
+    // Burns the liquidity sent to the pair, paying out its share of both tokens to `to`.
+    pub(crate) fn pair_burn(&mut self, pair: Key, to: Key) -> Result<(U256, U256), SimError> {
+        let state: Pair = self.pair(pair)?;
+        let balance0: U512 = u256_to_u512(self.balance_of(state.token0, pair));
+        let balance1: U512 = u256_to_u512(self.balance_of(state.token1, pair));
+        let liquidity: U256 = self.balance_of(pair, pair);
+        let total_supply: U512 = u256_to_u512(self.total_supply(pair));
+        if total_supply.is_zero() {
+            return Err(PairError::InsufficientLiquidityBurned.into());
+        }
+        let amount0: U256 = to_u256(u256_to_u512(liquidity) * balance0 / total_supply)?;
+        let amount1: U256 = to_u256(u256_to_u512(liquidity) * balance1 / total_supply)?;
+        if amount0.is_zero() || amount1.is_zero() {
+            return Err(PairError::InsufficientLiquidityBurned.into());
+        }
+        self.burn(pair, pair, liquidity)?;
+        self.transfer(state.token0, pair, to, amount0)?;
+        self.transfer(state.token1, pair, to, amount1)?;
+        let balance0: U256 = self.balance_of(state.token0, pair);
+        let balance1: U256 = self.balance_of(state.token1, pair);
+        self.update(pair, balance0, balance1)?;
+        Ok((amount0, amount1))
+    }
+
+    // Sends the amounts out to `to` and checks that the tokens sent in keep k after the fee.
+    pub(crate) fn pair_swap(
+        &mut self,
+        pair: Key,
+        amount0_out: U256,
+        amount1_out: U256,
+        to: Key,
+    ) -> Result<(), SimError> {
+        let state: Pair = self.pair(pair)?;
+        if amount0_out.is_zero() && amount1_out.is_zero() {
+            return Err(PairError::InsufficientOutputAmount.into());
+        }
+        if amount0_out >= state.reserve0 || amount1_out >= state.reserve1 {
+            return Err(PairError::InsufficientLiquidity.into());
+        }
+        if to == state.token0 || to == state.token1 {
+            return Err(PairError::InvalidTo.into());
+        }
+        if !amount0_out.is_zero() {
+            self.transfer(state.token0, pair, to, amount0_out)?;
+        }
+        if !amount1_out.is_zero() {
+            self.transfer(state.token1, pair, to, amount1_out)?;
+        }
+        let balance0: U256 = self.balance_of(state.token0, pair);
+        let balance1: U256 = self.balance_of(state.token1, pair);
+        let amount0_in: U256 = balance0.saturating_sub(state.reserve0 - amount0_out);
+        let amount1_in: U256 = balance1.saturating_sub(state.reserve1 - amount1_out);
+        if amount0_in.is_zero() && amount1_in.is_zero() {
+            return Err(PairError::InsufficientInputAmount.into());
+        }
+        let balance0_adjusted: U512 = u256_to_u512(balance0) * 1000 - u256_to_u512(amount0_in) * 3;
+        let balance1_adjusted: U512 = u256_to_u512(balance1) * 1000 - u256_to_u512(amount1_in) * 3;
+        if balance0_adjusted * balance1_adjusted
+            < u256_to_u512(state.reserve0) * u256_to_u512(state.reserve1) * 1_000_000
+        {
+            return Err(PairError::K.into());
+        }
+        self.update(pair, balance0, balance1)
+    }
+
+    // Syncs the reserves to the balances, which must fit the pair's U128 reserves.
+    fn update(&mut self, pair: Key, balance0: U256, balance1: U256) -> Result<(), SimError> {
+        let max: U256 = U256::from(U128::MAX.as_u128());
+        if balance0 > max || balance1 > max {
+            return Err(PairError::Overflow.into());
+        }
+        let state: &mut Pair = self
+            .pairs
+            .get_mut(&pair)
+            .ok_or(SimError::Router(Errors::PairNotFound))?;
+        state.reserve0 = balance0;
+        state.reserve1 = balance1;
+        Ok(())
+    }
+}
+
+// The router's and the library's token order.
+pub(crate) fn sort_tokens(token_a: Key, token_b: Key) -> Result<(Key, Key), SimError> {
+    if token_a == token_b {
+        return Err(Errors::IdenticalAddresses.into());
+    }
+    Ok(if token_a < token_b {
+        (token_a, token_b)
+    } else {
+        (token_b, token_a)
+    })
+}
+
+fn to_u256(amount: U512) -> Result<U256, SimError> {
+    if amount > u256_to_u512(U256::MAX) {
+        return Err(PairError::Overflow.into());
+    }
+    Ok(u512_to_u256(amount))
+}
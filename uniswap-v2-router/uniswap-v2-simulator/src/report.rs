@@ -0,0 +1,120 @@
+use crate::{
+    error::SimError,
+    scenario::{Names, Scenario, Step},
+};
+use common::{Key, U256};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+// State a report reads after every step, from the simulator or from a deployed router.
+pub trait Ledger {
+    fn balance_of(&self, token: Key, owner: Key) -> U256;
+
+    // Reserves of the pair ordered as (token_a, token_b).
+    fn reserves(&self, pair: Key, token_a: Key, token_b: Key) -> (U256, U256);
+
+    // Cspr of an account, where it is not blurred by the gas of deploys.
+    fn cspr_of(&self, _owner: Key) -> Option<U256> {
+        None
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    pub steps: Vec<StepReport>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StepReport {
+    pub caller: String,
+    pub call: String,
+    // amounts the call returns, or why it reverted
+    pub outcome: Result<Vec<U256>, String>,
+    // reserves of every pair by pair and token name
+    pub reserves: BTreeMap<String, BTreeMap<String, U256>>,
+    // non zero balances of every account by account and token name, liquidity tokens included
+    pub balances: BTreeMap<String, BTreeMap<String, U256>>,
+    pub cspr: BTreeMap<String, U256>,
+}
+
+impl StepReport {
+    pub fn read(
+        scenario: &Scenario,
+        names: &Names,
+        step: &Step,
+        outcome: Result<Vec<U256>, String>,
+        ledger: &impl Ledger,
+    ) -> Result<StepReport, SimError> {
+        let mut reserves: BTreeMap<String, BTreeMap<String, U256>> = BTreeMap::new();
+        for ((token_a, token_b), pair) in scenario.pairs.iter().zip(scenario.pair_names()) {
+            let (reserve_a, reserve_b): (U256, U256) =
+                ledger.reserves(names.key(&pair)?, names.key(token_a)?, names.key(token_b)?);
+            let pair_reserves: &mut BTreeMap<String, U256> = reserves.entry(pair).or_default();
+            pair_reserves.insert(token_a.clone(), reserve_a);
+            pair_reserves.insert(token_b.clone(), reserve_b);
+        }
+        let mut balances: BTreeMap<String, BTreeMap<String, U256>> = BTreeMap::new();
+        let mut cspr: BTreeMap<String, U256> = BTreeMap::new();
+        for account in &scenario.accounts {
+            let owner: Key = names.key(account)?;
+            let account_balances: &mut BTreeMap<String, U256> =
+                balances.entry(account.clone()).or_default();
+            for token in scenario.token_names() {
+                let balance: U256 = ledger.balance_of(names.key(&token)?, owner);
+                if !balance.is_zero() {
+                    account_balances.insert(token, balance);
+                }
+            }
+            if let Some(amount) = ledger.cspr_of(owner) {
+                cspr.insert(account.clone(), amount);
+            }
+        }
+        Ok(StepReport {
+            caller: step.caller.clone(),
+            call: step.call.clone(),
+            outcome,
+            reserves,
+            balances,
+            cspr,
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        let outcome: Value = match &self.outcome {
+            Ok(amounts) => json!({ "ok": amounts.iter().map(U256::to_string).collect::<Vec<_>>() }),
+            Err(error) => json!({ "error": error }),
+        };
+        json!({
+            "caller": self.caller,
+            "call": self.call,
+            "outcome": outcome,
+            "reserves": nested_amounts(&self.reserves),
+            "balances": nested_amounts(&self.balances),
+            "cspr": amounts(&self.cspr),
+        })
+    }
+}
+
+impl Report {
+    pub fn to_json(&self) -> Value {
+        json!({ "steps": self.steps.iter().map(StepReport::to_json).collect::<Vec<_>>() })
+    }
+}
+
+fn amounts(amounts: &BTreeMap<String, U256>) -> Value {
+    Value::Object(
+        amounts
+            .iter()
+            .map(|(name, amount)| (name.clone(), Value::String(amount.to_string())))
+            .collect::<Map<String, Value>>(),
+    )
+}
+
+fn nested_amounts(amounts_by_name: &BTreeMap<String, BTreeMap<String, U256>>) -> Value {
+    Value::Object(
+        amounts_by_name
+            .iter()
+            .map(|(name, by_name)| (name.clone(), amounts(by_name)))
+            .collect::<Map<String, Value>>(),
+    )
+}
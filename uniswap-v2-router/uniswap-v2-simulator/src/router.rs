@@ -0,0 +1,418 @@
+use crate::{error::SimError, pair::sort_tokens, Simulator};
+use common::{
+    errors::{Errors, Side},
+    Key, U256,
+};
+use uniswap_v2_library_crate::math;
+use uniswap_v2_router_sdk::RouterCall;
+
+// The router's liquidity and swap entry points, with the math the router links from the library.
+// Deadlines are not checked as the simulator has no block time, and callers are taken to be
+// whitelisted and to have approved the router for what it transfers.
+impl Simulator {
+    // Runs a call of `caller`, returning the amounts the session code router records for it:
+    // `amount_a`, `amount_b` and `liquidity` of liquidity calls, the amounts along the path of
+    // swaps.
+    pub fn call(&mut self, caller: Key, call: &RouterCall) -> Result<Vec<U256>, SimError> {
+        self.execute(|sim| match call.clone() {
+            RouterCall::AddLiquidity {
+                token_a,
+                token_b,
+                amount_a_desired,
+                amount_b_desired,
+                amount_a_min,
+                amount_b_min,
+                to,
+                pair,
+                ..
+            } => {
+                if amount_a_desired.is_zero() {
+                    return Err(Errors::AmountADesiredIsZero.into());
+                }
+                if amount_b_desired.is_zero() {
+                    return Err(Errors::AmountBDesiredIsZero.into());
+                }
+                let (amount_a, amount_b): (U256, U256) = sim.add_liquidity_amounts(
+                    token_a,
+                    token_b,
+                    (amount_a_desired, amount_b_desired),
+                    (amount_a_min, amount_b_min),
+                    pair,
+                )?;
+                let pair: Key = sim.pairs_for(&[token_a, token_b])?[0];
+                sim.transfer(token_a, caller, pair, amount_a)?;
+                sim.transfer(token_b, caller, pair, amount_b)?;
+                let liquidity: U256 = sim.pair_mint(pair, to)?;
+                Ok(vec![amount_a, amount_b, liquidity])
+            }
+            RouterCall::AddLiquidityCspr {
+                token,
+                amount_token_desired,
+                amount_cspr_desired,
+                amount_token_min,
+                amount_cspr_min,
+                to,
+                pair,
+                ..
+            } => {
+                let wcspr: Key = sim.wcspr;
+                let (amount_token, amount_cspr): (U256, U256) = sim.add_liquidity_amounts(
+                    token,
+                    wcspr,
+                    (amount_token_desired, amount_cspr_desired),
+                    (amount_token_min, amount_cspr_min),
+                    pair,
+                )?;
+                let pair: Key = sim.pairs_for(&[token, wcspr])?[0];
+                if amount_token.is_zero() {
+                    return Err(Errors::AmountTokenIsZero.into());
+                }
+                sim.transfer(token, caller, pair, amount_token)?;
+                sim.deposit(caller, sim.router, amount_cspr)?;
+                sim.transfer(wcspr, sim.router, pair, amount_cspr)?;
+                let liquidity: U256 = sim.pair_mint(pair, to)?;
+                Ok(vec![amount_token, amount_cspr, liquidity])
+            }
+            RouterCall::RemoveLiquidity {
+                token_a,
+                token_b,
+                liquidity,
+                amount_a_min,
+                amount_b_min,
+                to,
+                ..
+            } => {
+                let (amount_a, amount_b): (U256, U256) = sim.remove_liquidity(
+                    caller,
+                    (token_a, token_b),
+                    liquidity,
+                    (amount_a_min, amount_b_min),
+                    to,
+                )?;
+                Ok(vec![amount_a, amount_b])
+            }
+            RouterCall::RemoveLiquidityCspr {
+                token,
+                liquidity,
+                amount_token_min,
+                amount_cspr_min,
+                to,
+                ..
+            } => {
+                let router: Key = sim.router;
+                let (amount_token, amount_cspr): (U256, U256) = sim.remove_liquidity(
+                    caller,
+                    (token, sim.wcspr),
+                    liquidity,
+                    (amount_token_min, amount_cspr_min),
+                    router,
+                )?;
+                sim.transfer(token, router, to, amount_token)?;
+                sim.withdraw(router, to, amount_cspr)?;
+                Ok(vec![amount_token, amount_cspr])
+            }
+            RouterCall::SwapExactTokensForTokens {
+                amount_in,
+                amount_out_min,
+                path,
+                to,
+                ..
+            } => sim.swap(
+                caller,
+                Swap::ExactIn(amount_in, amount_out_min),
+                path.tokens(),
+                to,
+                false,
+                false,
+            ),
+            RouterCall::SwapTokensForExactTokens {
+                amount_out,
+                amount_in_max,
+                path,
+                to,
+                ..
+            } => sim.swap(
+                caller,
+                Swap::ExactOut(amount_out, amount_in_max),
+                path.tokens(),
+                to,
+                false,
+                false,
+            ),
+            RouterCall::SwapExactCsprForTokens {
+                amount_in,
+                amount_out_min,
+                path,
+                to,
+                ..
+            } => sim.swap(
+                caller,
+                Swap::ExactIn(amount_in, amount_out_min),
+                path.tokens(),
+                to,
+                true,
+                false,
+            ),
+            RouterCall::SwapCsprForExactTokens {
+                amount_out,
+                amount_in_max,
+                path,
+                to,
+                ..
+            } => sim.swap(
+                caller,
+                Swap::ExactOut(amount_out, amount_in_max),
+                path.tokens(),
+                to,
+                true,
+                false,
+            ),
+            RouterCall::SwapExactTokensForCspr {
+                amount_in,
+                amount_out_min,
+                path,
+                to,
+                ..
+            } => sim.swap(
+                caller,
+                Swap::ExactIn(amount_in, amount_out_min),
+                path.tokens(),
+                to,
+                false,
+                true,
+            ),
+            RouterCall::SwapTokensForExactCspr {
+                amount_out,
+                amount_in_max,
+                path,
+                to,
+                ..
+            } => sim.swap(
+                caller,
+                Swap::ExactOut(amount_out, amount_in_max),
+                path.tokens(),
+                to,
+                false,
+                true,
+            ),
+            RouterCall::GetAmountsOut { amount_in, path } => {
+                sim.amounts_out(amount_in, path.tokens())
+            }
+            RouterCall::GetAmountsIn { amount_out, path } => {
+                sim.amounts_in(amount_out, path.tokens())
+            }
+            call => Err(SimError::Unsupported(call.entry_point())),
+        })
+    }
+
+    // Chained get_amount_out over the pairs of the path.
+    pub fn amounts_out(&self, amount_in: U256, path: &[Key]) -> Result<Vec<U256>, SimError> {
+        let pairs: Vec<Key> = self.pairs_for(path)?;
+        self.amounts_out_along(amount_in, path, &pairs)
+    }
+
+    // Chained get_amount_in over the pairs of the path, from the last hop back.
+    pub fn amounts_in(&self, amount_out: U256, path: &[Key]) -> Result<Vec<U256>, SimError> {
+        let pairs: Vec<Key> = self.pairs_for(path)?;
+        self.amounts_in_along(amount_out, path, &pairs)
+    }
+
+    // `_add_liquidity`: initializes the pair passed in if the factory has none for the tokens, then
+    // takes the desired amounts of an empty pair, or the most of them at the pair's ratio.
+    fn add_liquidity_amounts(
+        &mut self,
+        token_a: Key,
+        token_b: Key,
+        (amount_a_desired, amount_b_desired): (U256, U256),
+        (amount_a_min, amount_b_min): (U256, U256),
+        pair_received: Option<Key>,
+    ) -> Result<(U256, U256), SimError> {
+        if self.get_pair(token_a, token_b).is_none() {
+            let pair: Key = pair_received.ok_or(SimError::Router(Errors::PairNotFound))?;
+            self.create_pair(token_a, token_b, pair)?;
+        }
+        let pair: Key = self.pairs_for(&[token_a, token_b])?[0];
+        let (reserve_a, reserve_b): (U256, U256) = self.reserves(pair, token_a, token_b)?;
+        if reserve_a.is_zero() && reserve_b.is_zero() {
+            return Ok((amount_a_desired, amount_b_desired));
+        }
+        let amount_b_optimal: U256 = math::try_quote(amount_a_desired, reserve_a, reserve_b)?;
+        if amount_b_optimal <= amount_b_desired && amount_b_optimal >= amount_b_min {
+            return Ok((amount_a_desired, amount_b_optimal));
+        }
+        let amount_a_optimal: U256 = math::try_quote(amount_b_desired, reserve_b, reserve_a)?;
+        if amount_a_optimal > amount_a_desired {
+            return Err(Errors::OptimalAmountExceedsDesired.into());
+        }
+        // the router goes on with nothing to add, which the pair's mint then rejects
+        if amount_a_optimal >= amount_a_min {
+            Ok((amount_a_optimal, amount_b_desired))
+        } else {
+            Ok((U256::zero(), U256::zero()))
+        }
+    }
+
+    fn remove_liquidity(
+        &mut self,
+        caller: Key,
+        (token_a, token_b): (Key, Key),
+        liquidity: U256,
+        (amount_a_min, amount_b_min): (U256, U256),
+        to: Key,
+    ) -> Result<(U256, U256), SimError> {
+        let pair: Key = self.pairs_for(&[token_a, token_b])?[0];
+        self.transfer(pair, caller, pair, liquidity)?;
+        let (amount0, amount1): (U256, U256) = self.pair_burn(pair, to)?;
+        let (token0, _): (Key, Key) = sort_tokens(token_a, token_b)?;
+        let (amount_a, amount_b): (U256, U256) = if token_a == token0 {
+            (amount0, amount1)
+        } else {
+            (amount1, amount0)
+        };
+        if amount_a < amount_a_min || amount_b < amount_b_min {
+            return Err(Errors::SlippageExceeded { side: Side::Output }.into());
+        }
+        Ok((amount_a, amount_b))
+    }
+
+    // Swaps along the path, paying in cspr wrapped by the router when `cspr_in` and paying out
+    // cspr unwrapped by the router when `cspr_out`.
+    fn swap(
+        &mut self,
+        caller: Key,
+        swap: Swap,
+        path: &[Key],
+        to: Key,
+        cspr_in: bool,
+        cspr_out: bool,
+    ) -> Result<Vec<U256>, SimError> {
+        if cspr_in && path.first() != Some(&self.wcspr) {
+            return Err(Errors::PathMustStartWithWcspr.into());
+        }
+        if cspr_out && path.last() != Some(&self.wcspr) {
+            return Err(Errors::PathMustEndWithWcspr.into());
+        }
+        let pairs: Vec<Key> = self.pairs_for(path)?;
+        let amounts: Vec<U256> = match swap {
+            Swap::ExactIn(amount_in, amount_out_min) => {
+                let amounts: Vec<U256> = self.amounts_out_along(amount_in, path, &pairs)?;
+                if amounts[amounts.len() - 1] < amount_out_min {
+                    return Err(Errors::SlippageExceeded { side: Side::Output }.into());
+                }
+                amounts
+            }
+            Swap::ExactOut(amount_out, amount_in_max) => {
+                let amounts: Vec<U256> = self.amounts_in_along(amount_out, path, &pairs)?;
+                if amounts[0] > amount_in_max {
+                    return Err(Errors::SlippageExceeded { side: Side::Input }.into());
+                }
+                amounts
+            }
+        };
+        if cspr_in {
+            self.deposit(caller, self.router, amounts[0])?;
+            self.transfer(self.wcspr, self.router, pairs[0], amounts[0])?;
+        } else {
+            self.transfer(path[0], caller, pairs[0], amounts[0])?;
+        }
+        let receiver: Key = if cspr_out { self.router } else { to };
+        self.swap_along(&amounts, path, &pairs, receiver)?;
+        if cspr_out {
+            self.withdraw(self.router, to, amounts[amounts.len() - 1])?;
+        }
+        Ok(amounts)
+    }
+
+    // `_swap`: the output of intermediate hops goes straight to the next pair.
+    fn swap_along(
+        &mut self,
+        amounts: &[U256],
+        path: &[Key],
+        pairs: &[Key],
+        to: Key,
+    ) -> Result<(), SimError> {
+        for i in 0..(path.len() - 1) {
+            let (token0, _): (Key, Key) = sort_tokens(path[i], path[i + 1])?;
+            let amount_out: U256 = amounts[i + 1];
+            let (amount0_out, amount1_out): (U256, U256) = if path[i] == token0 {
+                (U256::zero(), amount_out)
+            } else {
+                (amount_out, U256::zero())
+            };
+            let to: Key = if i < path.len() - 2 { pairs[i + 1] } else { to };
+            self.pair_swap(pairs[i], amount0_out, amount1_out, to)?;
+        }
+        Ok(())
+    }
+
+    fn pairs_for(&self, path: &[Key]) -> Result<Vec<Key>, SimError> {
+        path.windows(2)
+            .map(|hop| {
+                sort_tokens(hop[0], hop[1])?;
+                self.get_pair(hop[0], hop[1])
+                    .ok_or(SimError::Router(Errors::PairNotFound))
+            })
+            .collect()
+    }
+
+    fn amounts_out_along(
+        &self,
+        amount_in: U256,
+        path: &[Key],
+        pairs: &[Key],
+    ) -> Result<Vec<U256>, SimError> {
+        if path.len() < 2 {
+            return Err(Errors::InvalidPath.into());
+        }
+        let mut amounts: Vec<U256> = vec![amount_in];
+        for i in 0..(path.len() - 1) {
+            let (reserve_in, reserve_out): (U256, U256) =
+                self.reserves(pairs[i], path[i], path[i + 1])?;
+            amounts.push(math::try_get_amount_out(
+                amounts[i],
+                reserve_in,
+                reserve_out,
+            )?);
+        }
+        Ok(amounts)
+    }
+
+    fn amounts_in_along(
+        &self,
+        amount_out: U256,
+        path: &[Key],
+        pairs: &[Key],
+    ) -> Result<Vec<U256>, SimError> {
+        if path.len() < 2 {
+            return Err(Errors::InvalidPath.into());
+        }
+        let mut amounts: Vec<U256> = vec![U256::zero(); path.len()];
+        amounts[path.len() - 1] = amount_out;
+        for i in (1..path.len()).rev() {
+            let (reserve_in, reserve_out): (U256, U256) =
+                self.reserves(pairs[i - 1], path[i - 1], path[i])?;
+            amounts[i - 1] = math::try_get_amount_in(amounts[i], reserve_in, reserve_out)?;
+        }
+        Ok(amounts)
+    }
+
+    // Reserves of the pair ordered as (token_a, token_b).
+    fn reserves(&self, pair: Key, token_a: Key, token_b: Key) -> Result<(U256, U256), SimError> {
+        let (reserve0, reserve1): (U256, U256) = self
+            .get_reserves(pair)
+            .ok_or(SimError::Router(Errors::PairNotFound))?;
+        let (token0, _): (Key, Key) = sort_tokens(token_a, token_b)?;
+        Ok(if token_a == token0 {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        })
+    }
+}
+
+enum Swap {
+    // amount in and the least amount out
+    ExactIn(U256, U256),
+    // amount out and the most amount in
+    ExactOut(U256, U256),
+}
@@ -0,0 +1,395 @@
+use crate::{
+    error::SimError,
+    report::{Ledger, Report, StepReport},
+    Simulator,
+};
+use common::{account::AccountHash, Key, U256};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use uniswap_v2_router_sdk::{Deadline, Path, RouterCall};
+
+// Names the scenario gives wcspr and the router, which can't be used for tokens or accounts.
+pub const WCSPR: &str = "WCSPR";
+pub const ROUTER: &str = "ROUTER";
+
+// The simulator does not check deadlines, runs against a deployed router must not time out.
+const NO_DEADLINE: Deadline = Deadline::At(u64::MAX);
+
+// Router calls of named accounts on named tokens, with the balances the accounts start with:
+//
+// {
+//   "accounts": ["alice"],
+//   "tokens": ["A", "B"],
+//   "pairs": [["A", "B"], ["A", "WCSPR"]],
+//   "balances": {"alice": {"A": "1000000", "B": "1000000"}},
+//   "cspr": {"alice": "1000000"},
+//   "steps": [{"caller": "alice", "call": "add_liquidity", "token_a": "A", "token_b": "B",
+//              "amount_a_desired": "10000", "amount_b_desired": "20000"}]
+// }
+//
+// Steps take the args of the router entry point they call, with `path` as a list of token names.
+// Minimums default to 0 and `to` to the caller. The pair of two tokens is named `A/B` after its
+// declaration and passed to every add_liquidity of the tokens.
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    pub accounts: Vec<String>,
+    pub tokens: Vec<String>,
+    pub pairs: Vec<(String, String)>,
+    pub balances: BTreeMap<String, BTreeMap<String, U256>>,
+    pub cspr: BTreeMap<String, U256>,
+    pub steps: Vec<Step>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub caller: String,
+    pub call: String,
+    args: Map<String, Value>,
+}
+
+// Keys of the names of a scenario.
+#[derive(Clone, Debug, Default)]
+pub struct Names {
+    keys: BTreeMap<String, Key>,
+}
+
+impl Names {
+    pub fn new() -> Names {
+        Names::default()
+    }
+
+    pub fn insert(&mut self, name: &str, key: Key) {
+        self.keys.insert(name.into(), key);
+    }
+
+    pub fn key(&self, name: &str) -> Result<Key, SimError> {
+        self.keys
+            .get(name)
+            .copied()
+            .ok_or_else(|| invalid(format!("unknown name `{}`", name)))
+    }
+
+    // Distinct keys for every name, for runs of the simulator alone.
+    pub fn generated(scenario: &Scenario) -> Names {
+        let key = |kind: u8, index: usize| -> [u8; 32] {
+            let mut key: [u8; 32] = [kind; 32];
+            key[1..9].copy_from_slice(&(index as u64).to_le_bytes());
+            key
+        };
+        let mut names: Names = Names::new();
+        names.insert(WCSPR, Key::Hash(key(1, 0)));
+        names.insert(ROUTER, Key::Hash(key(2, 0)));
+        for (index, account) in scenario.accounts.iter().enumerate() {
+            names.insert(account, Key::Account(AccountHash::new(key(3, index))));
+        }
+        for (index, token) in scenario.tokens.iter().enumerate() {
+            names.insert(token, Key::Hash(key(4, index)));
+        }
+        for (index, pair) in scenario.pair_names().iter().enumerate() {
+            names.insert(pair, Key::Hash(key(5, index)));
+        }
+        names
+    }
+}
+
+impl Scenario {
+    pub fn from_json(json: &Value) -> Result<Scenario, SimError> {
+        let (empty_list, empty_object): (Value, Value) = (json!([]), json!({}));
+        let scenario: Scenario = Scenario {
+            accounts: name_list(&json["accounts"], "accounts")?,
+            tokens: name_list(&json["tokens"], "tokens")?,
+            pairs: list(or_empty(&json["pairs"], &empty_list), "pairs")?
+                .iter()
+                .map(|pair| match name_list(pair, "pairs")?.as_slice() {
+                    [token_a, token_b] => Ok((token_a.clone(), token_b.clone())),
+                    _ => Err(invalid("a pair is not two tokens".into())),
+                })
+                .collect::<Result<_, SimError>>()?,
+            balances: object(or_empty(&json["balances"], &empty_object), "balances")?
+                .iter()
+                .map(|(account, balances)| {
+                    let balances: BTreeMap<String, U256> = object(balances, account)?
+                        .iter()
+                        .map(|(token, amount)| Ok((token.clone(), amount_of(amount, token)?)))
+                        .collect::<Result<_, SimError>>()?;
+                    Ok((account.clone(), balances))
+                })
+                .collect::<Result<_, SimError>>()?,
+            cspr: object(or_empty(&json["cspr"], &empty_object), "cspr")?
+                .iter()
+                .map(|(account, amount)| Ok((account.clone(), amount_of(amount, account)?)))
+                .collect::<Result<_, SimError>>()?,
+            steps: list(&json["steps"], "steps")?
+                .iter()
+                .map(Step::from_json)
+                .collect::<Result<_, SimError>>()?,
+        };
+        for name in scenario.accounts.iter().chain(&scenario.tokens) {
+            if name == WCSPR || name == ROUTER {
+                return Err(invalid(format!("`{}` is a reserved name", name)));
+            }
+        }
+        // every name must resolve before anything runs
+        scenario.calls(&Names::generated(&scenario))?;
+        Ok(scenario)
+    }
+
+    // `A/B` for every declared pair of `A` and `B`.
+    pub fn pair_names(&self) -> Vec<String> {
+        self.pairs
+            .iter()
+            .map(|(token_a, token_b)| format!("{}/{}", token_a, token_b))
+            .collect()
+    }
+
+    // Every token a report lists the balances of, wcspr and the pairs' liquidity tokens included.
+    pub fn token_names(&self) -> Vec<String> {
+        let mut tokens: Vec<String> = self.tokens.clone();
+        tokens.push(WCSPR.into());
+        tokens.extend(self.pair_names());
+        tokens
+    }
+
+    // Callers and router calls of the steps.
+    pub fn calls(&self, names: &Names) -> Result<Vec<(Key, RouterCall)>, SimError> {
+        self.steps
+            .iter()
+            .map(|step| Ok((names.key(&step.caller)?, step.router_call(self, names)?)))
+            .collect()
+    }
+
+    pub fn run(&self) -> Result<Report, SimError> {
+        self.run_with(&Names::generated(self))
+    }
+
+    pub fn run_with(&self, names: &Names) -> Result<Report, SimError> {
+        let mut simulator: Simulator = Simulator::new(names.key(WCSPR)?, names.key(ROUTER)?);
+        for (account, balances) in &self.balances {
+            for (token, amount) in balances {
+                simulator.mint(names.key(token)?, names.key(account)?, *amount);
+            }
+        }
+        for (account, amount) in &self.cspr {
+            simulator.set_cspr(names.key(account)?, *amount);
+        }
+        let mut report: Report = Report::default();
+        for (step, (caller, call)) in self.steps.iter().zip(self.calls(names)?) {
+            let outcome: Result<Vec<U256>, String> = simulator
+                .call(caller, &call)
+                .map_err(|error| error.to_string());
+            report
+                .steps
+                .push(StepReport::read(self, names, step, outcome, &simulator)?);
+        }
+        Ok(report)
+    }
+
+    fn pair_of(&self, names: &Names, token_a: &str, token_b: &str) -> Option<Key> {
+        self.pairs
+            .iter()
+            .position(|(a, b)| (a == token_a && b == token_b) || (a == token_b && b == token_a))
+            .and_then(|index| names.key(&self.pair_names()[index]).ok())
+    }
+}
+
+impl Ledger for Simulator {
+    fn balance_of(&self, token: Key, owner: Key) -> U256 {
+        Simulator::balance_of(self, token, owner)
+    }
+
+    fn reserves(&self, pair: Key, token_a: Key, token_b: Key) -> (U256, U256) {
+        let (reserve0, reserve1): (U256, U256) = self.get_reserves(pair).unwrap_or_default();
+        if token_a < token_b {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        }
+    }
+
+    fn cspr_of(&self, owner: Key) -> Option<U256> {
+        Some(Simulator::cspr_of(self, owner))
+    }
+}
+
+impl Step {
+    fn from_json(json: &Value) -> Result<Step, SimError> {
+        let mut args: Map<String, Value> = object(json, "step")?.clone();
+        let mut take = |field: &str| match args.remove(field) {
+            Some(Value::String(value)) => Ok(value),
+            _ => Err(invalid(format!("step without `{}`", field))),
+        };
+        Ok(Step {
+            caller: take("caller")?,
+            call: take("call")?,
+            args,
+        })
+    }
+
+    pub fn router_call(&self, scenario: &Scenario, names: &Names) -> Result<RouterCall, SimError> {
+        let to: Key = match self.args.get("to") {
+            Some(to) => names.key(to.as_str().unwrap_or_default())?,
+            None => names.key(&self.caller)?,
+        };
+        let call: RouterCall = match self.call.as_str() {
+            "add_liquidity" => RouterCall::AddLiquidity {
+                token_a: self.key(names, "token_a")?,
+                token_b: self.key(names, "token_b")?,
+                amount_a_desired: self.amount("amount_a_desired")?,
+                amount_b_desired: self.amount("amount_b_desired")?,
+                amount_a_min: self.min("amount_a_min")?,
+                amount_b_min: self.min("amount_b_min")?,
+                to,
+                deadline: NO_DEADLINE,
+                pair: scenario.pair_of(names, self.name("token_a")?, self.name("token_b")?),
+            },
+            "add_liquidity_cspr" => RouterCall::AddLiquidityCspr {
+                token: self.key(names, "token")?,
+                amount_token_desired: self.amount("amount_token_desired")?,
+                amount_cspr_desired: self.amount("amount_cspr_desired")?,
+                amount_token_min: self.min("amount_token_min")?,
+                amount_cspr_min: self.min("amount_cspr_min")?,
+                to,
+                deadline: NO_DEADLINE,
+                pair: scenario.pair_of(names, self.name("token")?, WCSPR),
+            },
+            "remove_liquidity" => RouterCall::RemoveLiquidity {
+                token_a: self.key(names, "token_a")?,
+                token_b: self.key(names, "token_b")?,
+                liquidity: self.amount("liquidity")?,
+                amount_a_min: self.min("amount_a_min")?,
+                amount_b_min: self.min("amount_b_min")?,
+                to,
+                deadline: NO_DEADLINE,
+            },
+            "remove_liquidity_cspr" => RouterCall::RemoveLiquidityCspr {
+                token: self.key(names, "token")?,
+                liquidity: self.amount("liquidity")?,
+                amount_token_min: self.min("amount_token_min")?,
+                amount_cspr_min: self.min("amount_cspr_min")?,
+                to,
+                deadline: NO_DEADLINE,
+            },
+            "swap_exact_tokens_for_tokens" => RouterCall::SwapExactTokensForTokens {
+                amount_in: self.amount("amount_in")?,
+                amount_out_min: self.min("amount_out_min")?,
+                path: self.path(names)?,
+                to,
+                deadline: NO_DEADLINE,
+            },
+            "swap_tokens_for_exact_tokens" => RouterCall::SwapTokensForExactTokens {
+                amount_out: self.amount("amount_out")?,
+                amount_in_max: self.amount("amount_in_max")?,
+                path: self.path(names)?,
+                to,
+                deadline: NO_DEADLINE,
+            },
+            "swap_exact_cspr_for_tokens" => RouterCall::SwapExactCsprForTokens {
+                amount_in: self.amount("amount_in")?,
+                amount_out_min: self.min("amount_out_min")?,
+                path: self.path(names)?,
+                to,
+                deadline: NO_DEADLINE,
+            },
+            "swap_cspr_for_exact_tokens" => RouterCall::SwapCsprForExactTokens {
+                amount_out: self.amount("amount_out")?,
+                amount_in_max: self.amount("amount_in_max")?,
+                path: self.path(names)?,
+                to,
+                deadline: NO_DEADLINE,
+            },
+            "swap_exact_tokens_for_cspr" => RouterCall::SwapExactTokensForCspr {
+                amount_in: self.amount("amount_in")?,
+                amount_out_min: self.min("amount_out_min")?,
+                path: self.path(names)?,
+                to,
+                deadline: NO_DEADLINE,
+            },
+            "swap_tokens_for_exact_cspr" => RouterCall::SwapTokensForExactCspr {
+                amount_out: self.amount("amount_out")?,
+                amount_in_max: self.amount("amount_in_max")?,
+                path: self.path(names)?,
+                to,
+                deadline: NO_DEADLINE,
+            },
+            call => return Err(invalid(format!("`{}` is not a simulated call", call))),
+        };
+        Ok(call)
+    }
+
+    fn name(&self, field: &str) -> Result<&str, SimError> {
+        self.args
+            .get(field)
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid(format!("`{}` of `{}` is not a name", field, self.call)))
+    }
+
+    fn key(&self, names: &Names, field: &str) -> Result<Key, SimError> {
+        names.key(self.name(field)?)
+    }
+
+    fn amount(&self, field: &str) -> Result<U256, SimError> {
+        match self.args.get(field) {
+            Some(amount) => amount_of(amount, field),
+            None => Err(invalid(format!("`{}` without `{}`", self.call, field))),
+        }
+    }
+
+    fn min(&self, field: &str) -> Result<U256, SimError> {
+        match self.args.get(field) {
+            Some(amount) => amount_of(amount, field),
+            None => Ok(U256::zero()),
+        }
+    }
+
+    fn path(&self, names: &Names) -> Result<Path, SimError> {
+        let tokens: Vec<Key> = name_list(self.args.get("path").unwrap_or(&Value::Null), "path")?
+            .iter()
+            .map(|token| names.key(token))
+            .collect::<Result<_, SimError>>()?;
+        Path::new(tokens).map_err(|error| invalid(error.to_string()))
+    }
+}
+
+// `pairs`, `balances` and `cspr` may be left out.
+fn or_empty<'a>(json: &'a Value, empty: &'a Value) -> &'a Value {
+    if json.is_null() {
+        empty
+    } else {
+        json
+    }
+}
+
+fn invalid(reason: String) -> SimError {
+    SimError::InvalidScenario(reason)
+}
+
+fn list<'a>(json: &'a Value, field: &str) -> Result<&'a Vec<Value>, SimError> {
+    json.as_array()
+        .ok_or_else(|| invalid(format!("`{}` is not a list", field)))
+}
+
+fn object<'a>(json: &'a Value, field: &str) -> Result<&'a Map<String, Value>, SimError> {
+    json.as_object()
+        .ok_or_else(|| invalid(format!("`{}` is not an object", field)))
+}
+
+fn name_list(json: &Value, field: &str) -> Result<Vec<String>, SimError> {
+    list(json, field)?
+        .iter()
+        .map(|name| {
+            name.as_str()
+                .map(String::from)
+                .ok_or_else(|| invalid(format!("`{}` has a name that is not a string", field)))
+        })
+        .collect()
+}
+
+// Amounts are decimal strings, or numbers when they fit a u64.
+fn amount_of(json: &Value, field: &str) -> Result<U256, SimError> {
+    let amount: Option<U256> = match json {
+        Value::String(amount) => U256::from_dec_str(amount).ok(),
+        Value::Number(amount) => amount.as_u64().map(U256::from),
+        _ => None,
+    };
+    amount.ok_or_else(|| invalid(format!("`{}` is not an amount", field)))
+}
@@ -0,0 +1,142 @@
+use crate::error::SimError;
+use common::{Key, U256};
+use std::collections::BTreeMap;
+
+// Token balances and supply, of the erc20 tokens, wcspr and the pairs' liquidity tokens alike.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Token {
+    pub balances: BTreeMap<Key, U256>,
+    pub total_supply: U256,
+}
+
+// Reserves a pair last synced to, in the order of its sorted tokens.
+#[derive(Clone, Debug)]
+pub(crate) struct Pair {
+    pub token0: Key,
+    pub token1: Key,
+    pub reserve0: U256,
+    pub reserve1: U256,
+}
+
+// State of the factory, the pairs, wcspr and the tokens the router moves. The router holds no
+// state of its own between calls, only its key is needed as the receiver of cspr swaps.
+#[derive(Clone, Debug)]
+pub struct Simulator {
+    pub(crate) wcspr: Key,
+    pub(crate) router: Key,
+    pub(crate) tokens: BTreeMap<Key, Token>,
+    pub(crate) cspr: BTreeMap<Key, U256>,
+    // pairs of the factory by their sorted tokens
+    pub(crate) factory_pairs: BTreeMap<(Key, Key), Key>,
+    pub(crate) pairs: BTreeMap<Key, Pair>,
+}
+
+impl Simulator {
+    pub fn new(wcspr: Key, router: Key) -> Simulator {
+        Simulator {
+            wcspr,
+            router,
+            tokens: BTreeMap::new(),
+            cspr: BTreeMap::new(),
+            factory_pairs: BTreeMap::new(),
+            pairs: BTreeMap::new(),
+        }
+    }
+
+    pub fn balance_of(&self, token: Key, owner: Key) -> U256 {
+        self.tokens
+            .get(&token)
+            .and_then(|token| token.balances.get(&owner))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn total_supply(&self, token: Key) -> U256 {
+        self.tokens
+            .get(&token)
+            .map(|token| token.total_supply)
+            .unwrap_or_default()
+    }
+
+    pub fn cspr_of(&self, owner: Key) -> U256 {
+        self.cspr.get(&owner).copied().unwrap_or_default()
+    }
+
+    pub fn set_cspr(&mut self, owner: Key, amount: U256) {
+        self.cspr.insert(owner, amount);
+    }
+
+    // Runs a call on a copy of the state that is kept only if the call succeeds, as a reverted
+    // deploy leaves no changes.
+    pub(crate) fn execute<T>(
+        &mut self,
+        call: impl FnOnce(&mut Simulator) -> Result<T, SimError>,
+    ) -> Result<T, SimError> {
+        let mut state: Simulator = self.clone();
+        let result: T = call(&mut state)?;
+        *self = state;
+        Ok(result)
+    }
+
+    pub fn mint(&mut self, token: Key, owner: Key, amount: U256) {
+        let token: &mut Token = self.tokens.entry(token).or_default();
+        *token.balances.entry(owner).or_default() += amount;
+        token.total_supply += amount;
+    }
+
+    pub(crate) fn burn(&mut self, token: Key, owner: Key, amount: U256) -> Result<(), SimError> {
+        self.debit(token, owner, amount)?;
+        let token: &mut Token = self.tokens.entry(token).or_default();
+        token.total_supply -= amount;
+        Ok(())
+    }
+
+    pub(crate) fn transfer(
+        &mut self,
+        token: Key,
+        from: Key,
+        to: Key,
+        amount: U256,
+    ) -> Result<(), SimError> {
+        self.debit(token, from, amount)?;
+        *self
+            .tokens
+            .entry(token)
+            .or_default()
+            .balances
+            .entry(to)
+            .or_default() += amount;
+        Ok(())
+    }
+
+    fn debit(&mut self, token: Key, owner: Key, amount: U256) -> Result<(), SimError> {
+        let balance: U256 = self.balance_of(token, owner);
+        let rest: U256 = balance
+            .checked_sub(amount)
+            .ok_or(SimError::InsufficientBalance { token, owner })?;
+        self.tokens
+            .entry(token)
+            .or_default()
+            .balances
+            .insert(owner, rest);
+        Ok(())
+    }
+
+    // wcspr's deposit: the payer's cspr is wrapped into wcspr of `to`.
+    pub(crate) fn deposit(&mut self, payer: Key, to: Key, amount: U256) -> Result<(), SimError> {
+        let rest: U256 = self
+            .cspr_of(payer)
+            .checked_sub(amount)
+            .ok_or(SimError::InsufficientCspr(payer))?;
+        self.cspr.insert(payer, rest);
+        self.mint(self.wcspr, to, amount);
+        Ok(())
+    }
+
+    // wcspr's withdraw: wcspr of `owner` is unwrapped into cspr of `to`.
+    pub(crate) fn withdraw(&mut self, owner: Key, to: Key, amount: U256) -> Result<(), SimError> {
+        self.burn(self.wcspr, owner, amount)?;
+        *self.cspr.entry(to).or_default() += amount;
+        Ok(())
+    }
+}